@@ -0,0 +1,19 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rlox::interpreter::interpreter::INTERPRETER_STACK_SIZE;
+use rlox::parser::parser::Parser;
+use rlox::scanner::scan::Scanner;
+
+fuzz_target!(|source: &str| {
+    let tokens = Scanner::new(source.to_string()).scan_tokens();
+    // deeply nested input needs the stack the interpreter runs with.
+    std::thread::Builder::new()
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(move || {
+            let _ = Parser::new(tokens).parse();
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rlox::interpreter::interpreter::{Interpreter, INTERPRETER_STACK_SIZE};
+use rlox::parser::parser::Parser;
+use rlox::resolver::resolver::Resolver;
+use rlox::scanner::scan::Scanner;
+
+fuzz_target!(|source: &str| {
+    let tokens = Scanner::new(source.to_string()).scan_tokens();
+    // deeply nested input needs the stack the interpreter runs with.
+    std::thread::Builder::new()
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(move || {
+            if let Ok(ast) = Parser::new(tokens).parse() {
+                let mut interpreter = Interpreter::new();
+                let _ = Resolver::new(&mut interpreter).resolve(&ast);
+            }
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+});
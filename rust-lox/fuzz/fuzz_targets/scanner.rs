@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use rlox::scanner::scan::Scanner;
+
+fuzz_target!(|source: &str| {
+    Scanner::new(source.to_string()).scan_tokens();
+});
@@ -1,5 +1,7 @@
 pub mod args_parser {
-    use clap::Parser;
+    use crate::scanner::scan::TokenDumpFormat;
+    use crate::stmt::stmt::GraphFormat;
+    use clap::{Parser, Subcommand};
 
     #[derive(Parser, Debug)]
     #[clap(author, version, about, long_about = None)]
@@ -12,8 +14,116 @@ pub mod args_parser {
         #[clap(short, long, default_value_t = false)]
         pub graphviz: bool,
 
+        /// Image format the graphviz AST is rendered to.
+        #[clap(long, value_enum, default_value_t = GraphFormat::Svg)]
+        pub graph_format: GraphFormat,
+
+        /// Directory the graphviz `.dot` file and image are written to.
+        #[clap(long, value_name = "DIR", default_value_t = String::from("ast"))]
+        pub out_dir: String,
+
         /// Similar flag to the graphviz AST images flag, however it is in the CLI.
         #[clap(short, long, default_value_t = false)]
         pub cli_graph: bool,
+
+        /// Annotate variables in the --cli-graph tree with the scope depth they resolve to.
+        #[clap(long, default_value_t = false)]
+        pub scope_depth: bool,
+
+        /// Print the scanned tokens in the given format and stop before parsing.
+        #[clap(long, value_enum, value_name = "FORMAT")]
+        pub dump_tokens: Option<TokenDumpFormat>,
+
+        /// Print the AST in the given format instead of running the program.
+        #[clap(long, value_enum, value_name = "FORMAT")]
+        pub dump_ast: Option<AstFormat>,
+
+        /// Treat the source as an AST previously written by --dump-ast json and run it.
+        #[clap(long, default_value_t = false)]
+        pub load_ast: bool,
+
+        /// Type check the program, using its optional type annotations, before running it.
+        #[clap(long, default_value_t = false)]
+        pub check: bool,
+
+        /// Fold constant expressions and drop branches with constant conditions before running.
+        #[clap(short = 'O', long, default_value_t = false)]
+        pub optimize: bool,
+
+        /// Run the program under the interactive debugger.
+        #[clap(long, default_value_t = false)]
+        pub debug: bool,
+
+        /// Line to pause at when running with --debug, can be repeated.
+        #[clap(long = "break", value_name = "LINE")]
+        pub breakpoints: Vec<u32>,
+
+        /// Record call counts and timings of functions and print a report at exit.
+        #[clap(long, default_value_t = false)]
+        pub profile: bool,
+
+        /// Also write the profile as folded stacks, readable by flame graph tools.
+        #[clap(long, value_name = "PATH")]
+        pub profile_folded: Option<String>,
+
+        /// Record executed lines and branches and write lcov and annotated source reports.
+        #[clap(long, default_value_t = false)]
+        pub coverage: bool,
+
+        /// Directory the coverage reports are written to.
+        #[clap(long, value_name = "DIR", default_value_t = String::from("coverage"))]
+        pub coverage_dir: String,
+
+        /// Run timers and sleeping fibers on a virtual clock which jumps to the next due task instead of waiting.
+        #[clap(long, default_value_t = false)]
+        pub virtual_clock: bool,
+
+        /// Directory whose files the program may read with readFile, listDir and exists, can be repeated.
+        #[clap(long, value_name = "DIR")]
+        pub allow_read: Vec<String>,
+
+        /// Directory whose files the program may write with writeFile, appendFile and removeFile, can be repeated.
+        #[clap(long, value_name = "DIR")]
+        pub allow_write: Vec<String>,
+
+        /// Let the program read environment variables with getenv.
+        #[clap(long, default_value_t = false)]
+        pub allow_env: bool,
+
+        /// Arguments of the program, read with args(). Without --src-path the first one is the source.
+        #[clap(value_name = "ARGS", trailing_var_arg = true)]
+        pub script_args: Vec<String>,
+
+        #[clap(subcommand)]
+        pub command: Option<Command>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+    pub enum AstFormat {
+        Json,
+    }
+
+    #[derive(Subcommand, Debug)]
+    pub enum Command {
+        /// Run annotated `.lox` test files and print a pass/fail summary.
+        Test(TestArgs),
+    }
+
+    #[derive(clap::Args, Debug)]
+    pub struct TestArgs {
+        /// Test file or directory which is searched recursively for `.lox` files.
+        pub path: String,
+
+        /// Number of test files run in parallel, defaults to the number of CPUs.
+        #[clap(short, long)]
+        pub jobs: Option<usize>,
+
+        /// Time limit of a single test file in milliseconds.
+        #[clap(long, value_name = "MS", default_value_t = 10000)]
+        pub timeout: u64,
+
+        /// Run the tests with another interpreter, the test file path is appended to the command.
+        #[clap(long, value_name = "CMD")]
+        pub interpreter: Option<String>,
     }
 }
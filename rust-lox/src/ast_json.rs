@@ -0,0 +1,900 @@
+/*!
+ * JSON export and import of the AST, so that external tools do not have to parse Lox.
+ *
+ * Schema(version 1), every node is an object with a `type` field:
+ *
+ * - program: `{"version": 1, "statements": [stmt]}`
+ * - token: `{"kind": "Identifier", "lexeme": "a", "line": 1, "column": 5, "length": 1}`,
+ *   `kind` is the token type name(`Plus`, `And`, `EOF`, ...), `Identifier`, `String`,
+ *   `Interpolation` and `Number` tokens also carry their `value`.
+ * - literal value: JSON number, string, boolean or `null` for nil, `{"native": "str"}` for the
+ *   native function string interpolation is lowered to, `{"native": "iterate"}` for the one
+ *   `for (var x in iterable)` loops are lowered to.
+ * - stmt:
+ *   - `{"type": "Expression", "expression": expr}`
+ *   - `{"type": "Print", "keyword": token, "expression": expr}`
+ *   - `{"type": "Return", "keyword": token, "value": expr}`
+ *   - `{"type": "Yield", "keyword": token, "value": expr}`, a bare `yield` has a nil literal value
+ *   - `{"type": "Var" | "Const", "name": token, "annotation"?: token, "initializer": expr}`,
+ *     both have the same shape, `Const` declares a binding that can't be assigned
+ *   - `{"type": "Block", "statements": [stmt]}`
+ *   - `{"type": "Class", "name": token, "superclass": expr | null, "traits"?: [expr],
+ *     "methods": [stmt], "fields"?: [{"name": token, "annotation": token}]}`
+ *   - `{"type": "Trait", "name": token, "methods": [stmt]}`
+ *   - `{"type": "Function", "name": token, "params": [token], "param_types"?: [token | null],
+ *     "result_type"?: token | null, "defaults"?: [expr | null], "rest"?: bool, "body": [stmt]}`
+ *   - `{"type": "If", "condition": expr, "then": stmt, "else": stmt | null}`
+ *   - `{"type": "While", "condition": expr, "body": stmt}`
+ *   - `{"type": "Match", "keyword": token, "subject": expr, "cases": [case], "default": stmt | null}`
+ * - case: `{"keyword": token, "patterns": [pattern], "guard": expr | null, "body": stmt}`
+ * - pattern:
+ *   - `{"kind": "Literal", "token": token, "value": literal value}`, the token of a negative
+ *     number is the number, its value is negated
+ *   - `{"kind": "Class", "name": token, "binding": token | null}`
+ * - expr:
+ *   - `{"type": "Binary" | "Logical", "left": expr, "operator": token, "right": expr}`,
+ *     `??` is a `Logical` operator
+ *   - `{"type": "Call", "callee": expr, "paren": token, "arguments": [expr],
+ *     "names"?: [token | null]}`
+ *   - `{"type": "Index", "object": expr, "bracket": token, "index": expr}`
+ *   - `{"type": "Grouping", "expression": expr}`
+ *   - `{"type": "Literal", "value": literal value}`
+ *   - `{"type": "Unary", "operator": token, "right": expr}`
+ *   - `{"type": "Variable", "name": token}`
+ *   - `{"type": "Assign", "name": token, "value": expr}`
+ *   - `{"type": "Get", "object": expr, "name": token}`
+ *   - `{"type": "Set", "object": expr, "name": token, "value": expr}`
+ *   - `{"type": "Update", "target": expr, "operator": token, "value": expr, "postfix": bool}`,
+ *     `a += b`, `++a` or `a--`: the target is a `Variable` or a `Get`, the operator the
+ *     compound one(`PlusEqual`, `PlusPlus`, ...), the value is a literal `1` for `++` and `--`
+ *   - `{"type": "Conditional", "condition": expr, "question": token, "then": expr, "else": expr}`
+ *   - `{"type": "OptionalGet", "object": expr, "name": token}`, `object?.name`
+ *   - `{"type": "This", "keyword": token}`
+ *   - `{"type": "Super", "keyword": token, "method": token}`
+ *
+ * Keys marked with `?` hold type annotations, default values or argument names and are only
+ * written when the source has some.
+ * Fields may be added in later versions, existing ones keep their meaning.
+ */
+pub mod ast_json {
+    use crate::expr::expr::Expr;
+    use crate::iterator::iterator::Iterate;
+    use crate::rlox_callable::rlox_callable::{Callable, Str};
+    use crate::scanner::scan::{token_to_json, Token, TokenType};
+    use crate::stmt::stmt::{
+        FunctionTypes, LiteralValue, MatchCase, OptionalParams, Pattern, Stmt,
+    };
+    use serde_json::{json, Value};
+
+    pub const SCHEMA_VERSION: u64 = 1;
+
+    /// Token types without a value, used to map `kind` names back to token types.
+    const SIMPLE_TOKEN_TYPES: [TokenType; 67] = [
+        TokenType::LeftParen,
+        TokenType::RightParen,
+        TokenType::LeftBrace,
+        TokenType::RightBrace,
+        TokenType::LeftBracket,
+        TokenType::RightBracket,
+        TokenType::Comma,
+        TokenType::Colon,
+        TokenType::Dot,
+        TokenType::Minus,
+        TokenType::Plus,
+        TokenType::Semicolon,
+        TokenType::Slash,
+        TokenType::Star,
+        TokenType::Percent,
+        TokenType::Ampersand,
+        TokenType::Pipe,
+        TokenType::Caret,
+        TokenType::Tilde,
+        TokenType::Question,
+        TokenType::Bang,
+        TokenType::BangEqual,
+        TokenType::Equal,
+        TokenType::EqualEqual,
+        TokenType::EqualGreater,
+        TokenType::Greater,
+        TokenType::GreaterEqual,
+        TokenType::Less,
+        TokenType::LessEqual,
+        TokenType::StarStar,
+        TokenType::LessLess,
+        TokenType::GreaterGreater,
+        TokenType::PlusPlus,
+        TokenType::MinusMinus,
+        TokenType::PlusEqual,
+        TokenType::MinusEqual,
+        TokenType::StarEqual,
+        TokenType::SlashEqual,
+        TokenType::PercentEqual,
+        TokenType::QuestionQuestion,
+        TokenType::QuestionDot,
+        TokenType::DotDotDot,
+        TokenType::And,
+        TokenType::Case,
+        TokenType::Class,
+        TokenType::Const,
+        TokenType::Default,
+        TokenType::Else,
+        TokenType::False,
+        TokenType::Fun,
+        TokenType::For,
+        TokenType::If,
+        TokenType::In,
+        TokenType::Match,
+        TokenType::Nil,
+        TokenType::Or,
+        TokenType::Print,
+        TokenType::Return,
+        TokenType::Super,
+        TokenType::This,
+        TokenType::Trait,
+        TokenType::True,
+        TokenType::Var,
+        TokenType::While,
+        TokenType::With,
+        TokenType::Yield,
+        TokenType::EOF,
+    ];
+
+    pub fn to_json(ast: &[Stmt]) -> Result<Value, String> {
+        Ok(json!({
+            "version": SCHEMA_VERSION,
+            "statements": stmts_to_json(ast)?,
+        }))
+    }
+
+    pub fn to_json_string(ast: &[Stmt]) -> Result<String, String> {
+        serde_json::to_string_pretty(&to_json(ast)?).map_err(|why| why.to_string())
+    }
+
+    pub fn from_json(program: &Value) -> Result<Vec<Stmt>, String> {
+        let version = program
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| String::from("program: missing 'version'"))?;
+        if version > SCHEMA_VERSION {
+            return Err(format!(
+                "program: unsupported schema version {}, expected at most {}",
+                version, SCHEMA_VERSION
+            ));
+        }
+        stmts_from_json(field(program, "statements", "program")?, "program")
+    }
+
+    pub fn from_json_str(source: &str) -> Result<Vec<Stmt>, String> {
+        let program: Value = serde_json::from_str(source).map_err(|why| why.to_string())?;
+        from_json(&program)
+    }
+
+    fn literal_to_json(value: &LiteralValue) -> Result<Value, String> {
+        match value {
+            LiteralValue::Number(number) => Ok(json!(number)),
+            LiteralValue::String(string) => Ok(json!(string)),
+            LiteralValue::Bool(bool) => Ok(json!(bool)),
+            LiteralValue::Nil => Ok(Value::Null),
+            LiteralValue::Callable(Callable::Str(str)) => Ok(json!({"native": str.to_string()})),
+            LiteralValue::Callable(Callable::Iterate(iterate)) => {
+                Ok(json!({"native": iterate.to_string()}))
+            }
+            LiteralValue::Callable(callable) => {
+                Err(format!("literal: can't serialize callable {}", callable))
+            }
+            LiteralValue::List(_) => Err(String::from("literal: can't serialize a list")),
+        }
+    }
+
+    fn stmts_to_json(stmts: &[Stmt]) -> Result<Value, String> {
+        Ok(Value::Array(
+            stmts.iter().map(stmt_to_json).collect::<Result<_, _>>()?,
+        ))
+    }
+
+    fn stmt_to_json(stmt: &Stmt) -> Result<Value, String> {
+        Ok(match stmt {
+            Stmt::ExprStmt(expr) => json!({
+                "type": "Expression",
+                "expression": expr_to_json(expr)?,
+            }),
+            Stmt::PrintStmt(keyword, expr) => json!({
+                "type": "Print",
+                "keyword": token_to_json(keyword),
+                "expression": expr_to_json(expr)?,
+            }),
+            Stmt::ReturnStmt(keyword, value) => json!({
+                "type": "Return",
+                "keyword": token_to_json(keyword),
+                "value": expr_to_json(value)?,
+            }),
+            Stmt::YieldStmt(keyword, value) => json!({
+                "type": "Yield",
+                "keyword": token_to_json(keyword),
+                "value": expr_to_json(value)?,
+            }),
+            Stmt::VarStmt(name, var_type, initializer)
+            | Stmt::ConstStmt(name, var_type, initializer) => {
+                let mut var = json!({
+                    "type": if matches!(stmt, Stmt::ConstStmt(..)) { "Const" } else { "Var" },
+                    "name": token_to_json(name),
+                    "initializer": expr_to_json(initializer)?,
+                });
+                if let Some(var_type) = var_type {
+                    var["annotation"] = token_to_json(var_type);
+                }
+                var
+            }
+            Stmt::BlockStmt(stmts) => json!({
+                "type": "Block",
+                "statements": stmts_to_json(stmts)?,
+            }),
+            Stmt::ClassStmt(name, superclass, traits, methods, fields) => {
+                let mut class = json!({
+                    "type": "Class",
+                    "name": token_to_json(name),
+                    "superclass": match superclass {
+                        Some(superclass) => expr_to_json(superclass)?,
+                        None => Value::Null,
+                    },
+                    "methods": stmts_to_json(methods)?,
+                });
+                if !traits.is_empty() {
+                    class["traits"] = traits
+                        .iter()
+                        .map(expr_to_json)
+                        .collect::<Result<Vec<_>, _>>()?
+                        .into();
+                }
+                if !fields.is_empty() {
+                    class["fields"] = fields
+                        .iter()
+                        .map(|(name, field_type)| {
+                            json!({
+                                "name": token_to_json(name),
+                                "annotation": token_to_json(field_type),
+                            })
+                        })
+                        .collect();
+                }
+                class
+            }
+            Stmt::TraitStmt(name, methods) => json!({
+                "type": "Trait",
+                "name": token_to_json(name),
+                "methods": stmts_to_json(methods)?,
+            }),
+            Stmt::Function(name, params, body, types, optional) => {
+                let mut function = json!({
+                    "type": "Function",
+                    "name": token_to_json(name),
+                    "params": params.iter().map(token_to_json).collect::<Vec<Value>>(),
+                    "body": stmts_to_json(body)?,
+                });
+                if !types.is_empty() {
+                    function["param_types"] =
+                        types.params.iter().map(optional_token_to_json).collect();
+                    function["result_type"] = optional_token_to_json(&types.result);
+                }
+                if !optional.is_empty() {
+                    function["defaults"] = optional
+                        .defaults
+                        .iter()
+                        .map(|default| match default {
+                            Some(default) => expr_to_json(default),
+                            None => Ok(Value::Null),
+                        })
+                        .collect::<Result<Vec<Value>, String>>()?
+                        .into();
+                    function["rest"] = json!(optional.rest);
+                }
+                function
+            }
+            Stmt::IfStmt(condition, then_stmt, else_stmt) => json!({
+                "type": "If",
+                "condition": expr_to_json(condition)?,
+                "then": stmt_to_json(then_stmt)?,
+                "else": match else_stmt {
+                    Some(else_stmt) => stmt_to_json(else_stmt)?,
+                    None => Value::Null,
+                },
+            }),
+            Stmt::WhileStmt(condition, body) => json!({
+                "type": "While",
+                "condition": expr_to_json(condition)?,
+                "body": stmt_to_json(body)?,
+            }),
+            Stmt::MatchStmt(keyword, subject, cases, default) => json!({
+                "type": "Match",
+                "keyword": token_to_json(keyword),
+                "subject": expr_to_json(subject)?,
+                "cases": cases.iter().map(case_to_json).collect::<Result<Vec<_>, _>>()?,
+                "default": match default {
+                    Some(default) => stmt_to_json(default)?,
+                    None => Value::Null,
+                },
+            }),
+        })
+    }
+
+    fn case_to_json(case: &MatchCase) -> Result<Value, String> {
+        Ok(json!({
+            "keyword": token_to_json(&case.keyword),
+            "patterns": case.patterns.iter().map(pattern_to_json).collect::<Result<Vec<_>, _>>()?,
+            "guard": match &case.guard {
+                Some(guard) => expr_to_json(guard)?,
+                None => Value::Null,
+            },
+            "body": stmt_to_json(&case.body)?,
+        }))
+    }
+
+    fn pattern_to_json(pattern: &Pattern) -> Result<Value, String> {
+        Ok(match pattern {
+            Pattern::Literal(token, value) => json!({
+                "kind": "Literal",
+                "token": token_to_json(token),
+                "value": literal_to_json(value)?,
+            }),
+            Pattern::Class(name, binding) => json!({
+                "kind": "Class",
+                "name": token_to_json(name),
+                "binding": optional_token_to_json(binding),
+            }),
+        })
+    }
+
+    fn expr_to_json(expr: &Expr) -> Result<Value, String> {
+        Ok(match expr {
+            Expr::Binary(left, operator, right) => json!({
+                "type": "Binary",
+                "left": expr_to_json(left)?,
+                "operator": token_to_json(operator),
+                "right": expr_to_json(right)?,
+            }),
+            Expr::Logical(left, operator, right) => json!({
+                "type": "Logical",
+                "left": expr_to_json(left)?,
+                "operator": token_to_json(operator),
+                "right": expr_to_json(right)?,
+            }),
+            Expr::Call(callee, paren, arguments, names) => {
+                let mut call = json!({
+                    "type": "Call",
+                    "callee": expr_to_json(callee)?,
+                    "paren": token_to_json(paren),
+                    "arguments": arguments
+                        .iter()
+                        .map(expr_to_json)
+                        .collect::<Result<Vec<Value>, String>>()?,
+                });
+                if names.iter().any(Option::is_some) {
+                    call["names"] = names.iter().map(optional_token_to_json).collect();
+                }
+                call
+            }
+            Expr::Index(object, bracket, index) => json!({
+                "type": "Index",
+                "object": expr_to_json(object)?,
+                "bracket": token_to_json(bracket),
+                "index": expr_to_json(index)?,
+            }),
+            Expr::Grouping(expression) => json!({
+                "type": "Grouping",
+                "expression": expr_to_json(expression)?,
+            }),
+            Expr::Literal(value) => json!({
+                "type": "Literal",
+                "value": literal_to_json(value)?,
+            }),
+            Expr::Unary(operator, right) => json!({
+                "type": "Unary",
+                "operator": token_to_json(operator),
+                "right": expr_to_json(right)?,
+            }),
+            Expr::Variable(name) => json!({
+                "type": "Variable",
+                "name": token_to_json(name),
+            }),
+            Expr::Assign(name, value) => json!({
+                "type": "Assign",
+                "name": token_to_json(name),
+                "value": expr_to_json(value)?,
+            }),
+            Expr::Get(object, name) => json!({
+                "type": "Get",
+                "object": expr_to_json(object)?,
+                "name": token_to_json(name),
+            }),
+            Expr::Set(object, name, value) => json!({
+                "type": "Set",
+                "object": expr_to_json(object)?,
+                "name": token_to_json(name),
+                "value": expr_to_json(value)?,
+            }),
+            Expr::Update(target, operator, value, postfix) => json!({
+                "type": "Update",
+                "target": expr_to_json(target)?,
+                "operator": token_to_json(operator),
+                "value": expr_to_json(value)?,
+                "postfix": postfix,
+            }),
+            Expr::Conditional(condition, question, then_expr, else_expr) => json!({
+                "type": "Conditional",
+                "condition": expr_to_json(condition)?,
+                "question": token_to_json(question),
+                "then": expr_to_json(then_expr)?,
+                "else": expr_to_json(else_expr)?,
+            }),
+            Expr::OptionalGet(object, name) => json!({
+                "type": "OptionalGet",
+                "object": expr_to_json(object)?,
+                "name": token_to_json(name),
+            }),
+            Expr::This(keyword) => json!({
+                "type": "This",
+                "keyword": token_to_json(keyword),
+            }),
+            Expr::Super(keyword, method) => json!({
+                "type": "Super",
+                "keyword": token_to_json(keyword),
+                "method": token_to_json(method),
+            }),
+        })
+    }
+
+    fn optional_token_to_json(token: &Option<Token>) -> Value {
+        match token {
+            Some(token) => token_to_json(token),
+            None => Value::Null,
+        }
+    }
+
+    fn optional_token_from_json(
+        token: Option<&Value>,
+        context: &str,
+    ) -> Result<Option<Token>, String> {
+        match token {
+            None | Some(Value::Null) => Ok(None),
+            Some(token) => token_from_json(token, context).map(Some),
+        }
+    }
+
+    fn field<'a>(object: &'a Value, name: &str, context: &str) -> Result<&'a Value, String> {
+        object
+            .get(name)
+            .ok_or_else(|| format!("{}: missing '{}'", context, name))
+    }
+
+    fn str_field<'a>(object: &'a Value, name: &str, context: &str) -> Result<&'a str, String> {
+        field(object, name, context)?
+            .as_str()
+            .ok_or_else(|| format!("{}: '{}' must be a string", context, name))
+    }
+
+    fn u32_field(object: &Value, name: &str, context: &str) -> Result<u32, String> {
+        field(object, name, context)?
+            .as_u64()
+            .and_then(|number| u32::try_from(number).ok())
+            .ok_or_else(|| format!("{}: '{}' must be a positive integer", context, name))
+    }
+
+    fn token_from_json(token: &Value, context: &str) -> Result<Token, String> {
+        let kind = str_field(token, "kind", context)?;
+        let t_type = match kind {
+            "Identifier" => TokenType::Identifier(str_field(token, "value", context)?.to_string()),
+            "String" => TokenType::String(str_field(token, "value", context)?.to_string()),
+            "Interpolation" => {
+                TokenType::Interpolation(str_field(token, "value", context)?.to_string())
+            }
+            "Number" => TokenType::Number(
+                field(token, "value", context)?
+                    .as_f64()
+                    .ok_or_else(|| format!("{}: 'value' must be a number", context))?,
+            ),
+            _ => SIMPLE_TOKEN_TYPES
+                .iter()
+                .find(|t_type| format!("{:?}", t_type) == kind)
+                .cloned()
+                .ok_or_else(|| format!("{}: unknown token kind '{}'", context, kind))?,
+        };
+
+        let lexeme = match token.get("lexeme") {
+            Some(lexeme) => lexeme
+                .as_str()
+                .ok_or_else(|| format!("{}: 'lexeme' must be a string", context))?
+                .to_string(),
+            None => t_type.to_string(),
+        };
+        let length = match token.get("length") {
+            Some(_) => u32_field(token, "length", context)?,
+            None => lexeme.len() as u32,
+        };
+
+        Ok(Token::new(
+            t_type,
+            lexeme,
+            u32_field(token, "line", context)?,
+            u32_field(token, "column", context)?,
+            length,
+        ))
+    }
+
+    fn literal_from_json(value: &Value, context: &str) -> Result<LiteralValue, String> {
+        match value {
+            Value::Null => Ok(LiteralValue::Nil),
+            Value::Bool(bool) => Ok(LiteralValue::Bool(*bool)),
+            Value::Number(number) => number
+                .as_f64()
+                .map(LiteralValue::Number)
+                .ok_or_else(|| format!("{}: invalid number literal", context)),
+            Value::String(string) => Ok(LiteralValue::String(string.clone())),
+            Value::Object(_) if value.get("native") == Some(&json!("str")) => {
+                Ok(LiteralValue::Callable(Callable::Str(Str {})))
+            }
+            Value::Object(_) if value.get("native") == Some(&json!("iterate")) => {
+                Ok(LiteralValue::Callable(Callable::Iterate(Iterate {})))
+            }
+            _ => Err(format!(
+                "{}: literal must be a number, string, boolean, null or native function",
+                context
+            )),
+        }
+    }
+
+    fn stmts_from_json(stmts: &Value, context: &str) -> Result<Vec<Stmt>, String> {
+        stmts
+            .as_array()
+            .ok_or_else(|| format!("{}: expected a list of statements", context))?
+            .iter()
+            .enumerate()
+            .map(|(idx, stmt)| stmt_from_json(stmt, &format!("{}[{}]", context, idx)))
+            .collect()
+    }
+
+    fn stmt_from_json(stmt: &Value, context: &str) -> Result<Stmt, String> {
+        let expr = |name: &str| expr_from_json(field(stmt, name, context)?, context);
+        let token = |name: &str| token_from_json(field(stmt, name, context)?, context);
+
+        match str_field(stmt, "type", context)? {
+            "Expression" => Ok(Stmt::ExprStmt(expr("expression")?)),
+            "Print" => Ok(Stmt::PrintStmt(token("keyword")?, expr("expression")?)),
+            "Return" => Ok(Stmt::ReturnStmt(token("keyword")?, expr("value")?)),
+            "Yield" => Ok(Stmt::YieldStmt(token("keyword")?, expr("value")?)),
+            "Var" => Ok(Stmt::VarStmt(
+                token("name")?,
+                optional_token_from_json(stmt.get("annotation"), context)?,
+                expr("initializer")?,
+            )),
+            "Const" => Ok(Stmt::ConstStmt(
+                token("name")?,
+                optional_token_from_json(stmt.get("annotation"), context)?,
+                expr("initializer")?,
+            )),
+            "Block" => Ok(Stmt::BlockStmt(stmts_from_json(
+                field(stmt, "statements", context)?,
+                context,
+            )?)),
+            "Class" => {
+                let superclass = match field(stmt, "superclass", context)? {
+                    Value::Null => None,
+                    superclass => Some(expr_from_json(superclass, context)?),
+                };
+                let fields = match stmt.get("fields") {
+                    Some(fields) => fields
+                        .as_array()
+                        .ok_or_else(|| format!("{}: 'fields' must be a list", context))?
+                        .iter()
+                        .map(|field_type| {
+                            Ok((
+                                token_from_json(field(field_type, "name", context)?, context)?,
+                                token_from_json(
+                                    field(field_type, "annotation", context)?,
+                                    context,
+                                )?,
+                            ))
+                        })
+                        .collect::<Result<Vec<(Token, Token)>, String>>()?,
+                    None => Vec::new(),
+                };
+                let traits = match stmt.get("traits") {
+                    Some(traits) => traits
+                        .as_array()
+                        .ok_or_else(|| format!("{}: 'traits' must be a list", context))?
+                        .iter()
+                        .map(|used_trait| expr_from_json(used_trait, context))
+                        .collect::<Result<Vec<Expr>, String>>()?,
+                    None => Vec::new(),
+                };
+                Ok(Stmt::ClassStmt(
+                    token("name")?,
+                    superclass,
+                    traits,
+                    stmts_from_json(field(stmt, "methods", context)?, context)?,
+                    fields,
+                ))
+            }
+            "Trait" => Ok(Stmt::TraitStmt(
+                token("name")?,
+                stmts_from_json(field(stmt, "methods", context)?, context)?,
+            )),
+            "Function" => {
+                let params = field(stmt, "params", context)?
+                    .as_array()
+                    .ok_or_else(|| format!("{}: 'params' must be a list of tokens", context))?
+                    .iter()
+                    .map(|param| token_from_json(param, context))
+                    .collect::<Result<Vec<Token>, String>>()?;
+                let mut types = FunctionTypes {
+                    params: vec![None; params.len()],
+                    result: optional_token_from_json(stmt.get("result_type"), context)?,
+                };
+                if let Some(param_types) = stmt.get("param_types") {
+                    types.params = param_types
+                        .as_array()
+                        .filter(|param_types| param_types.len() == params.len())
+                        .ok_or_else(|| {
+                            format!("{}: 'param_types' must have one entry per param", context)
+                        })?
+                        .iter()
+                        .map(|param_type| optional_token_from_json(Some(param_type), context))
+                        .collect::<Result<Vec<Option<Token>>, String>>()?;
+                }
+                let mut optional = OptionalParams {
+                    defaults: vec![None; params.len()],
+                    rest: false,
+                };
+                if let Some(defaults) = stmt.get("defaults") {
+                    optional.defaults = defaults
+                        .as_array()
+                        .filter(|defaults| defaults.len() == params.len())
+                        .ok_or_else(|| {
+                            format!("{}: 'defaults' must have one entry per param", context)
+                        })?
+                        .iter()
+                        .map(|default| match default {
+                            Value::Null => Ok(None),
+                            default => expr_from_json(default, context).map(Some),
+                        })
+                        .collect::<Result<Vec<Option<Expr>>, String>>()?;
+                    optional.rest = field(stmt, "rest", context)?
+                        .as_bool()
+                        .filter(|rest| !rest || !params.is_empty())
+                        .ok_or_else(|| {
+                            format!(
+                                "{}: 'rest' must be a boolean, true only with params",
+                                context
+                            )
+                        })?;
+                }
+                Ok(Stmt::Function(
+                    token("name")?,
+                    params,
+                    stmts_from_json(field(stmt, "body", context)?, context)?,
+                    types,
+                    optional,
+                ))
+            }
+            "If" => {
+                let else_stmt = match field(stmt, "else", context)? {
+                    Value::Null => None,
+                    else_stmt => Some(Box::new(stmt_from_json(else_stmt, context)?)),
+                };
+                Ok(Stmt::IfStmt(
+                    expr("condition")?,
+                    Box::new(stmt_from_json(field(stmt, "then", context)?, context)?),
+                    else_stmt,
+                ))
+            }
+            "While" => Ok(Stmt::WhileStmt(
+                expr("condition")?,
+                Box::new(stmt_from_json(field(stmt, "body", context)?, context)?),
+            )),
+            "Match" => {
+                let cases = field(stmt, "cases", context)?
+                    .as_array()
+                    .ok_or_else(|| format!("{}: 'cases' must be a list", context))?
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, case)| case_from_json(case, &format!("{}.cases[{}]", context, idx)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let default = match field(stmt, "default", context)? {
+                    Value::Null => None,
+                    default => Some(Box::new(stmt_from_json(default, context)?)),
+                };
+                Ok(Stmt::MatchStmt(
+                    token("keyword")?,
+                    expr("subject")?,
+                    cases,
+                    default,
+                ))
+            }
+            unknown => Err(format!("{}: unknown statement type '{}'", context, unknown)),
+        }
+    }
+
+    fn case_from_json(case: &Value, context: &str) -> Result<MatchCase, String> {
+        let patterns = field(case, "patterns", context)?
+            .as_array()
+            .ok_or_else(|| format!("{}: 'patterns' must be a list", context))?
+            .iter()
+            .map(|pattern| pattern_from_json(pattern, context))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(MatchCase {
+            keyword: token_from_json(field(case, "keyword", context)?, context)?,
+            patterns,
+            guard: match field(case, "guard", context)? {
+                Value::Null => None,
+                guard => Some(expr_from_json(guard, context)?),
+            },
+            body: stmt_from_json(field(case, "body", context)?, context)?,
+        })
+    }
+
+    fn pattern_from_json(pattern: &Value, context: &str) -> Result<Pattern, String> {
+        match str_field(pattern, "kind", context)? {
+            "Literal" => Ok(Pattern::Literal(
+                token_from_json(field(pattern, "token", context)?, context)?,
+                literal_from_json(field(pattern, "value", context)?, context)?,
+            )),
+            "Class" => Ok(Pattern::Class(
+                token_from_json(field(pattern, "name", context)?, context)?,
+                optional_token_from_json(pattern.get("binding"), context)?,
+            )),
+            unknown => Err(format!("{}: unknown pattern kind '{}'", context, unknown)),
+        }
+    }
+
+    fn expr_from_json(expr: &Value, context: &str) -> Result<Expr, String> {
+        let sub_expr = |name: &str| -> Result<Box<Expr>, String> {
+            Ok(Box::new(expr_from_json(
+                field(expr, name, context)?,
+                context,
+            )?))
+        };
+        let token = |name: &str| token_from_json(field(expr, name, context)?, context);
+
+        match str_field(expr, "type", context)? {
+            "Binary" => Ok(Expr::Binary(
+                sub_expr("left")?,
+                token("operator")?,
+                sub_expr("right")?,
+            )),
+            "Logical" => Ok(Expr::Logical(
+                sub_expr("left")?,
+                token("operator")?,
+                sub_expr("right")?,
+            )),
+            "Call" => {
+                let arguments = field(expr, "arguments", context)?
+                    .as_array()
+                    .ok_or_else(|| format!("{}: 'arguments' must be a list", context))?
+                    .iter()
+                    .map(|argument| expr_from_json(argument, context))
+                    .collect::<Result<Vec<Expr>, String>>()?;
+                let names = match expr.get("names") {
+                    Some(names) => names
+                        .as_array()
+                        .filter(|names| names.len() == arguments.len())
+                        .ok_or_else(|| {
+                            format!("{}: 'names' must have one entry per argument", context)
+                        })?
+                        .iter()
+                        .map(|name| optional_token_from_json(Some(name), context))
+                        .collect::<Result<Vec<Option<Token>>, String>>()?,
+                    None => vec![None; arguments.len()],
+                };
+                Ok(Expr::Call(
+                    sub_expr("callee")?,
+                    token("paren")?,
+                    arguments,
+                    names,
+                ))
+            }
+            "Grouping" => Ok(Expr::Grouping(sub_expr("expression")?)),
+            "Literal" => Ok(Expr::Literal(literal_from_json(
+                field(expr, "value", context)?,
+                context,
+            )?)),
+            "Unary" => Ok(Expr::Unary(token("operator")?, sub_expr("right")?)),
+            "Variable" => Ok(Expr::Variable(token("name")?)),
+            "Assign" => Ok(Expr::Assign(token("name")?, sub_expr("value")?)),
+            "Get" => Ok(Expr::Get(sub_expr("object")?, token("name")?)),
+            "Set" => Ok(Expr::Set(
+                sub_expr("object")?,
+                token("name")?,
+                sub_expr("value")?,
+            )),
+            "Update" => Ok(Expr::Update(
+                sub_expr("target")?,
+                token("operator")?,
+                sub_expr("value")?,
+                field(expr, "postfix", context)?
+                    .as_bool()
+                    .ok_or_else(|| format!("{}: 'postfix' must be a boolean", context))?,
+            )),
+            "Conditional" => Ok(Expr::Conditional(
+                sub_expr("condition")?,
+                token("question")?,
+                sub_expr("then")?,
+                sub_expr("else")?,
+            )),
+            "OptionalGet" => Ok(Expr::OptionalGet(sub_expr("object")?, token("name")?)),
+            "Index" => Ok(Expr::Index(
+                sub_expr("object")?,
+                token("bracket")?,
+                sub_expr("index")?,
+            )),
+            "This" => Ok(Expr::This(token("keyword")?)),
+            "Super" => Ok(Expr::Super(token("keyword")?, token("method")?)),
+            unknown => Err(format!(
+                "{}: unknown expression type '{}'",
+                context, unknown
+            )),
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::parser::parser::Parser;
+        use crate::scanner::scan::Scanner;
+
+        fn parse(source: &str) -> Vec<Stmt> {
+            Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap()
+        }
+
+        #[test]
+        fn round_trip_ok() {
+            let ast = parse(
+                "class A < B { init(x) { this.x = -x; super.init(); } }\n\
+                 fun f(a, b) { if (a and !b) return a; else { while (b) b = nil; } }\n\
+                 var s = \"str\"; print (1 + 2.5) * f(true, false).x;\n\
+                 print \"a\\t${s} and ${\"nested ${s}\"}\";\n\
+                 var n = 7 % 3 ** 2 | ~1 << 2; n += 1; s.b++; --n;\n\
+                 print n > 1 ? s?.b : s?.m() ?? 2;\n\
+                 match (n) { case 1, -2.5, \"a\", nil => print 1; case A a if a.x => {} default => print 2; }\n\
+                 fun h(a, b = a + 1, ...rest) { return rest[0]; } h(b: 2, a: 1);\n\
+                 fun g() { yield; yield 1; }\n\
+                 trait T { m() { return super.m(); } } class C < A with T, U {}",
+            );
+            let json = to_json_string(&ast).unwrap();
+            assert_eq!(from_json_str(&json).unwrap(), ast);
+        }
+
+        #[test]
+        fn round_trip_type_annotations_ok() {
+            let ast = parse(
+                "class P { x: number; init(x: number, f) { this.x = x; } }\n\
+                 fun g(a, b: string): nil {}\n\
+                 var p: P = P(1, nil); const q: P = p;",
+            );
+            let json = to_json(&ast).unwrap();
+            assert_eq!(json["statements"][1]["param_types"][0], Value::Null);
+            assert_eq!(json["statements"][1]["result_type"]["kind"], "Nil");
+            assert_eq!(from_json(&json).unwrap(), ast);
+        }
+
+        #[test]
+        fn token_schema_ok() {
+            let json = to_json(&parse("var a = 1;")).unwrap();
+            let name = &json["statements"][0]["name"];
+            assert_eq!(json["version"], 1);
+            assert_eq!(name["kind"], "Identifier");
+            assert_eq!(name["value"], "a");
+            assert_eq!(name["line"], 1);
+            assert_eq!(name["column"], 5);
+        }
+
+        #[test]
+        fn load_invalid_nok() {
+            let error = from_json_str(
+                r#"{"version": 1, "statements": [{"type": "Print", "expression": {"type": "Nope"}}]}"#,
+            )
+            .unwrap_err();
+            assert_eq!(error, "program[0]: missing 'keyword'");
+        }
+    }
+}
@@ -0,0 +1,1198 @@
+pub mod checker {
+    use crate::error_handling::error_handling::{error, warning, RLoxErrorType};
+    use crate::expr::expr::{Expr, Visitor};
+    use crate::function_name;
+    use crate::interpreter::interpreter::Error;
+    use crate::rlox_callable::rlox_callable::Arity;
+    use crate::scanner::scan::{Token, TokenType};
+    use crate::stmt::stmt::{
+        FunctionTypes, LiteralValue, MatchCase, OptionalParams, Pattern, Stmt,
+    };
+    use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+    use std::fmt;
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Type {
+        /// Unannotated or unknown, compatible with every other type.
+        Any,
+        Nil,
+        Bool,
+        Number,
+        String,
+        /// `None` for the `fun` annotation, which says nothing about the signature.
+        Function(Option<Signature>),
+        /// The class itself, calling it creates an instance.
+        Class(ClassId),
+        Instance(ClassId),
+    }
+
+    /// A class or trait declaration, classes sharing a name in different scopes are different types.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct ClassId {
+        name: String,
+        line: u32,
+        column: u32,
+    }
+
+    impl ClassId {
+        fn of(name: &Token) -> Self {
+            Self {
+                name: name.get_token_type().to_string(),
+                line: name.get_line(),
+                column: name.get_column(),
+            }
+        }
+    }
+
+    /// Parameter types, the type of a rest parameter is the one of each of its arguments.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Signature {
+        pub params: Vec<Type>,
+        pub arity: Arity,
+        pub result: Box<Type>,
+    }
+
+    impl fmt::Display for Type {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Type::Any => write!(f, "any"),
+                Type::Nil => write!(f, "nil"),
+                Type::Bool => write!(f, "bool"),
+                Type::Number => write!(f, "number"),
+                Type::String => write!(f, "string"),
+                Type::Function(None) => write!(f, "fun"),
+                Type::Function(Some(signature)) => {
+                    let params = signature
+                        .params
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, param)| match signature.arity.max {
+                            None if idx + 1 == signature.params.len() => format!("...{}", param),
+                            _ if idx >= signature.arity.min => format!("{}?", param),
+                            _ => param.to_string(),
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    write!(f, "fun({}): {}", params, signature.result)
+                }
+                Type::Class(class) => write!(f, "class {}", class.name),
+                Type::Instance(class) => write!(f, "{}", class.name),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct ClassInfo {
+        superclass: Option<ClassId>,
+        fields: HashMap<String, Type>,
+        methods: HashMap<String, Signature>,
+    }
+
+    /**
+     * Gradual type checker run before the resolver with `--check`.
+     * Annotated variables, parameters, results and class fields are checked against the
+     * types inferred for expressions, unannotated ones are `any` and accept everything.
+     * An unannotated variable only takes the type of its initializer when it is never
+     * reassigned. `nil` can be stored in any annotated variable, like in most languages
+     * with references. The checker reports every error it finds before giving up.
+     */
+    #[derive(Default)]
+    pub struct Checker {
+        classes: HashMap<ClassId, ClassInfo>,
+        /// Method signatures of each trait, copied into the classes using it.
+        traits: HashMap<ClassId, HashMap<String, Signature>>,
+        scopes: Vec<HashMap<String, Type>>,
+        /// Classes and traits visible in each of the `scopes`, by name.
+        declarations: Vec<HashMap<String, ClassId>>,
+        /// Names assigned somewhere in the program, their type can change at runtime.
+        assigned: HashSet<String>,
+        /// Declared result types of the functions being checked, innermost last.
+        results: Vec<Type>,
+        current_class: Option<ClassId>,
+        errors: usize,
+    }
+
+    impl Checker {
+        pub fn new() -> Self {
+            Self {
+                scopes: vec![HashMap::default()],
+                declarations: vec![HashMap::default()],
+                ..Self::default()
+            }
+        }
+
+        pub fn check(&mut self, ast: &[Stmt]) -> Result<(), Error> {
+            collect_assigned_names(ast, &mut self.assigned);
+            self.check_stmts(ast);
+
+            match self.errors {
+                0 => Ok(()),
+                _ => Err(Error::LoxRuntimeError),
+            }
+        }
+
+        /**
+         * Declares the classes and traits of a scope before its statements are checked, so
+         * that types can refer to a class declared further down. Nested ones are declared
+         * when their own scope is checked.
+         */
+        fn declare_classes(&mut self, stmts: &[Stmt]) {
+            for stmt in stmts {
+                if let Stmt::ClassStmt(name, ..) | Stmt::TraitStmt(name, _) = stmt {
+                    self.declare(name);
+                }
+            }
+            // every class name is known before field and method types refer to them.
+            for stmt in stmts {
+                if let Stmt::ClassStmt(name, ..) = stmt {
+                    self.classes.insert(ClassId::of(name), ClassInfo::default());
+                }
+            }
+            for stmt in stmts {
+                if let Stmt::TraitStmt(name, methods) = stmt {
+                    let methods = self.method_signatures(methods);
+                    self.traits.insert(ClassId::of(name), methods);
+                }
+            }
+            for stmt in stmts {
+                if let Stmt::ClassStmt(name, superclass, used_traits, methods, fields) = stmt {
+                    let info = self.class_info(superclass, used_traits, methods, fields);
+                    self.classes.insert(ClassId::of(name), info);
+                }
+            }
+        }
+
+        fn declare(&mut self, name: &Token) {
+            if let Some(declarations) = self.declarations.last_mut() {
+                declarations.insert(name.get_token_type().to_string(), ClassId::of(name));
+            }
+        }
+
+        /// The innermost class or trait declared with `name`.
+        fn declaration(&self, name: &str) -> Option<ClassId> {
+            self.declarations
+                .iter()
+                .rev()
+                .find_map(|declarations| declarations.get(name).cloned())
+        }
+
+        fn begin_scope(&mut self) {
+            self.scopes.push(HashMap::default());
+            self.declarations.push(HashMap::default());
+        }
+
+        fn end_scope(&mut self) {
+            self.scopes.pop();
+            self.declarations.pop();
+        }
+
+        /// Methods of the traits come first, the class's own methods override them.
+        fn class_info(
+            &mut self,
+            superclass: &Option<Expr>,
+            used_traits: &[Expr],
+            methods: &[Stmt],
+            fields: &[(Token, Token)],
+        ) -> ClassInfo {
+            let mut info = ClassInfo {
+                superclass: match superclass {
+                    Some(Expr::Variable(name)) => {
+                        self.declaration(&name.get_token_type().to_string())
+                    }
+                    _ => None,
+                },
+                ..ClassInfo::default()
+            };
+            for (name, field_type) in fields {
+                let field_type = self.type_of_name(field_type);
+                info.fields
+                    .insert(name.get_token_type().to_string(), field_type);
+            }
+            for used_trait in used_traits {
+                if let Expr::Variable(name) = used_trait {
+                    let methods = self
+                        .declaration(&name.get_token_type().to_string())
+                        .and_then(|used_trait| self.traits.get(&used_trait));
+                    if let Some(methods) = methods {
+                        info.methods.extend(methods.clone());
+                    }
+                }
+            }
+            info.methods.extend(self.method_signatures(methods));
+            info
+        }
+
+        fn method_signatures(&mut self, methods: &[Stmt]) -> HashMap<String, Signature> {
+            let mut signatures = HashMap::default();
+            for method in methods {
+                if let Stmt::Function(name, _, body, types, optional) = method {
+                    let signature = self.signature(types, optional, body);
+                    signatures.insert(name.get_token_type().to_string(), signature);
+                }
+            }
+            signatures
+        }
+
+        fn report(&mut self, token: &Token, message: String) {
+            self.errors += 1;
+            error(
+                token.get_line(),
+                token.get_column(),
+                message,
+                function_name!(),
+                Some(RLoxErrorType::TypeError),
+            );
+        }
+
+        fn warn(&self, token: &Token, message: String) {
+            warning(
+                token.get_line(),
+                token.get_column(),
+                message,
+                function_name!(),
+            );
+        }
+
+        fn type_of_name(&mut self, token: &Token) -> Type {
+            match token.get_token_type() {
+                TokenType::Nil => Type::Nil,
+                TokenType::Fun => Type::Function(None),
+                TokenType::Identifier(name) => match name.as_str() {
+                    "any" => Type::Any,
+                    "bool" => Type::Bool,
+                    "number" => Type::Number,
+                    "string" => Type::String,
+                    _ => match self.declaration(&name) {
+                        Some(class) if self.classes.contains_key(&class) => Type::Instance(class),
+                        _ => {
+                            self.report(token, format!("Unknown type '{}'.", name));
+                            Type::Any
+                        }
+                    },
+                },
+                _ => Type::Any,
+            }
+        }
+
+        fn annotation(&mut self, annotation: &Option<Token>) -> Type {
+            match annotation {
+                Some(token) => self.type_of_name(token),
+                None => Type::Any,
+            }
+        }
+
+        /// Calling a function containing `yield` gives a generator, whatever its result annotation.
+        fn signature(
+            &mut self,
+            types: &FunctionTypes,
+            optional: &OptionalParams,
+            body: &[Stmt],
+        ) -> Signature {
+            let fixed = types.params.len() - optional.rest as usize;
+            let result = self.annotation(&types.result);
+            Signature {
+                params: types
+                    .params
+                    .iter()
+                    .map(|param| self.annotation(param))
+                    .collect(),
+                arity: Arity {
+                    min: optional.defaults[..fixed]
+                        .iter()
+                        .filter(|default| default.is_none())
+                        .count(),
+                    max: (!optional.rest).then_some(fixed),
+                },
+                result: Box::new(match body.iter().any(Stmt::contains_yield) {
+                    true => Type::Any,
+                    false => result,
+                }),
+            }
+        }
+
+        fn superclass_of(&self, class: &ClassId) -> Option<&ClassId> {
+            self.classes
+                .get(class)
+                .and_then(|info| info.superclass.as_ref())
+        }
+
+        /// Walks `class` and its superclasses, inheritance cycles are only reported later by the resolver.
+        fn find_in_hierarchy<T>(
+            &self,
+            class: &ClassId,
+            find: impl Fn(&ClassInfo) -> Option<T>,
+        ) -> Option<T> {
+            let mut class = class.clone();
+            for _ in 0..=self.classes.len() {
+                let info = self.classes.get(&class)?;
+                if let Some(found) = find(info) {
+                    return Some(found);
+                }
+                class = info.superclass.clone()?;
+            }
+            None
+        }
+
+        fn is_subclass(&self, class: &ClassId, superclass: &ClassId) -> bool {
+            class == superclass
+                || self
+                    .find_in_hierarchy(class, |info| {
+                        (info.superclass.as_ref() == Some(superclass)).then_some(())
+                    })
+                    .is_some()
+        }
+
+        fn method(&self, class: &ClassId, name: &str) -> Option<Signature> {
+            self.find_in_hierarchy(class, |info| info.methods.get(name).cloned())
+        }
+
+        fn field(&self, class: &ClassId, name: &str) -> Option<Type> {
+            self.find_in_hierarchy(class, |info| info.fields.get(name).cloned())
+        }
+
+        /// Whether a value of type `actual` can be stored where `expected` is declared.
+        fn accepts(&self, expected: &Type, actual: &Type) -> bool {
+            match (expected, actual) {
+                (Type::Any, _) | (_, Type::Any) | (_, Type::Nil) => true,
+                (Type::Function(None), Type::Function(_) | Type::Class(_)) => true,
+                (Type::Function(Some(_)), Type::Function(None)) => true,
+                (Type::Function(Some(expected)), Type::Function(Some(actual))) => {
+                    expected.params.len() == actual.params.len()
+                }
+                (Type::Instance(expected), Type::Instance(actual)) => {
+                    self.is_subclass(actual, expected)
+                }
+                (expected, actual) => expected == actual,
+            }
+        }
+
+        fn lookup(&self, name: &Token) -> Type {
+            let name = name.get_token_type().to_string();
+            self.scopes
+                .iter()
+                .rev()
+                .find_map(|scope| scope.get(&name).cloned())
+                .unwrap_or(Type::Any)
+        }
+
+        fn define(&mut self, name: &Token, value: Type) {
+            if let Some(scope) = self.scopes.last_mut() {
+                scope.insert(name.get_token_type().to_string(), value);
+            }
+        }
+
+        /// Type a declaration without annotation gets, reassigned names can hold anything.
+        fn inferred(&self, name: &Token, value: Type) -> Type {
+            match self.assigned.contains(&name.get_token_type().to_string()) {
+                true => Type::Any,
+                false => value,
+            }
+        }
+
+        fn type_of(&mut self, expr: &Expr) -> Type {
+            expr.accept(self)
+        }
+
+        fn check_stmts(&mut self, stmts: &[Stmt]) {
+            self.declare_classes(stmts);
+            for stmt in stmts {
+                self.check_stmt(stmt);
+            }
+        }
+
+        fn check_stmt(&mut self, stmt: &Stmt) {
+            match stmt {
+                Stmt::ExprStmt(expr) | Stmt::PrintStmt(_, expr) | Stmt::YieldStmt(_, expr) => {
+                    self.type_of(expr);
+                }
+                Stmt::ReturnStmt(keyword, value) => {
+                    let value = self.type_of(value);
+                    let expected = self.results.last().cloned().unwrap_or(Type::Any);
+                    if !self.accepts(&expected, &value) {
+                        self.report(
+                            keyword,
+                            format!(
+                                "Can't return {} from a function returning {}.",
+                                value, expected
+                            ),
+                        );
+                    }
+                }
+                Stmt::VarStmt(name, annotation, initializer)
+                | Stmt::ConstStmt(name, annotation, initializer) => {
+                    let value = self.type_of(initializer);
+                    let declared = match annotation {
+                        Some(annotation) => self.type_of_name(annotation),
+                        None => self.inferred(name, value.clone()),
+                    };
+                    if !self.accepts(&declared, &value) {
+                        self.report(
+                            name,
+                            format!(
+                                "Can't assign {} to '{}' of type {}.",
+                                value,
+                                name.get_token_type(),
+                                declared
+                            ),
+                        );
+                    }
+                    self.define(name, declared);
+                }
+                Stmt::BlockStmt(stmts) => {
+                    self.begin_scope();
+                    self.check_stmts(stmts);
+                    self.end_scope();
+                }
+                Stmt::ClassStmt(name, superclass, used_traits, methods, _) => {
+                    let class = ClassId::of(name);
+                    // a redeclared class replaces the earlier one from here on.
+                    self.declare(name);
+                    self.define(name, self.inferred(name, Type::Class(class.clone())));
+                    if let Some(superclass) = superclass {
+                        self.type_of(superclass);
+                    }
+                    for used_trait in used_traits {
+                        self.type_of(used_trait);
+                    }
+
+                    let enclosing = self.current_class.replace(class.clone());
+                    for method in methods {
+                        if let Stmt::Function(name, params, body, _, optional) = method {
+                            // the signature was already built, and its types reported, by `check`.
+                            let signature = self.method(&class, &name.get_token_type().to_string());
+                            if let Some(signature) = signature {
+                                self.check_function(name, params, body, optional, signature);
+                            }
+                        }
+                    }
+                    self.current_class = enclosing;
+                }
+                // `this` in a trait method can be an instance of any class using the trait.
+                Stmt::TraitStmt(name, methods) => {
+                    self.declare(name);
+                    self.define(name, Type::Any);
+                    let signatures = self
+                        .traits
+                        .get(&ClassId::of(name))
+                        .cloned()
+                        .unwrap_or_default();
+                    let enclosing = self.current_class.take();
+                    for method in methods {
+                        if let Stmt::Function(name, params, body, _, optional) = method {
+                            if let Some(signature) =
+                                signatures.get(&name.get_token_type().to_string())
+                            {
+                                let signature = signature.clone();
+                                self.check_function(name, params, body, optional, signature);
+                            }
+                        }
+                    }
+                    self.current_class = enclosing;
+                }
+                Stmt::Function(name, params, body, types, optional) => {
+                    let signature = self.signature(types, optional, body);
+                    let function = Type::Function(Some(signature.clone()));
+                    self.define(name, self.inferred(name, function));
+                    self.check_function(name, params, body, optional, signature);
+                }
+                Stmt::IfStmt(condition, then_stmt, else_stmt) => {
+                    self.type_of(condition);
+                    self.check_stmt(then_stmt);
+                    if let Some(else_stmt) = else_stmt {
+                        self.check_stmt(else_stmt);
+                    }
+                }
+                Stmt::WhileStmt(condition, body) => {
+                    self.type_of(condition);
+                    self.check_stmt(body);
+                }
+                Stmt::MatchStmt(keyword, subject, cases, default) => {
+                    let subject = self.type_of(subject);
+                    for case in cases {
+                        self.check_case(case);
+                    }
+                    match default {
+                        Some(default) => self.check_stmt(default),
+                        None if subject == Type::Bool && covers_bools(cases) => {}
+                        None => self.warn(
+                            keyword,
+                            String::from(
+                                "Match has no 'default' case, values matching no case are ignored.",
+                            ),
+                        ),
+                    }
+                }
+            }
+        }
+
+        fn check_case(&mut self, case: &MatchCase) {
+            self.begin_scope();
+            for pattern in &case.patterns {
+                if let Pattern::Class(class, Some(binding)) = pattern {
+                    let instance = match self.lookup(class) {
+                        Type::Class(class) => Type::Instance(class),
+                        _ => Type::Any,
+                    };
+                    self.define(binding, self.inferred(binding, instance));
+                }
+            }
+            if let Some(guard) = &case.guard {
+                self.type_of(guard);
+            }
+            self.check_stmt(&case.body);
+            self.end_scope();
+        }
+
+        fn check_function(
+            &mut self,
+            name: &Token,
+            params: &[Token],
+            body: &[Stmt],
+            optional: &OptionalParams,
+            signature: Signature,
+        ) {
+            self.begin_scope();
+            for (idx, (param, param_type)) in params.iter().zip(signature.params).enumerate() {
+                if let Some(Some(default)) = optional.defaults.get(idx) {
+                    let value = self.type_of(default);
+                    if !self.accepts(&param_type, &value) {
+                        self.report(
+                            param,
+                            format!(
+                                "Default value of '{}' must be {} but got {}.",
+                                param.get_token_type(),
+                                param_type,
+                                value
+                            ),
+                        );
+                    }
+                }
+                match optional.rest && idx + 1 == params.len() {
+                    true => self.define(param, Type::Any),
+                    false => self.define(param, param_type),
+                }
+            }
+            // `return;` in an initializer returns `this`, whatever the annotation says.
+            let is_initializer =
+                self.current_class.is_some() && name.get_token_type().to_string() == "init";
+            self.results.push(match is_initializer {
+                true => Type::Any,
+                false => *signature.result,
+            });
+            self.check_stmts(body);
+            self.results.pop();
+            self.end_scope();
+        }
+
+        fn check_call(
+            &mut self,
+            callee: &str,
+            paren: &Token,
+            signature: &Signature,
+            args: &[Type],
+            named: bool,
+        ) {
+            // named arguments are matched to the parameters at runtime, only the positional
+            // ones before them are checked.
+            if !named && !signature.arity.accepts(args.len()) {
+                self.report(
+                    paren,
+                    format!(
+                        "Expected {} arguments but got {}.",
+                        signature.arity,
+                        args.len()
+                    ),
+                );
+                return;
+            }
+            let last = signature.params.len().saturating_sub(1);
+            for (idx, actual) in args.iter().enumerate() {
+                // every argument past the fixed parameters goes to the rest parameter.
+                let position = match signature.arity.max {
+                    None => idx.min(last),
+                    Some(_) => idx,
+                };
+                let Some(expected) = signature.params.get(position) else {
+                    break;
+                };
+                if !self.accepts(expected, actual) {
+                    self.report(
+                        paren,
+                        format!(
+                            "Argument {} of '{}' must be {} but got {}.",
+                            idx + 1,
+                            callee,
+                            expected,
+                            actual
+                        ),
+                    );
+                }
+            }
+        }
+
+        /// Type of a binary operation on operands of the given types, reporting ill-typed ones.
+        fn binary_type(&mut self, left: Type, operator: &Token, right: Type) -> Type {
+            match operator.get_token_type() {
+                TokenType::EqualEqual | TokenType::BangEqual => Type::Bool,
+                TokenType::Greater
+                | TokenType::GreaterEqual
+                | TokenType::Less
+                | TokenType::LessEqual => {
+                    self.expect_numbers(operator, &[&left, &right], "Operands must be numbers.");
+                    Type::Bool
+                }
+                TokenType::Ampersand
+                | TokenType::Pipe
+                | TokenType::Caret
+                | TokenType::LessLess
+                | TokenType::GreaterGreater => {
+                    self.expect_numbers(operator, &[&left, &right], "Operands must be integers.");
+                    Type::Number
+                }
+                TokenType::Plus => match (&left, &right) {
+                    (Type::Number, Type::Number) => Type::Number,
+                    (Type::String, Type::String | Type::Number | Type::Any)
+                    | (Type::Number | Type::Any, Type::String) => Type::String,
+                    (Type::Number | Type::Any, Type::Number | Type::Any) => Type::Any,
+                    _ => {
+                        self.report(
+                            operator,
+                            "Operands must be two numbers or two strings.".to_string(),
+                        );
+                        Type::Any
+                    }
+                },
+                _ => {
+                    self.expect_numbers(operator, &[&left, &right], "Operands must be numbers.");
+                    Type::Number
+                }
+            }
+        }
+
+        fn property_type(&mut self, object: Type, name: &Token) -> Type {
+            let property = name.get_token_type().to_string();
+            match object {
+                Type::Instance(class) => match self.field(&class, &property) {
+                    Some(field) => field,
+                    None => self
+                        .method(&class, &property)
+                        .map_or(Type::Any, |method| Type::Function(Some(method))),
+                },
+                Type::Any => Type::Any,
+                Type::String if property == "iterator" => Type::Any,
+                _ => {
+                    self.report(name, "Only instances have properties.".to_string());
+                    Type::Any
+                }
+            }
+        }
+
+        fn expect_numbers(&mut self, operator: &Token, operands: &[&Type], message: &str) {
+            if operands
+                .iter()
+                .any(|operand| !matches!(operand, Type::Any | Type::Number))
+            {
+                self.report(operator, message.to_string());
+            }
+        }
+    }
+
+    impl Visitor<Type> for Checker {
+        fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Type {
+            let left = self.type_of(left);
+            let right = self.type_of(right);
+            self.binary_type(left, operator, right)
+        }
+
+        fn visit_grouping_expr(&mut self, expression: &Expr) -> Type {
+            self.type_of(expression)
+        }
+
+        fn visit_literal_expr(&mut self, value: &LiteralValue) -> Type {
+            match value {
+                LiteralValue::Number(_) => Type::Number,
+                LiteralValue::Bool(_) => Type::Bool,
+                LiteralValue::String(_) => Type::String,
+                LiteralValue::Nil => Type::Nil,
+                LiteralValue::Callable(_) | LiteralValue::List(_) => Type::Any,
+            }
+        }
+
+        fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> Type {
+            let right = self.type_of(right);
+            match operator.get_token_type() {
+                TokenType::Minus => {
+                    self.expect_numbers(operator, &[&right], "Operand must be a number.");
+                    Type::Number
+                }
+                TokenType::Tilde => {
+                    self.expect_numbers(operator, &[&right], "Operand must be an integer.");
+                    Type::Number
+                }
+                _ => Type::Bool,
+            }
+        }
+
+        fn visit_variable_expr(&mut self, token: &Token) -> Type {
+            self.lookup(token)
+        }
+
+        fn visit_assign_expr(&mut self, token: &Token, expr: &Expr) -> Type {
+            let value = self.type_of(expr);
+            let declared = self.lookup(token);
+            if !self.accepts(&declared, &value) {
+                self.report(
+                    token,
+                    format!(
+                        "Can't assign {} to '{}' of type {}.",
+                        value,
+                        token.get_token_type(),
+                        declared
+                    ),
+                );
+            }
+            value
+        }
+
+        fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> Type {
+            let left = self.type_of(left);
+            let right = self.type_of(right);
+            match left == right {
+                true => left,
+                false
+                    if left == Type::Nil
+                        && operator.get_token_type() == TokenType::QuestionQuestion =>
+                {
+                    right
+                }
+                false => Type::Any,
+            }
+        }
+
+        fn visit_call_expr(
+            &mut self,
+            callee: &Expr,
+            paren: &Token,
+            arguments: &Vec<Expr>,
+            names: &Vec<Option<Token>>,
+        ) -> Type {
+            let callee_type = self.type_of(callee);
+            let mut args: Vec<Type> = arguments.iter().map(|arg| self.type_of(arg)).collect();
+            let named = names.iter().any(Option::is_some);
+            args.truncate(names.iter().take_while(|name| name.is_none()).count());
+            let callee_name = match callee {
+                Expr::Variable(name)
+                | Expr::Get(_, name)
+                | Expr::OptionalGet(_, name)
+                | Expr::Super(_, name) => name.get_token_type().to_string(),
+                _ => callee.to_string(),
+            };
+
+            match callee_type {
+                Type::Function(Some(signature)) => {
+                    self.check_call(&callee_name, paren, &signature, &args, named);
+                    *signature.result
+                }
+                Type::Class(class) => {
+                    let init = self.method(&class, "init").unwrap_or(Signature {
+                        params: Vec::new(),
+                        arity: Arity::exact(0),
+                        result: Box::new(Type::Any),
+                    });
+                    self.check_call(&callee_name, paren, &init, &args, named);
+                    Type::Instance(class)
+                }
+                Type::Any | Type::Function(None) => Type::Any,
+                // `obj?.method()` on a nil obj is never called.
+                Type::Nil if matches!(callee, Expr::OptionalGet(_, _)) => Type::Nil,
+                _ => {
+                    self.report(paren, "Can only call functions and classes".to_string());
+                    Type::Any
+                }
+            }
+        }
+
+        fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> Type {
+            let object = self.type_of(object);
+            self.property_type(object, name)
+        }
+
+        fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> Type {
+            let object = self.type_of(object);
+            let value = self.type_of(value);
+            match object {
+                Type::Instance(class) => {
+                    let property = name.get_token_type().to_string();
+                    if let Some(field) = self.field(&class, &property) {
+                        if !self.accepts(&field, &value) {
+                            self.report(
+                                name,
+                                format!(
+                                    "Can't assign {} to field '{}' of type {}.",
+                                    value, property, field
+                                ),
+                            );
+                        }
+                    }
+                }
+                Type::Any => {}
+                _ => self.report(name, "Only instances have fields.".to_string()),
+            }
+            value
+        }
+
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+            postfix: bool,
+        ) -> Type {
+            let old = self.type_of(target);
+            let value = self.type_of(value);
+            let errors = self.errors;
+            let new = match operator.update_operator() {
+                Some(binary_operator) => self.binary_type(old.clone(), &binary_operator, value),
+                None => Type::Any,
+            };
+            // an ill-typed operation was already reported, its result type means nothing.
+            if errors == self.errors && !self.accepts(&old, &new) {
+                self.report(
+                    operator,
+                    format!("Can't assign {} to '{}' of type {}.", new, target, old),
+                );
+            }
+            match postfix {
+                true => old,
+                false => new,
+            }
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            _question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> Type {
+            self.type_of(condition);
+            let then_type = self.type_of(then_expr);
+            let else_type = self.type_of(else_expr);
+            match then_type == else_type {
+                true => then_type,
+                false => Type::Any,
+            }
+        }
+
+        fn visit_optional_get_expr(&mut self, object: &Expr, name: &Token) -> Type {
+            match self.type_of(object) {
+                Type::Nil => Type::Nil,
+                object => self.property_type(object, name),
+            }
+        }
+
+        fn visit_index_expr(&mut self, object: &Expr, bracket: &Token, index: &Expr) -> Type {
+            let object = self.type_of(object);
+            let index = self.type_of(index);
+            self.expect_numbers(bracket, &[&index], "Index must be an integer.");
+            match object {
+                Type::String => Type::String,
+                Type::Any => Type::Any,
+                _ => {
+                    self.report(
+                        bracket,
+                        "Only lists and strings can be indexed.".to_string(),
+                    );
+                    Type::Any
+                }
+            }
+        }
+
+        fn visit_this_expr(&mut self, _keyword: &Token) -> Type {
+            match &self.current_class {
+                Some(class) => Type::Instance(class.clone()),
+                None => Type::Any,
+            }
+        }
+
+        fn visit_super_expr(&mut self, _keyword: &Token, method: &Token) -> Type {
+            let superclass = match &self.current_class {
+                Some(class) => self.superclass_of(class).cloned(),
+                None => None,
+            };
+            superclass
+                .and_then(|superclass| {
+                    self.method(&superclass, &method.get_token_type().to_string())
+                })
+                .map_or(Type::Any, |method| Type::Function(Some(method)))
+        }
+    }
+
+    /// Finds the names assigned anywhere in the program, in nested functions and classes too.
+    fn collect_assigned_names(stmts: &[Stmt], assigned: &mut HashSet<String>) {
+        for stmt in stmts {
+            match stmt {
+                Stmt::ExprStmt(expr)
+                | Stmt::PrintStmt(_, expr)
+                | Stmt::ReturnStmt(_, expr)
+                | Stmt::YieldStmt(_, expr)
+                | Stmt::VarStmt(_, _, expr)
+                | Stmt::ConstStmt(_, _, expr) => collect_assigned(expr, assigned),
+                Stmt::BlockStmt(stmts) => collect_assigned_names(stmts, assigned),
+                Stmt::Function(_, _, stmts, _, optional) => {
+                    for default in optional.defaults.iter().flatten() {
+                        collect_assigned(default, assigned);
+                    }
+                    collect_assigned_names(stmts, assigned)
+                }
+                Stmt::ClassStmt(_, _, _, methods, _) | Stmt::TraitStmt(_, methods) => {
+                    collect_assigned_names(methods, assigned)
+                }
+                Stmt::IfStmt(condition, then_stmt, else_stmt) => {
+                    collect_assigned(condition, assigned);
+                    collect_assigned_names(std::slice::from_ref(then_stmt), assigned);
+                    if let Some(else_stmt) = else_stmt {
+                        collect_assigned_names(std::slice::from_ref(else_stmt), assigned);
+                    }
+                }
+                Stmt::WhileStmt(condition, body) => {
+                    collect_assigned(condition, assigned);
+                    collect_assigned_names(std::slice::from_ref(body), assigned);
+                }
+                Stmt::MatchStmt(_, subject, cases, default) => {
+                    collect_assigned(subject, assigned);
+                    for case in cases {
+                        if let Some(guard) = &case.guard {
+                            collect_assigned(guard, assigned);
+                        }
+                        collect_assigned_names(std::slice::from_ref(&case.body), assigned);
+                    }
+                    if let Some(default) = default {
+                        collect_assigned_names(std::slice::from_ref(default), assigned);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Whether unguarded cases match both `true` and `false`, every bool is handled then.
+    fn covers_bools(cases: &[MatchCase]) -> bool {
+        let covers = |expected: bool| {
+            cases.iter().any(|case| {
+                case.guard.is_none()
+                    && case.patterns.iter().any(|pattern| match pattern {
+                        Pattern::Literal(_, LiteralValue::Bool(value)) => *value == expected,
+                        _ => false,
+                    })
+            })
+        };
+        covers(true) && covers(false)
+    }
+
+    fn collect_assigned(expr: &Expr, assigned: &mut HashSet<String>) {
+        match expr {
+            Expr::Assign(name, value) => {
+                assigned.insert(name.get_token_type().to_string());
+                collect_assigned(value, assigned);
+            }
+            Expr::Binary(left, _, right) | Expr::Logical(left, _, right) => {
+                collect_assigned(left, assigned);
+                collect_assigned(right, assigned);
+            }
+            Expr::Call(callee, _, arguments, _) => {
+                collect_assigned(callee, assigned);
+                arguments
+                    .iter()
+                    .for_each(|arg| collect_assigned(arg, assigned));
+            }
+            Expr::Grouping(expr)
+            | Expr::Unary(_, expr)
+            | Expr::Get(expr, _)
+            | Expr::OptionalGet(expr, _) => collect_assigned(expr, assigned),
+            Expr::Conditional(condition, _, then_expr, else_expr) => {
+                collect_assigned(condition, assigned);
+                collect_assigned(then_expr, assigned);
+                collect_assigned(else_expr, assigned);
+            }
+            Expr::Set(object, _, value) | Expr::Index(object, _, value) => {
+                collect_assigned(object, assigned);
+                collect_assigned(value, assigned);
+            }
+            Expr::Update(target, _, value, _) => {
+                if let Expr::Variable(name) = &**target {
+                    assigned.insert(name.get_token_type().to_string());
+                }
+                collect_assigned(target, assigned);
+                collect_assigned(value, assigned);
+            }
+            Expr::Literal(_) | Expr::Variable(_) | Expr::This(_) | Expr::Super(_, _) => {}
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::error_handling::error_handling::{finish_capture, start_capture};
+        use crate::parser::parser::Parser;
+        use crate::scanner::scan::Scanner;
+
+        /// Checks `source` and returns the reported messages.
+        fn check_source(source: &str) -> Vec<String> {
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            start_capture();
+            let result = Checker::new().check(&ast);
+            let errors = finish_capture().errors;
+            assert_eq!(result.is_err(), !errors.is_empty());
+            errors
+        }
+
+        fn assert_messages(source: &str, expected: &[&str]) {
+            let errors = check_source(source);
+            assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+            for (error, message) in errors.iter().zip(expected) {
+                assert!(
+                    error.contains(message),
+                    "{} does not contain {}",
+                    error,
+                    message
+                );
+            }
+        }
+
+        #[test]
+        fn unannotated_code_ok() {
+            assert_messages(
+                "var a = 1; a = \"s\"; print a + 1;\n\
+                 fun f(x) { return x; } print f(1) - f(2);\n\
+                 class A { init(v) { this.v = v; } } print A(1).v;",
+                &[],
+            );
+        }
+
+        #[test]
+        fn annotated_code_ok() {
+            assert_messages(
+                "class P { x: number; init(x: number) { this.x = x; } }\n\
+                 class Q < P { twice(): number { return this.x * 2; } }\n\
+                 fun f(a: string, p: P): string { return a + p.x; }\n\
+                 var q: P = Q(1); var n: number = nil; var s: string = f(\"a\", q);\n\
+                 var g: fun = f; print Q(2).twice() + 1;",
+                &[],
+            );
+        }
+
+        #[test]
+        fn report_mismatches_nok() {
+            assert_messages(
+                "print \"a\" - 1;\n\
+                 var x: number = \"one\";\n\
+                 fun f(a: number): string { return a; }\n\
+                 f(1, 2); f(\"a\");\n\
+                 class P { x: number; } P().x = true;\n\
+                 var p: P = 1; var u: Unknown;",
+                &[
+                    "Operands must be numbers.",
+                    "Can't assign string to 'x' of type number.",
+                    "Can't return number from a function returning string.",
+                    "Expected 1 arguments but got 2.",
+                    "Argument 1 of 'f' must be number but got string.",
+                    "Can't assign bool to field 'x' of type number.",
+                    "Can't assign number to 'p' of type P.",
+                    "Unknown type 'Unknown'.",
+                ],
+            );
+        }
+
+        #[test]
+        fn infer_only_never_assigned_variables_ok() {
+            assert_messages(
+                "var a = \"a\"; print a - 1;",
+                &["Operands must be numbers."],
+            );
+            assert_messages("var a = \"a\"; a = 2; print a - 1;", &[]);
+        }
+
+        #[test]
+        fn check_update_operators_nok() {
+            assert_messages(
+                "var n: number = 1; n += 2; n++; n %= 2; print ~n | 1 << 2;\n\
+                 var s: string = \"a\"; s += 1; s--; n += \"b\";\n\
+                 class C { x: number; } var c = C(); c.x -= 1; print ~\"c\";",
+                &[
+                    "Operands must be numbers.",
+                    "Can't assign string to 'n' of type number.",
+                    "Operand must be an integer.",
+                ],
+            );
+        }
+
+        #[test]
+        fn check_match_cases_nok() {
+            assert_messages(
+                "class P { x: number; } var p = P();\n\
+                 match (p) { case P q if q.x > 0 => print q.x - \"a\"; default => {} }",
+                &["Operands must be numbers."],
+            );
+        }
+
+        #[test]
+        fn check_optional_params_nok() {
+            assert_messages(
+                "fun f(a: number, b: string = \"b\", ...rest: number) { print rest[0]; }\n\
+                 f(1); f(1, \"c\", 2, 3); f(b: \"c\", a: 1); f(1, \"c\", \"d\"); f();\n\
+                 fun g(a: number = \"a\") {} print \"abc\"[1] - 1;",
+                &[
+                    "Argument 3 of 'f' must be number but got string.",
+                    "Expected at least 1 arguments but got 0.",
+                    "Default value of 'a' must be number but got string.",
+                    "Operands must be numbers.",
+                ],
+            );
+        }
+
+        #[test]
+        fn shadowed_class_ok() {
+            assert_messages(
+                "class A { m() { return 1; } } print A().m() - 1;\n\
+                 fun f() { class A { m(): string { return \"s\"; } } return A().m(); }\n\
+                 var a: A = A(); print a.m() - 1;",
+                &[],
+            );
+            assert_messages(
+                "class A { x: number; }\n\
+                 { class A { x: string; } var a: A = A(); a.x = \"s\"; }\n\
+                 var a: A = A(); a.x = \"s\";",
+                &["Can't assign string to field 'x' of type number."],
+            );
+        }
+
+        #[test]
+        fn class_declared_in_function_unknown_outside_nok() {
+            assert_messages(
+                "fun f() { class B {} var b: B = B(); } var b: B;",
+                &["Unknown type 'B'."],
+            );
+        }
+
+        #[test]
+        fn warn_match_without_default() {
+            let warnings = |source: &str| {
+                let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                    .parse()
+                    .unwrap();
+                start_capture();
+                Checker::new().check(&ast).unwrap();
+                finish_capture().warnings
+            };
+            assert_eq!(warnings("match (1) { case 1 => print 1; }").len(), 1);
+            assert!(warnings("match (1) { case 1 => print 1; default => {} }").is_empty());
+            assert!(warnings("match (1 > 2) { case true => {} case false => {} }").is_empty());
+            assert_eq!(
+                warnings("match (1 > 2) { case true => {} case false if 1 > 2 => {} }").len(),
+                1
+            );
+        }
+    }
+}
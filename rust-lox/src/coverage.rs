@@ -0,0 +1,399 @@
+pub mod coverage {
+    use crate::expr::expr::{Expr, Visitor};
+    use crate::scanner::scan::Token;
+    use crate::stmt::stmt::{LiteralValue, MatchCase, OptionalParams, Stmt, StmtVisitor};
+    use std::collections::BTreeMap;
+    use std::fs::File;
+    use std::io::prelude::*;
+    use std::path::Path;
+
+    /// Position(line, column) of the token a branch is attached to.
+    pub type BranchPosition = (u32, u32);
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct BranchCounts {
+        /// Condition was truthy / logical operator short-circuited.
+        pub taken: u64,
+        /// Condition was falsy / right operand of the logical operator was evaluated.
+        pub not_taken: u64,
+    }
+
+    /**
+     * Line and branch coverage of a single Lox script.
+     * Every statement line and branch point of the AST is registered up front,
+     * so that code which never runs is reported with a zero count.
+     */
+    #[derive(Clone, Debug, Default)]
+    pub struct Coverage {
+        lines: BTreeMap<u32, u64>,
+        branches: BTreeMap<BranchPosition, BranchCounts>,
+    }
+
+    impl Coverage {
+        pub fn new(ast: &Vec<Stmt>) -> Self {
+            let mut coverage = Self::default();
+            coverage.visit_block_stmt(ast);
+            coverage
+        }
+
+        pub fn hit_line(&mut self, line: u32) {
+            *self.lines.entry(line).or_default() += 1;
+        }
+
+        pub fn hit_branch(&mut self, position: BranchPosition, taken: bool) {
+            let counts = self.branches.entry(position).or_default();
+            if taken {
+                counts.taken += 1;
+            } else {
+                counts.not_taken += 1;
+            }
+        }
+
+        pub fn lines(&self) -> &BTreeMap<u32, u64> {
+            &self.lines
+        }
+
+        pub fn branches(&self) -> &BTreeMap<BranchPosition, BranchCounts> {
+            &self.branches
+        }
+
+        fn register_line(&mut self, line: Option<u32>) {
+            if let Some(line) = line {
+                self.lines.entry(line).or_default();
+            }
+        }
+
+        fn register_branch(&mut self, token: Option<&Token>) {
+            if let Some(token) = token {
+                self.branches
+                    .entry((token.get_line(), token.get_column()))
+                    .or_default();
+            }
+        }
+
+        /// Coverage in the lcov tracefile format, as consumed by genhtml and most CI tools.
+        pub fn lcov(&self, source_path: &str) -> String {
+            let mut lcov = format!("TN:\nSF:{}\n", source_path);
+
+            let mut block = 0;
+            let mut previous_line = 0;
+            let mut branches_hit = 0;
+            for ((line, _), counts) in &self.branches {
+                // lcov numbers the branch blocks per line.
+                block = if *line == previous_line { block + 1 } else { 0 };
+                previous_line = *line;
+
+                let evaluated = counts.taken + counts.not_taken > 0;
+                for (branch, count) in [(0, counts.taken), (1, counts.not_taken)] {
+                    if count > 0 {
+                        branches_hit += 1;
+                    }
+                    let taken = if evaluated {
+                        count.to_string()
+                    } else {
+                        String::from("-")
+                    };
+                    lcov.push_str(&format!("BRDA:{},{},{},{}\n", line, block, branch, taken));
+                }
+            }
+            lcov.push_str(&format!("BRF:{}\n", self.branches.len() * 2));
+            lcov.push_str(&format!("BRH:{}\n", branches_hit));
+
+            for (line, hits) in &self.lines {
+                lcov.push_str(&format!("DA:{},{}\n", line, hits));
+            }
+            lcov.push_str(&format!("LF:{}\n", self.lines.len()));
+            lcov.push_str(&format!(
+                "LH:{}\n",
+                self.lines.values().filter(|hits| **hits > 0).count()
+            ));
+            lcov.push_str("end_of_record\n");
+            lcov
+        }
+
+        /**
+         * Source listing prefixed with execution counts, gcov style:
+         * `-` marks lines without statements and `#####` lines which never ran.
+         */
+        pub fn annotated_source(&self, source: &str) -> String {
+            let mut listing = String::new();
+            for (idx, text) in source.lines().enumerate() {
+                let line = idx as u32 + 1;
+                let count = match self.lines.get(&line) {
+                    Some(0) => String::from("#####"),
+                    Some(hits) => hits.to_string(),
+                    None => String::from("-"),
+                };
+                listing.push_str(&format!("{:>9}:{:>5}: {}\n", count, line, text));
+
+                for ((_, column), counts) in self.branches.range((line, 0)..(line + 1, 0)) {
+                    listing.push_str(&format!(
+                        "{:>9} branch at col {}: taken {}, not taken {}\n",
+                        "", column, counts.taken, counts.not_taken
+                    ));
+                }
+            }
+            listing
+        }
+
+        /// Writes `lcov.info` and `<file name>.txt` with the annotated listing into `out_dir`.
+        pub fn write_reports(
+            &self,
+            source_path: &str,
+            source: &str,
+            out_dir: &str,
+        ) -> std::io::Result<()> {
+            std::fs::create_dir_all(out_dir)?;
+            let out_dir = Path::new(out_dir);
+
+            let absolute_path = match std::fs::canonicalize(source_path) {
+                Ok(path) => path.display().to_string(),
+                Err(_) => source_path.to_string(),
+            };
+            let mut lcov_file = File::create(out_dir.join("lcov.info"))?;
+            lcov_file.write_all(self.lcov(&absolute_path).as_bytes())?;
+
+            let file_name = match Path::new(source_path).file_name() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => String::from("source"),
+            };
+            let mut listing_file = File::create(out_dir.join(format!("{}.txt", file_name)))?;
+            listing_file.write_all(self.annotated_source(source).as_bytes())
+        }
+    }
+
+    impl StmtVisitor<()> for Coverage {
+        fn visit_expr_stmt(&mut self, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_print_stmt(&mut self, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_return_stmt(&mut self, _keyword: &Token, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_yield_stmt(&mut self, _keyword: &Token, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_var_stmt(&mut self, _token: &Token, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_const_stmt(&mut self, _token: &Token, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_block_stmt(&mut self, stmts: &Vec<Stmt>) {
+            for stmt in stmts {
+                self.register_line(stmt.line());
+                stmt.accept(self);
+            }
+        }
+
+        fn visit_class_stmt(
+            &mut self,
+            _name: &Token,
+            _superclass: &Option<Expr>,
+            _traits: &Vec<Expr>,
+            methods: &Vec<Stmt>,
+        ) {
+            for method in methods {
+                method.accept(self);
+            }
+        }
+
+        fn visit_trait_stmt(&mut self, _name: &Token, methods: &Vec<Stmt>) {
+            for method in methods {
+                method.accept(self);
+            }
+        }
+
+        fn visit_function_stmt(
+            &mut self,
+            _name: &Token,
+            _params: &Vec<Token>,
+            body: &Vec<Stmt>,
+            optional: &OptionalParams,
+        ) {
+            for default in optional.defaults.iter().flatten() {
+                default.accept(self);
+            }
+            self.visit_block_stmt(body);
+        }
+
+        fn visit_if_stmt(&mut self, expr: &Expr, stmt: &Stmt, else_stmt: &Option<Box<Stmt>>) {
+            self.register_branch(expr.first_token());
+            expr.accept(self);
+            self.register_line(stmt.line());
+            stmt.accept(self);
+            if let Some(else_stmt) = else_stmt {
+                self.register_line(else_stmt.line());
+                else_stmt.accept(self);
+            }
+        }
+
+        fn visit_while_stmt(&mut self, expr: &Expr, stmt: &Stmt) {
+            self.register_branch(expr.first_token());
+            expr.accept(self);
+            self.register_line(stmt.line());
+            stmt.accept(self);
+        }
+
+        fn visit_match_stmt(
+            &mut self,
+            _keyword: &Token,
+            subject: &Expr,
+            cases: &Vec<MatchCase>,
+            default: &Option<Box<Stmt>>,
+        ) {
+            subject.accept(self);
+            for case in cases {
+                // taken when the case runs, i.e. a pattern matched and the guard holds.
+                self.register_branch(Some(&case.keyword));
+                if let Some(guard) = &case.guard {
+                    guard.accept(self);
+                }
+                self.register_line(case.body.line());
+                case.body.accept(self);
+            }
+            if let Some(default) = default {
+                self.register_line(default.line());
+                default.accept(self);
+            }
+        }
+    }
+
+    impl Visitor<()> for Coverage {
+        fn visit_binary_expr(&mut self, left: &Expr, _operator: &Token, right: &Expr) {
+            left.accept(self);
+            right.accept(self);
+        }
+
+        fn visit_grouping_expr(&mut self, expression: &Expr) {
+            expression.accept(self);
+        }
+
+        fn visit_literal_expr(&mut self, _value: &LiteralValue) {}
+
+        fn visit_unary_expr(&mut self, _operator: &Token, right: &Expr) {
+            right.accept(self);
+        }
+
+        fn visit_variable_expr(&mut self, _token: &Token) {}
+
+        fn visit_assign_expr(&mut self, _token: &Token, expr: &Expr) {
+            expr.accept(self);
+        }
+
+        fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) {
+            self.register_branch(Some(operator));
+            left.accept(self);
+            right.accept(self);
+        }
+
+        fn visit_call_expr(
+            &mut self,
+            callee: &Expr,
+            _paren: &Token,
+            arguments: &Vec<Expr>,
+            _names: &Vec<Option<Token>>,
+        ) {
+            callee.accept(self);
+            for argument in arguments {
+                argument.accept(self);
+            }
+        }
+
+        fn visit_get_expr(&mut self, object: &Expr, _name: &Token) {
+            object.accept(self);
+        }
+
+        fn visit_set_expr(&mut self, object: &Expr, _name: &Token, value: &Expr) {
+            object.accept(self);
+            value.accept(self);
+        }
+
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            _operator: &Token,
+            value: &Expr,
+            _postfix: bool,
+        ) {
+            target.accept(self);
+            value.accept(self);
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) {
+            self.register_branch(Some(question));
+            condition.accept(self);
+            then_expr.accept(self);
+            else_expr.accept(self);
+        }
+
+        fn visit_optional_get_expr(&mut self, object: &Expr, _name: &Token) {
+            object.accept(self);
+        }
+
+        fn visit_index_expr(&mut self, object: &Expr, _bracket: &Token, index: &Expr) {
+            object.accept(self);
+            index.accept(self);
+        }
+
+        fn visit_this_expr(&mut self, _keyword: &Token) {}
+
+        fn visit_super_expr(&mut self, _keyword: &Token, _method: &Token) {}
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::interpreter::interpreter::Interpreter;
+        use crate::parser::parser::Parser;
+        use crate::resolver::resolver::Resolver;
+        use crate::scanner::scan::Scanner;
+
+        fn run_with_coverage(source: &str) -> Coverage {
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            let mut interpreter = Interpreter::new();
+            interpreter.coverage = Some(Coverage::new(&ast));
+            let mut resolver = Resolver::new(&mut interpreter);
+            resolver.resolve(&ast).unwrap();
+            resolver.interpreter.interpret(&ast).unwrap();
+            interpreter.coverage.unwrap()
+        }
+
+        #[test]
+        fn record_lines_and_branches_ok() {
+            let coverage = run_with_coverage("var a = 1;\nif (a > 2)\n  a = 0;\nvar b = a;");
+            assert_eq!(coverage.lines()[&1], 1);
+            assert_eq!(coverage.lines()[&3], 0);
+            assert_eq!(coverage.lines()[&4], 1);
+            assert_eq!(coverage.branches()[&(2, 5)].taken, 0);
+            assert_eq!(coverage.branches()[&(2, 5)].not_taken, 1);
+        }
+
+        #[test]
+        fn lcov_report_ok() {
+            let coverage = run_with_coverage("var a = false or true;");
+            let lcov = coverage.lcov("test.lox");
+            assert!(lcov.contains("SF:test.lox\n"));
+            assert!(lcov.contains("DA:1,1\n"));
+            assert!(lcov.contains("BRDA:1,0,0,0\nBRDA:1,0,1,1\n"));
+            assert!(lcov.contains("LF:1\nLH:1\n"));
+            assert!(lcov.ends_with("end_of_record\n"));
+        }
+    }
+}
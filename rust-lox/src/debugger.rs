@@ -0,0 +1,335 @@
+pub mod debugger {
+    use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::parser::parser::Parser;
+    use crate::scanner::scan::Scanner;
+    use crate::stmt::stmt::Stmt;
+    use rustc_hash::FxHashSet as HashSet;
+    use std::io::{self, BufRead, Write};
+    use std::rc::Rc;
+
+    const HELP: &str = "\
+commands:
+    c, continue        run until the next breakpoint
+    s, step            step into the next statement
+    n, next            step over calls made by the current statement
+    o, out             run until the current function returns
+    b, break <line>    set a breakpoint
+    d, delete <line>   remove a breakpoint
+    i, info            list breakpoints
+    l, list            show the source around the current line
+    e, env             print the environment chain
+    p, print <expr>    evaluate an expression in the paused scope
+    q, quit            stop the program
+    h, help            show this message";
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum StepMode {
+        /// Run until a breakpoint is hit.
+        Continue,
+        /// Pause at the very next statement, entering calls.
+        StepIn,
+        /// Pause at the next statement of the given call depth or an outer one.
+        StepOver(usize),
+        /// Pause at the next statement once the given call depth returned.
+        StepOut(usize),
+    }
+
+    /**
+     * Terminal driven debugger, the interpreter hands it every statement right
+     * before executing it and it decides whether to pause and open a prompt.
+     */
+    pub struct Debugger {
+        source_lines: Vec<String>,
+        breakpoints: HashSet<u32>,
+        mode: StepMode,
+        last_position: Option<(u32, usize)>,
+        current_line: u32,
+        input: Box<dyn BufRead>,
+        output: Box<dyn Write>,
+    }
+
+    impl Debugger {
+        pub fn new(source: &str, breakpoints: Vec<u32>) -> Self {
+            Self::with_io(
+                source,
+                breakpoints,
+                Box::new(io::BufReader::new(io::stdin())),
+                Box::new(io::stdout()),
+            )
+        }
+
+        pub fn with_io(
+            source: &str,
+            breakpoints: Vec<u32>,
+            input: Box<dyn BufRead>,
+            output: Box<dyn Write>,
+        ) -> Self {
+            // without any breakpoint there is nowhere to stop, pause before the first statement.
+            let mode = if breakpoints.is_empty() {
+                StepMode::StepIn
+            } else {
+                StepMode::Continue
+            };
+
+            Self {
+                source_lines: source.lines().map(String::from).collect(),
+                breakpoints: breakpoints.into_iter().collect(),
+                mode,
+                last_position: None,
+                current_line: 0,
+                input,
+                output,
+            }
+        }
+
+        pub fn on_statement(
+            &mut self,
+            interpreter: &mut Interpreter,
+            stmt: &Stmt,
+        ) -> Result<(), Error> {
+            // a block is never interesting on its own, its first statement is.
+            if let Stmt::BlockStmt(_) = stmt {
+                return Ok(());
+            }
+
+            let line = match stmt.line() {
+                Some(line) => line,
+                None => return Ok(()),
+            };
+            let depth = interpreter.call_depth;
+            let pause = self.should_pause(line, depth);
+            self.last_position = Some((line, depth));
+
+            if !pause {
+                return Ok(());
+            }
+
+            self.current_line = line;
+            self.show_line(line, true);
+            self.prompt(interpreter, depth)
+        }
+
+        /// A loop evaluates its condition again, its body is a new statement even on the same line.
+        pub fn on_loop_condition(&mut self) {
+            self.last_position = None;
+        }
+
+        fn should_pause(&self, line: u32, depth: usize) -> bool {
+            let stepping = match self.mode {
+                StepMode::Continue => false,
+                StepMode::StepIn => true,
+                StepMode::StepOver(step_depth) => depth <= step_depth,
+                StepMode::StepOut(step_depth) => depth < step_depth,
+            };
+
+            // several statements on the same line only trigger a breakpoint once.
+            stepping
+                || (self.breakpoints.contains(&line) && self.last_position != Some((line, depth)))
+        }
+
+        fn prompt(&mut self, interpreter: &mut Interpreter, depth: usize) -> Result<(), Error> {
+            loop {
+                let _ = write!(self.output, "(rlox-dbg) ");
+                let _ = self.output.flush();
+
+                let mut input = String::new();
+                match self.input.read_line(&mut input) {
+                    Ok(0) | Err(_) => {
+                        // nobody is left to answer the prompt, let the program finish.
+                        self.mode = StepMode::Continue;
+                        self.breakpoints.clear();
+                        return Ok(());
+                    }
+                    Ok(_) => (),
+                }
+
+                let input = input.trim();
+                let (command, argument) = match input.split_once(' ') {
+                    Some((command, argument)) => (command, argument.trim()),
+                    None => (input, ""),
+                };
+
+                match command {
+                    "c" | "continue" => {
+                        self.mode = StepMode::Continue;
+                        return Ok(());
+                    }
+                    "s" | "step" => {
+                        self.mode = StepMode::StepIn;
+                        return Ok(());
+                    }
+                    "n" | "next" => {
+                        self.mode = StepMode::StepOver(depth);
+                        return Ok(());
+                    }
+                    "o" | "out" => {
+                        self.mode = StepMode::StepOut(depth);
+                        return Ok(());
+                    }
+                    "b" | "break" => match argument.parse::<u32>() {
+                        Ok(line) => {
+                            self.breakpoints.insert(line);
+                            self.write_line(format!("Breakpoint set at line {}.", line));
+                        }
+                        Err(_) => self.write_line(format!("Invalid line number '{}'.", argument)),
+                    },
+                    "d" | "delete" => match argument.parse::<u32>() {
+                        Ok(line) => {
+                            if self.breakpoints.remove(&line) {
+                                self.write_line(format!("Breakpoint at line {} removed.", line));
+                            } else {
+                                self.write_line(format!("No breakpoint at line {}.", line));
+                            }
+                        }
+                        Err(_) => self.write_line(format!("Invalid line number '{}'.", argument)),
+                    },
+                    "i" | "info" => {
+                        let mut lines: Vec<u32> = self.breakpoints.iter().copied().collect();
+                        lines.sort();
+                        if lines.is_empty() {
+                            self.write_line(String::from("No breakpoints."));
+                        }
+                        for line in lines {
+                            self.write_line(format!("Breakpoint at line {}.", line));
+                        }
+                    }
+                    "l" | "list" => {
+                        let current = self.current_line;
+                        for line in current.saturating_sub(2).max(1)..=current + 2 {
+                            self.show_line(line, line == current);
+                        }
+                    }
+                    "e" | "env" => self.print_environment(interpreter),
+                    "p" | "print" => self.evaluate(interpreter, argument)?,
+                    "q" | "quit" => return Err(Error::Exit(0)),
+                    "h" | "help" => self.write_line(String::from(HELP)),
+                    "" => (),
+                    _ => self.write_line(format!(
+                        "Unknown command '{}', type 'help' for a list of commands.",
+                        command
+                    )),
+                }
+            }
+        }
+
+        fn show_line(&mut self, line: u32, is_current: bool) {
+            if let Some(text) = self.source_lines.get(line as usize - 1) {
+                let marker = if is_current { "->" } else { "  " };
+                let text = text.clone();
+                self.write_line(format!("{} {:>4} | {}", marker, line, text));
+            }
+        }
+
+        fn print_environment(&mut self, interpreter: &Interpreter) {
+            let mut environment = Some(Rc::clone(&interpreter.environment));
+            let mut depth = 0;
+
+            while let Some(current) = environment {
+                let scope = current.borrow();
+                let kind = if scope.enclosing.is_none() {
+                    "globals"
+                } else {
+                    "locals"
+                };
+                self.write_line(format!("#{} {}:", depth, kind));
+
+                let mut values: Vec<(&String, _)> = scope.values().iter().collect();
+                values.sort_by_key(|(name, _)| *name);
+                for (name, value) in values {
+                    self.write_line(format!("    {} = {}", name, value));
+                }
+
+                environment = scope.enclosing.clone();
+                depth += 1;
+            }
+        }
+
+        fn evaluate(&mut self, interpreter: &mut Interpreter, source: &str) -> Result<(), Error> {
+            let mut scanner = Scanner::new(source.to_string());
+            let mut parser = Parser::new(scanner.scan_tokens());
+
+            // parse and runtime errors are reported by the interpreter itself.
+            if let Ok(expr) = parser.parse_expression() {
+                match interpreter.evaluate_in_scope(&expr) {
+                    Ok(value) => self.write_line(format!("{}", value)),
+                    Err(Error::Exit(code)) => return Err(Error::Exit(code)),
+                    Err(_) => (),
+                }
+            }
+            Ok(())
+        }
+
+        fn write_line(&mut self, line: String) {
+            let _ = writeln!(self.output, "{}", line);
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::resolver::resolver::Resolver;
+        use std::cell::RefCell;
+        use std::io::Cursor;
+
+        #[derive(Clone, Default)]
+        struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+        impl Write for SharedBuffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        fn debug_source(source: &str, breakpoints: Vec<u32>, commands: &str) -> String {
+            let output = SharedBuffer::default();
+            let mut interpreter = Interpreter::new();
+            interpreter.debugger = Some(Debugger::with_io(
+                source,
+                breakpoints,
+                Box::new(Cursor::new(commands.to_string())),
+                Box::new(output.clone()),
+            ));
+
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            let mut resolver = Resolver::new(&mut interpreter);
+            resolver.resolve(&ast).unwrap();
+            resolver.interpreter.interpret(&ast).unwrap();
+
+            let bytes = output.0.borrow().clone();
+            String::from_utf8(bytes).unwrap()
+        }
+
+        #[test]
+        fn pause_at_breakpoint_and_evaluate_ok() {
+            let source = "fun add(a, b) {\n  var sum = a + b;\n  return sum;\n}\nadd(1, 2);";
+            let output = debug_source(source, vec![3], "p sum * 2\nc\n");
+            assert!(output.contains("->    3 |   return sum;"));
+            assert!(output.contains("6\n"));
+        }
+
+        #[test]
+        fn step_over_call_ok() {
+            let source = "fun f() {\n  var x = 1;\n}\nf();\nvar y = 2;";
+            let output = debug_source(source, vec![], "n\nn\nc\n");
+            assert!(output.contains("->    1 |"));
+            assert!(output.contains("->    4 |"));
+            assert!(output.contains("->    5 |"));
+            assert!(!output.contains("->    2 |"));
+        }
+
+        #[test]
+        fn breakpoint_in_one_line_loop_ok() {
+            let source = "var i = 0;\nwhile (i < 3) i = i + 1;";
+            let output = debug_source(source, vec![2], "p i\nc\np i\nc\np i\nc\np i\nc\n");
+            assert_eq!(output.matches("->    2 |").count(), 4);
+            assert!(output.contains("2\n"));
+        }
+    }
+}
@@ -11,6 +11,8 @@ pub mod environment {
     #[derive(Debug, PartialEq, Clone, Default)]
     pub struct Environment {
         values: HashMap<String, Rc<LiteralValue>>,
+        /// Declarations of the names bound with `const`, the resolver can't see every global.
+        constants: HashMap<String, Token>,
         pub enclosing: Option<Rc<RefCell<Environment>>>,
     }
 
@@ -18,6 +20,7 @@ pub mod environment {
         pub fn new(enclosing: Rc<RefCell<Environment>>) -> Self {
             Environment {
                 values: HashMap::default(),
+                constants: HashMap::default(),
                 enclosing: Some(enclosing),
             }
         }
@@ -25,19 +28,31 @@ pub mod environment {
         pub fn new_without_enclosing() -> Self {
             Environment {
                 values: HashMap::default(),
+                constants: HashMap::default(),
                 enclosing: None,
             }
         }
 
         pub fn define(&mut self, token: &Token, value: Rc<LiteralValue>) {
-            self.values
-                .insert(token.get_token_type().to_string().clone(), value);
+            let name = token.get_token_type().to_string();
+            self.constants.remove(&name);
+            self.values.insert(name, value);
+        }
+
+        pub fn define_const(&mut self, token: &Token, value: Rc<LiteralValue>) {
+            let name = token.get_token_type().to_string();
+            self.constants.insert(name.clone(), token.clone());
+            self.values.insert(name, value);
         }
 
         pub fn define_str(&mut self, token_str: &str, value: Rc<LiteralValue>) {
             self.values.insert(String::from(token_str), value);
         }
 
+        pub fn values(&self) -> &HashMap<String, Rc<LiteralValue>> {
+            &self.values
+        }
+
         pub fn is_defined(&self, token: &Token) -> bool {
             match self.values.get(&token.get_token_type().to_string()) {
                 Some(_) => true,
@@ -68,7 +83,10 @@ pub mod environment {
 
         pub fn assign(&mut self, token: &Token, value: Rc<LiteralValue>) -> Result<(), Error> {
             let token_name = token.get_token_type().to_string();
-            if self.values.contains_key(&token_name) {
+            if let Some(declaration) = self.constants.get(&token_name) {
+                report_constant_assignment(token, declaration);
+                Err(Error::LoxRuntimeError)
+            } else if self.values.contains_key(&token_name) {
                 self.values.insert(token_name, value);
                 Ok(())
             } else if let Some(enclosing) = &self.enclosing {
@@ -104,7 +122,8 @@ pub mod environment {
 
         pub fn assign_at(&mut self, distance: usize, token: &Token, value: Rc<LiteralValue>) {
             if distance == 0 {
-                self.define(token, value);
+                self.values
+                    .insert(token.get_token_type().to_string(), value);
             } else {
                 self.enclosing
                     .as_ref()
@@ -114,4 +133,23 @@ pub mod environment {
             }
         }
     }
+
+    /// Reports an assignment to a constant, pointing at both the assignment and the declaration.
+    pub fn report_constant_assignment(assignment: &Token, declaration: &Token) {
+        let name = assignment.get_token_type();
+        error(
+            assignment.get_line(),
+            assignment.get_column(),
+            format!("Error at '{}': Can't assign to constant '{}'.", name, name),
+            function_name!(),
+            Some(RLoxErrorType::RuntimeError),
+        );
+        error(
+            declaration.get_line(),
+            declaration.get_column(),
+            format!("Constant '{}' is declared here.", name),
+            function_name!(),
+            Some(RLoxErrorType::RuntimeError),
+        );
+    }
 }
@@ -1,8 +1,9 @@
 pub mod error_handling {
     use chrono;
     use colored::{Colorize, CustomColor};
-    use log::error;
+    use log::{error, warn};
     use log::{Level, Metadata, Record};
+    use std::cell::{Cell, RefCell};
     use std::fmt;
     use std::sync::{LazyLock, RwLock};
     pub static LOGGER: SimpleLogger = SimpleLogger;
@@ -10,6 +11,108 @@ pub mod error_handling {
     pub static IS_WASM: LazyLock<RwLock<bool>> = LazyLock::new(|| RwLock::new(false));
     pub static WASM_OUTPUT: LazyLock<RwLock<Vec<String>>> = LazyLock::new(|| RwLock::new(vec![]));
     pub static WASM_ERRORS: LazyLock<RwLock<Vec<String>>> = LazyLock::new(|| RwLock::new(vec![]));
+
+    /// Exit codes of the interpreter(sysexits), also expected from external interpreters.
+    pub const EXIT_USAGE: i32 = 64;
+    pub const EXIT_COMPILE_ERROR: i32 = 65;
+    pub const EXIT_RUNTIME_ERROR: i32 = 70;
+    pub const EXIT_IO_ERROR: i32 = 74;
+
+    thread_local! {
+        // per thread, so that several programs can be interpreted in parallel(test runner).
+        static CAPTURE: RefCell<Option<CapturedOutput>> = const { RefCell::new(None) };
+        static REPORTED: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Program output and reported errors of a captured run, instead of stdout and the logger.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct CapturedOutput {
+        pub output: Vec<String>,
+        pub errors: Vec<String>,
+        pub warnings: Vec<String>,
+    }
+
+    pub fn start_capture() {
+        CAPTURE.with(|capture| *capture.borrow_mut() = Some(CapturedOutput::default()));
+    }
+
+    pub fn finish_capture() -> CapturedOutput {
+        CAPTURE.with(|capture| capture.borrow_mut().take().unwrap_or_default())
+    }
+
+    /**
+     * Runs `f` with a capture of its own and returns what it captured. A capture the caller
+     * started(the test runner, the wasm playground) is put back afterwards, unlike a
+     * `start_capture`/`finish_capture` pair which would discard it.
+     */
+    pub fn with_capture<T>(f: impl FnOnce() -> T) -> (T, CapturedOutput) {
+        let outer = CAPTURE.with(|capture| capture.borrow_mut().replace(CapturedOutput::default()));
+        let result = f();
+        let captured = CAPTURE.with(|capture| {
+            let mut capture = capture.borrow_mut();
+            let captured = capture.take().unwrap_or_default();
+            *capture = outer;
+            captured
+        });
+        (result, captured)
+    }
+
+    /// Capture and error count of a thread, moved along when another thread takes over its work.
+    #[derive(Debug, Default)]
+    pub struct Reporting {
+        capture: Option<CapturedOutput>,
+        reported: usize,
+    }
+
+    /// Takes this thread's capture and error count, leaving it with neither.
+    pub fn take_reporting() -> Reporting {
+        Reporting {
+            capture: CAPTURE.with(|capture| capture.borrow_mut().take()),
+            reported: REPORTED.with(|reported| reported.replace(0)),
+        }
+    }
+
+    /// Continues capturing and counting errors on this thread where `take_reporting` left off.
+    pub fn set_reporting(reporting: Reporting) {
+        CAPTURE.with(|capture| *capture.borrow_mut() = reporting.capture);
+        REPORTED.with(|reported| reported.set(reporting.reported));
+    }
+
+    /// Number of errors reported since `start_capture`.
+    pub fn captured_error_count() -> usize {
+        CAPTURE.with(|capture| {
+            capture
+                .borrow()
+                .as_ref()
+                .map_or(0, |captured| captured.errors.len())
+        })
+    }
+
+    /// Number of errors reported on this thread, captured or not.
+    pub fn reported_error_count() -> usize {
+        REPORTED.with(Cell::get)
+    }
+
+    /// Emits one line of program output(print statements).
+    pub fn output(line: String) {
+        let captured = CAPTURE.with(|capture| match capture.borrow_mut().as_mut() {
+            Some(captured) => {
+                // a printed string may span several lines.
+                captured.output.extend(line.lines().map(String::from));
+                true
+            }
+            None => false,
+        });
+        if captured {
+            return;
+        }
+
+        println!("{}", line);
+        if *IS_WASM.read().unwrap() {
+            WASM_OUTPUT.write().unwrap().push(line);
+        }
+    }
+
     pub struct SimpleLogger;
 
     impl log::Log for SimpleLogger {
@@ -18,8 +121,9 @@ pub mod error_handling {
         }
 
         fn log(&self, record: &Record) {
+            // stdout is left to the program's own output.
             if self.enabled(record.metadata()) {
-                println!(
+                eprintln!(
                     "{} - {} - {}",
                     chrono::offset::Local::now().to_string().bright_green(),
                     match record.level() {
@@ -58,11 +162,34 @@ pub mod error_handling {
         .report();
     }
 
+    /// Reports a suspicious construct that doesn't prevent the program from running.
+    pub fn warning(line: u32, column: u32, message: String, location: Option<String>) {
+        let warning_msg = format!(
+            "[Warning] <{}> [line: {} & col: {}] msg: {}",
+            location.unwrap_or(String::from("UNKNOWN LOCATION")),
+            line,
+            column,
+            message
+        );
+
+        let captured = CAPTURE.with(|capture| match capture.borrow_mut().as_mut() {
+            Some(captured) => {
+                captured.warnings.push(warning_msg.clone());
+                true
+            }
+            None => false,
+        });
+        if !captured {
+            warn!("{}", warning_msg);
+        }
+    }
+
     #[derive(Debug, PartialEq)]
     pub enum RLoxErrorType {
         RuntimeError,
         ParseError,
         ScannerError,
+        TypeError,
     }
 
     impl fmt::Display for RLoxErrorType {
@@ -72,6 +199,7 @@ pub mod error_handling {
                 RLoxErrorType::RuntimeError => str = "RuntimeErorr",
                 RLoxErrorType::ParseError => str = "ParseError",
                 RLoxErrorType::ScannerError => str = "ScannerError",
+                RLoxErrorType::TypeError => str = "TypeError",
             }
             write!(f, "{}", str)
         }
@@ -87,10 +215,23 @@ pub mod error_handling {
 
     impl RLoxError {
         fn report(&self) {
+            REPORTED.with(|reported| reported.set(reported.get() + 1));
             let error_msg = format!(
                 "[{}] <{}> [line: {} & col: {}] msg: {}",
                 self.error_type, self.location, self.line, self.column, self.message
             );
+
+            let captured = CAPTURE.with(|capture| match capture.borrow_mut().as_mut() {
+                Some(captured) => {
+                    captured.errors.push(error_msg.clone());
+                    true
+                }
+                None => false,
+            });
+            if captured {
+                return;
+            }
+
             error!("{}", error_msg);
 
             if *IS_WASM.read().unwrap() == true {
@@ -0,0 +1,290 @@
+/*!
+ * Fibers, timers and the event loop running them one at a time once the main script finished.
+ *
+ * Tasks run one at a time in the order they are due, ties in the order they were scheduled, so a
+ * program always runs the same way. On the virtual clock, the default of an embedded interpreter,
+ * time jumps to the next due task instead of waiting for it.
+ */
+pub mod event_loop {
+    use crate::fiber::fiber::{self, Fiber, Suspension};
+    use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::rlox_callable::rlox_callable::{Arity, Callable, RLoxCallable, RLoxFunction};
+    use crate::stmt::stmt::LiteralValue;
+    use std::{fmt, rc::Rc, thread, time::Duration, time::Instant};
+
+    const DELAY_ERROR: &str = "Delay must be a non-negative number of milliseconds.";
+
+    #[derive(Debug)]
+    enum TimeSource {
+        /// Current time in milliseconds, only moved forward by the event loop.
+        Virtual(f64),
+        /// When the program started.
+        Real(Instant),
+    }
+
+    #[derive(Clone, Debug)]
+    struct Timer {
+        id: usize,
+        callback: RLoxFunction,
+        /// Milliseconds between two calls of a `setInterval` timer.
+        interval: Option<f64>,
+    }
+
+    #[derive(Debug)]
+    enum Task {
+        Fiber(Fiber),
+        Timer(Timer),
+    }
+
+    #[derive(Debug)]
+    struct Scheduled {
+        due: f64,
+        /// Order the task was scheduled in, among tasks due at the same time.
+        sequence: usize,
+        task: Task,
+    }
+
+    #[derive(Debug)]
+    pub struct EventLoop {
+        time: TimeSource,
+        queue: Vec<Scheduled>,
+        sequence: usize,
+        timers: usize,
+    }
+
+    impl Default for EventLoop {
+        fn default() -> Self {
+            Self::new(TimeSource::Virtual(0.0))
+        }
+    }
+
+    impl EventLoop {
+        fn new(time: TimeSource) -> Self {
+            Self {
+                time,
+                queue: Vec::new(),
+                sequence: 0,
+                timers: 0,
+            }
+        }
+
+        /// Event loop waiting for its tasks in real time.
+        pub fn real_time() -> Self {
+            Self::new(TimeSource::Real(Instant::now()))
+        }
+
+        /// Milliseconds since the program started, on the clock of the event loop.
+        pub fn now(&self) -> f64 {
+            match &self.time {
+                TimeSource::Virtual(now) => *now,
+                TimeSource::Real(start) => start.elapsed().as_secs_f64() * 1000.0,
+            }
+        }
+
+        fn schedule(&mut self, delay: f64, task: Task) {
+            self.sequence += 1;
+            self.queue.push(Scheduled {
+                due: self.now() + delay,
+                sequence: self.sequence,
+                task,
+            });
+        }
+
+        /// Runs `fiber` once the tasks already due ran.
+        pub fn spawn(&mut self, fiber: Fiber) {
+            self.schedule(0.0, Task::Fiber(fiber));
+        }
+
+        /// Calls `callback` in `delay` milliseconds, and every `delay` milliseconds after when `repeat`.
+        pub fn set_timer(&mut self, callback: RLoxFunction, delay: f64, repeat: bool) -> usize {
+            self.timers += 1;
+            let timer = Timer {
+                id: self.timers,
+                callback,
+                interval: repeat.then_some(delay),
+            };
+            self.schedule(delay, Task::Timer(timer));
+            self.timers
+        }
+
+        /// Unknown and already fired timers are ignored.
+        pub fn clear_timer(&mut self, id: usize) {
+            self.queue.retain(
+                |scheduled| !matches!(&scheduled.task, Task::Timer(timer) if timer.id == id),
+            );
+        }
+
+        /// Removes the task due first, once its time came.
+        fn next_task(&mut self) -> Option<Task> {
+            let (idx, _) = self.queue.iter().enumerate().min_by(|(_, a), (_, b)| {
+                a.due.total_cmp(&b.due).then(a.sequence.cmp(&b.sequence))
+            })?;
+            let scheduled = self.queue.remove(idx);
+            match &mut self.time {
+                TimeSource::Virtual(now) => *now = now.max(scheduled.due),
+                TimeSource::Real(start) => {
+                    let wait = scheduled.due - start.elapsed().as_secs_f64() * 1000.0;
+                    if wait > 0.0 {
+                        thread::sleep(Duration::from_secs_f64(wait / 1000.0));
+                    }
+                }
+            }
+            Some(scheduled.task)
+        }
+    }
+
+    /// Runs the spawned fibers and the timers until none is left, a runtime error stops the loop.
+    pub fn run(interpreter: &mut Interpreter) -> Result<(), Error> {
+        while let Some(task) = interpreter.event_loop.next_task() {
+            match task {
+                Task::Fiber(mut fiber) => match fiber.resume(interpreter)? {
+                    Some(Suspension::Sleep(delay)) => {
+                        interpreter.event_loop.schedule(delay, Task::Fiber(fiber))
+                    }
+                    Some(Suspension::Pass) => {
+                        interpreter.event_loop.schedule(0.0, Task::Fiber(fiber))
+                    }
+                    None => {}
+                },
+                Task::Timer(timer) => {
+                    // scheduled before the call, so the callback can clear its own interval.
+                    if let Some(interval) = timer.interval {
+                        interpreter
+                            .event_loop
+                            .schedule(interval, Task::Timer(timer.clone()));
+                    }
+                    timer.callback.call(interpreter, &mut Vec::new())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn delay(value: &LiteralValue) -> Option<f64> {
+        match value {
+            LiteralValue::Number(delay) if delay.is_finite() && *delay >= 0.0 => Some(*delay),
+            _ => None,
+        }
+    }
+
+    /// Natives of the event loop, `sleep` and `yieldFiber` suspend the fiber calling them.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum EventLoopNative {
+        Spawn,
+        YieldFiber,
+        Sleep,
+        SetTimeout,
+        SetInterval,
+        ClearTimer,
+        Now,
+    }
+
+    impl fmt::Display for EventLoopNative {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                EventLoopNative::Spawn => "spawn",
+                EventLoopNative::YieldFiber => "yieldFiber",
+                EventLoopNative::Sleep => "sleep",
+                EventLoopNative::SetTimeout => "setTimeout",
+                EventLoopNative::SetInterval => "setInterval",
+                EventLoopNative::ClearTimer => "clearTimer",
+                EventLoopNative::Now => "now",
+            };
+            write!(f, "{}", name)
+        }
+    }
+
+    impl EventLoopNative {
+        pub const ALL: [EventLoopNative; 7] = [
+            EventLoopNative::Spawn,
+            EventLoopNative::YieldFiber,
+            EventLoopNative::Sleep,
+            EventLoopNative::SetTimeout,
+            EventLoopNative::SetInterval,
+            EventLoopNative::ClearTimer,
+            EventLoopNative::Now,
+        ];
+
+        fn callback(
+            &self,
+            interpreter: &Interpreter,
+            value: &LiteralValue,
+        ) -> Result<RLoxFunction, Error> {
+            match value {
+                LiteralValue::Callable(Callable::Function(function))
+                    if function.arity().accepts(0) =>
+                {
+                    Ok(function.clone())
+                }
+                _ => Err(interpreter.native_error(format!(
+                    "{}() takes a function which can be called without arguments.",
+                    self
+                ))),
+            }
+        }
+    }
+
+    impl RLoxCallable for EventLoopNative {
+        fn arity(&self) -> Arity {
+            match self {
+                EventLoopNative::YieldFiber | EventLoopNative::Now => Arity::exact(0),
+                EventLoopNative::Spawn | EventLoopNative::Sleep | EventLoopNative::ClearTimer => {
+                    Arity::exact(1)
+                }
+                EventLoopNative::SetTimeout | EventLoopNative::SetInterval => Arity::exact(2),
+            }
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            match self {
+                EventLoopNative::Spawn => {
+                    let function = self.callback(interpreter, &args[0])?;
+                    let fiber = Fiber::spawn(function).map_err(|err| {
+                        interpreter.native_error(format!("Can't start a fiber: {}.", err))
+                    })?;
+                    interpreter.event_loop.spawn(fiber);
+                    Ok(Rc::new(LiteralValue::Nil))
+                }
+                EventLoopNative::SetTimeout | EventLoopNative::SetInterval => {
+                    let function = self.callback(interpreter, &args[0])?;
+                    let Some(delay) = delay(&args[1]) else {
+                        return Err(interpreter.native_error(DELAY_ERROR.to_string()));
+                    };
+                    let repeat = *self == EventLoopNative::SetInterval;
+                    let id = interpreter.event_loop.set_timer(function, delay, repeat);
+                    Ok(Rc::new(LiteralValue::Number(id as f64)))
+                }
+                EventLoopNative::ClearTimer => match &*args[0] {
+                    LiteralValue::Number(id) => {
+                        interpreter.event_loop.clear_timer(*id as usize);
+                        Ok(Rc::new(LiteralValue::Nil))
+                    }
+                    _ => Err(interpreter.native_error(String::from("Timer id must be a number."))),
+                },
+                EventLoopNative::Now => {
+                    Ok(Rc::new(LiteralValue::Number(interpreter.event_loop.now())))
+                }
+                EventLoopNative::Sleep | EventLoopNative::YieldFiber => {
+                    let suspension = match self {
+                        EventLoopNative::Sleep => match delay(&args[0]) {
+                            Some(delay) => Suspension::Sleep(delay),
+                            None => return Err(interpreter.native_error(DELAY_ERROR.to_string())),
+                        },
+                        _ => Suspension::Pass,
+                    };
+                    match fiber::suspend(suspension) {
+                        Some(()) => Ok(Rc::new(LiteralValue::Nil)),
+                        None => Err(interpreter.native_error(format!(
+                            "Can only call {}() in a spawned function.",
+                            self
+                        ))),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 pub mod expr {
 
-    use crate::scanner::scan::Token;
+    use crate::scanner::scan::{Token, TokenType};
     use crate::stmt::stmt::LiteralValue;
     use std::fmt;
 
@@ -12,9 +12,10 @@ pub mod expr {
     pub enum Expr {
         Binary(Box<Expr>, Token, Box<Expr>),
         Call(
-            Box<Expr>, /*callee*/
-            Token,     /*parent*/
-            Vec<Expr>, /*arguments*/
+            Box<Expr>,          /*callee*/
+            Token,              /*parent*/
+            Vec<Expr>,          /*arguments*/
+            Vec<Option<Token>>, /*argument names, `f(a: 1)`*/
         ),
         Logical(Box<Expr>, Token, Box<Expr>),
         Grouping(Box<Expr>),
@@ -30,6 +31,27 @@ pub mod expr {
         ),
         This(Token /*keyword*/),
         Super(Token /*keyword*/, Token /*method*/),
+        /// `a += b`, `++a` or `a--`, the target(a Variable or a Get) is only evaluated once.
+        Update(
+            Box<Expr>, /*target*/
+            Token,     /*operator*/
+            Box<Expr>, /*value, 1 for `++` and `--`*/
+            bool,      /*postfix, evaluates to the old value*/
+        ),
+        Conditional(
+            Box<Expr>, /*condition*/
+            Token,     /*question mark*/
+            Box<Expr>, /*then*/
+            Box<Expr>, /*else*/
+        ),
+        /// `obj?.name`, nil when obj is nil. Each `?.` only guards its own access or call.
+        OptionalGet(Box<Expr> /*obj*/, Token /*name*/),
+        /// `list[index]` or `string[index]`.
+        Index(
+            Box<Expr>, /*obj*/
+            Token,     /*bracket*/
+            Box<Expr>, /*index*/
+        ),
     }
 
     impl Expr {
@@ -42,13 +64,47 @@ pub mod expr {
                 Expr::Variable(_) => "Variable".to_string(),
                 Expr::Assign(_, _) => "Assign".to_string(),
                 Expr::Logical(_, _, _) => "Logical".to_string(),
-                Expr::Call(_, _, _) => "Call".to_string(),
+                Expr::Call(_, _, _, _) => "Call".to_string(),
                 Expr::Get(_, _) => "Get".to_string(),
                 Expr::Set(_, _, _) => "Set".to_string(),
                 Expr::This(_) => "This".to_string(),
                 Expr::Super(_, _) => "super".to_string(),
+                Expr::Update(_, _, _, _) => "Update".to_string(),
+                Expr::Conditional(_, _, _, _) => "Conditional".to_string(),
+                Expr::OptionalGet(_, _) => "OptionalGet".to_string(),
+                Expr::Index(_, _, _) => "Index".to_string(),
             }
         }
+
+        /// Left-most token of the expression, literals carry no token.
+        pub fn first_token(&self) -> Option<&Token> {
+            match self {
+                Expr::Binary(left, operator, _) | Expr::Logical(left, operator, _) => {
+                    left.first_token().or(Some(operator))
+                }
+                Expr::Call(callee, paren, _, _) => callee.first_token().or(Some(paren)),
+                Expr::Index(obj, bracket, _) => obj.first_token().or(Some(bracket)),
+                Expr::Grouping(expression) => expression.first_token(),
+                Expr::Literal(_) => None,
+                Expr::Unary(operator, _) => Some(operator),
+                Expr::Variable(token) | Expr::Assign(token, _) => Some(token),
+                Expr::Get(obj, name) | Expr::Set(obj, name, _) | Expr::OptionalGet(obj, name) => {
+                    obj.first_token().or(Some(name))
+                }
+                Expr::Conditional(condition, question, _, _) => {
+                    condition.first_token().or(Some(question))
+                }
+                Expr::This(keyword) | Expr::Super(keyword, _) => Some(keyword),
+                Expr::Update(target, operator, _, postfix) => match postfix {
+                    true => target.first_token(),
+                    false => Some(operator),
+                },
+            }
+        }
+
+        pub fn line(&self) -> Option<u32> {
+            self.first_token().map(|token| token.get_line())
+        }
     }
 
     impl fmt::Display for Expr {
@@ -71,9 +127,12 @@ pub mod expr {
                 Expr::Logical(left, operator, right) => {
                     write!(f, "({} {} {})", left, operator.token_type_value(), right)
                 }
-                Expr::Call(calle, _, args) => {
+                Expr::Call(calle, _, args, names) => {
                     let mut args_str = String::new();
-                    for arg in args {
+                    for (arg, name) in args.iter().zip(names) {
+                        if let Some(name) = name {
+                            args_str.push_str(&format!("{}: ", name.get_token_type()));
+                        }
                         args_str.push_str(&format!("{}, ", arg));
                     }
                     write!(f, "{}({})", calle, args_str)
@@ -90,6 +149,24 @@ pub mod expr {
                 Expr::Super(keyword, _) => {
                     write!(f, "{}", keyword.get_token_type())
                 }
+                Expr::Update(target, operator, value, postfix) => {
+                    match (operator.get_token_type(), postfix) {
+                        (TokenType::PlusPlus | TokenType::MinusMinus, true) => {
+                            write!(f, "{}{}", target, operator.get_token_type())
+                        }
+                        (TokenType::PlusPlus | TokenType::MinusMinus, false) => {
+                            write!(f, "{}{}", operator.get_token_type(), target)
+                        }
+                        _ => write!(f, "{} {} {}", target, operator.get_token_type(), value),
+                    }
+                }
+                Expr::Conditional(condition, _, then_expr, else_expr) => {
+                    write!(f, "{} ? {} : {}", condition, then_expr, else_expr)
+                }
+                Expr::OptionalGet(obj, name) => {
+                    write!(f, "{}?.{}", obj, name.get_token_type())
+                }
+                Expr::Index(obj, _, index) => write!(f, "{}[{}]", obj, index),
             }
         }
     }
@@ -108,13 +185,21 @@ pub mod expr {
                 Expr::Logical(left, operator, right) => {
                     visitor.visit_logical_expr(left, operator, right)
                 }
-                Expr::Call(callee, paren, arguments) => {
-                    visitor.visit_call_expr(callee, paren, arguments)
+                Expr::Call(callee, paren, arguments, names) => {
+                    visitor.visit_call_expr(callee, paren, arguments, names)
                 }
                 Expr::Get(obj, name) => visitor.visit_get_expr(obj, name),
                 Expr::Set(obj, name, value) => visitor.visit_set_expr(obj, name, value),
                 Expr::This(keyword) => visitor.visit_this_expr(keyword),
                 Expr::Super(keyword, method) => visitor.visit_super_expr(keyword, method),
+                Expr::Update(target, operator, value, postfix) => {
+                    visitor.visit_update_expr(target, operator, value, *postfix)
+                }
+                Expr::Conditional(condition, question, then_expr, else_expr) => {
+                    visitor.visit_conditional_expr(condition, question, then_expr, else_expr)
+                }
+                Expr::OptionalGet(obj, name) => visitor.visit_optional_get_expr(obj, name),
+                Expr::Index(obj, bracket, index) => visitor.visit_index_expr(obj, bracket, index),
             }
         }
     }
@@ -127,11 +212,33 @@ pub mod expr {
         fn visit_variable_expr(&mut self, token: &Token) -> T;
         fn visit_assign_expr(&mut self, token: &Token, expr: &Expr) -> T;
         fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> T;
-        fn visit_call_expr(&mut self, callee: &Expr, paren: &Token, arguments: &Vec<Expr>) -> T;
+        fn visit_call_expr(
+            &mut self,
+            callee: &Expr,
+            paren: &Token,
+            arguments: &Vec<Expr>,
+            names: &Vec<Option<Token>>,
+        ) -> T;
         fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> T;
         fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> T;
         fn visit_this_expr(&mut self, keyword: &Token) -> T;
         fn visit_super_expr(&mut self, keyword: &Token, method: &Token) -> T;
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+            postfix: bool,
+        ) -> T;
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> T;
+        fn visit_optional_get_expr(&mut self, object: &Expr, name: &Token) -> T;
+        fn visit_index_expr(&mut self, object: &Expr, bracket: &Token, index: &Expr) -> T;
     }
 
     impl Expr {
@@ -148,13 +255,21 @@ pub mod expr {
                 Expr::Logical(left, operator, right) => {
                     visitor.visit_logical_expr(left, operator, right)
                 }
-                Expr::Call(callee, paren, arguments) => {
-                    visitor.visit_call_expr(callee, paren, arguments)
+                Expr::Call(callee, paren, arguments, names) => {
+                    visitor.visit_call_expr(callee, paren, arguments, names)
                 }
                 Expr::Get(object, name) => visitor.visit_get_expr(object, name),
                 Expr::Set(object, name, value) => visitor.visit_set_expr(object, name, value),
                 Expr::This(keyword) => visitor.visit_this_expr(keyword),
                 Expr::Super(keyword, method) => visitor.visit_super_expr(keyword, method),
+                Expr::Update(target, operator, value, postfix) => {
+                    visitor.visit_update_expr(target, operator, value, *postfix)
+                }
+                Expr::Conditional(condition, question, then_expr, else_expr) => {
+                    visitor.visit_conditional_expr(condition, question, then_expr, else_expr)
+                }
+                Expr::OptionalGet(obj, name) => visitor.visit_optional_get_expr(obj, name),
+                Expr::Index(obj, bracket, index) => visitor.visit_index_expr(obj, bracket, index),
             }
         }
     }
@@ -193,9 +308,18 @@ pub mod expr {
             format!("({} {} {})", operator.token_type_value(), left, right)
         }
 
-        fn visit_call_expr(&mut self, callee: &Expr, _: &Token, arguments: &Vec<Expr>) -> String {
+        fn visit_call_expr(
+            &mut self,
+            callee: &Expr,
+            _: &Token,
+            arguments: &Vec<Expr>,
+            names: &Vec<Option<Token>>,
+        ) -> String {
             let mut args_str = String::new();
-            for arg in arguments {
+            for (arg, name) in arguments.iter().zip(names) {
+                if let Some(name) = name {
+                    args_str.push_str(&format!("{}: ", name.get_token_type()));
+                }
                 args_str.push_str(&format!("{}, ", arg.accept(self)));
             }
             format!("{}({})", callee.accept(self), args_str)
@@ -216,5 +340,42 @@ pub mod expr {
         fn visit_super_expr(&mut self, keyword: &Token, method: &Token) -> String {
             format!("{} {}", keyword.get_token_type(), method.get_token_type())
         }
+
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+            postfix: bool,
+        ) -> String {
+            let name = match postfix {
+                true => format!("postfix{}", operator.token_type_value()),
+                false => operator.token_type_value(),
+            };
+            format!("({} {} {})", name, target.accept(self), value.accept(self))
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            _question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> String {
+            format!(
+                "(? {} {} {})",
+                condition.accept(self),
+                then_expr.accept(self),
+                else_expr.accept(self)
+            )
+        }
+
+        fn visit_optional_get_expr(&mut self, object: &Expr, name: &Token) -> String {
+            format!("{}?.{}", object, name.get_token_type())
+        }
+
+        fn visit_index_expr(&mut self, object: &Expr, _: &Token, index: &Expr) -> String {
+            format!("{}[{}]", object.accept(self), index.accept(self))
+        }
     }
 }
@@ -0,0 +1,185 @@
+/*!
+ * Fibers, spawned functions the event loop runs by turns, each on a call stack of its own.
+ *
+ * The interpreter is a recursive tree walker, a fiber suspended in `sleep()` is somewhere deep in
+ * the native stack of its calls. Every fiber runs on a thread of its own for that, which only
+ * runs while the event loop waits for it: the interpreter is handed from one thread to the other
+ * and back, so one thread at a time uses it, as if the fibers ran on the event loop's thread.
+ */
+pub mod fiber {
+    use crate::error_handling::error_handling::{set_reporting, take_reporting, Reporting};
+    use crate::interpreter::interpreter::{CallStack, Error, Interpreter, INTERPRETER_STACK_SIZE};
+    use crate::rlox_callable::rlox_callable::{RLoxCallable, RLoxFunction};
+    use std::cell::RefCell;
+    use std::fmt;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+    use std::thread::{self, JoinHandle};
+
+    /// Why a fiber gave way to the other fibers and timers.
+    #[derive(Debug)]
+    pub enum Suspension {
+        /// `sleep(ms)`, the fiber resumes once that many milliseconds passed.
+        Sleep(f64),
+        /// `yieldFiber()`, the fiber resumes after the other ready ones ran.
+        Pass,
+    }
+
+    /**
+     * A value sent between the event loop and the thread of a fiber.
+     *
+     * SAFETY: the interpreter and the `Rc`s it holds aren't thread safe. They are only used by
+     * the thread which received them last, the other one blocks until it receives them back, so
+     * every use is ordered by the channels like the uses of a single thread.
+     */
+    struct Handoff<T>(T);
+
+    unsafe impl<T> Send for Handoff<T> {}
+
+    enum Resume {
+        Run(*mut Interpreter, Reporting),
+        /// The fiber is dropped before it finished, its thread unwinds.
+        Cancel,
+    }
+
+    enum Stopped {
+        Suspended(Suspension),
+        Finished(Result<(), Error>),
+    }
+
+    /// Channels of the fiber running on this thread.
+    struct Channels {
+        resume: Receiver<Handoff<Resume>>,
+        stopped: Sender<Handoff<(Stopped, Reporting)>>,
+    }
+
+    thread_local! {
+        static CURRENT: RefCell<Option<Channels>> = const { RefCell::new(None) };
+    }
+
+    /// Payload the thread of a cancelled fiber unwinds with.
+    struct Cancelled;
+
+    pub struct Fiber {
+        name: String,
+        resume: Sender<Handoff<Resume>>,
+        stopped: Receiver<Handoff<(Stopped, Reporting)>>,
+        thread: Option<JoinHandle<()>>,
+        stack: CallStack,
+    }
+
+    impl fmt::Debug for Fiber {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "<fiber {}>", self.name)
+        }
+    }
+
+    impl Fiber {
+        /// Fiber calling `function` without arguments once it is first resumed.
+        pub fn spawn(function: RLoxFunction) -> std::io::Result<Self> {
+            let name = function.profile_name();
+            let (resume, resume_receiver) = channel();
+            let (stopped_sender, stopped) = channel();
+            let function = Handoff(function);
+            let thread = thread::Builder::new()
+                .name(format!("fiber {}", name))
+                .stack_size(INTERPRETER_STACK_SIZE)
+                .spawn(move || {
+                    // moved as a whole, `function.0` alone isn't `Send`.
+                    let function = function;
+                    let channels = Channels {
+                        resume: resume_receiver,
+                        stopped: stopped_sender,
+                    };
+                    let Ok(Handoff(Resume::Run(interpreter, reporting))) = channels.resume.recv()
+                    else {
+                        return;
+                    };
+                    set_reporting(reporting);
+                    CURRENT.with(|current| *current.borrow_mut() = Some(channels));
+
+                    // SAFETY: see `Handoff`, the event loop waits until the fiber stops.
+                    let interpreter = unsafe { &mut *interpreter };
+                    let result = function.0.call(interpreter, &mut Vec::new()).map(|_| ());
+
+                    let channels = CURRENT.with(|current| current.borrow_mut().take());
+                    if let Some(channels) = channels {
+                        let stopped = (Stopped::Finished(result), take_reporting());
+                        let _ = channels.stopped.send(Handoff(stopped));
+                    }
+                })?;
+            Ok(Self {
+                name,
+                resume,
+                stopped,
+                thread: Some(thread),
+                stack: CallStack::default(),
+            })
+        }
+
+        /**
+         * Runs the fiber until it suspends, `None` once it finished. A runtime error propagates
+         * to the caller and ends the fiber too.
+         */
+        pub fn resume(
+            &mut self,
+            interpreter: &mut Interpreter,
+        ) -> Result<Option<Suspension>, Error> {
+            interpreter.enter_stack(&mut self.stack);
+            let run = Resume::Run(interpreter as *mut Interpreter, take_reporting());
+            let stopped = match self.resume.send(Handoff(run)) {
+                Ok(()) => self.stopped.recv().ok(),
+                Err(_) => None,
+            };
+            interpreter.leave_stack(&mut self.stack);
+
+            let Some(Handoff((stopped, reporting))) = stopped else {
+                // the thread panicked, the panic goes on here.
+                if let Some(Err(panic)) = self.thread.take().map(JoinHandle::join) {
+                    std::panic::resume_unwind(panic);
+                }
+                return Ok(None);
+            };
+            set_reporting(reporting);
+            match stopped {
+                Stopped::Suspended(suspension) => Ok(Some(suspension)),
+                Stopped::Finished(result) => {
+                    if let Some(thread) = self.thread.take() {
+                        let _ = thread.join();
+                    }
+                    result.map(|_| None)
+                }
+            }
+        }
+    }
+
+    impl Drop for Fiber {
+        fn drop(&mut self) {
+            if let Some(thread) = self.thread.take() {
+                let _ = self.resume.send(Handoff(Resume::Cancel));
+                let _ = thread.join();
+            }
+        }
+    }
+
+    /**
+     * Suspends the fiber running on this thread until the event loop resumes it, from however
+     * deep in its calls. `None` when no fiber runs on this thread.
+     */
+    pub fn suspend(suspension: Suspension) -> Option<()> {
+        let channels = CURRENT.with(|current| current.borrow_mut().take())?;
+        let stopped = (Stopped::Suspended(suspension), take_reporting());
+        let resumed = match channels.stopped.send(Handoff(stopped)) {
+            Ok(()) => channels.resume.recv(),
+            Err(_) => std::panic::resume_unwind(Box::new(Cancelled)),
+        };
+        match resumed {
+            Ok(Handoff(Resume::Run(_, reporting))) => {
+                set_reporting(reporting);
+                CURRENT.with(|current| *current.borrow_mut() = Some(channels));
+                Some(())
+            }
+            // the frames of the fiber's calls are dropped on the way up.
+            Ok(Handoff(Resume::Cancel)) | Err(_) => std::panic::resume_unwind(Box::new(Cancelled)),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+/*!
+ * File system natives, sandboxed to the directories the embedder or the command line allowed.
+ *
+ * Nothing is readable or writable by default. Every path is canonicalized, symbolic links included,
+ * before it is compared with the allowed roots, so `..` or a link can't escape them. Dangling links
+ * are refused, and files are opened without following a link swapped in after the check.
+ */
+pub mod file_system {
+    use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::rlox_callable::rlox_callable::{Arity, RLoxCallable};
+    use crate::stmt::stmt::LiteralValue;
+    use std::path::{Component, Path, PathBuf};
+    use std::{cell::RefCell, fmt, fs, io, rc::Rc};
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Access {
+        Read,
+        Write,
+    }
+
+    /// Directories, canonicalized, whose files Lox programs may read or write.
+    #[derive(Debug, Default)]
+    pub struct Sandbox {
+        read: Vec<PathBuf>,
+        write: Vec<PathBuf>,
+    }
+
+    impl Sandbox {
+        /// Allows reading everything below `dir`, which must exist.
+        pub fn allow_read(&mut self, dir: &str) -> io::Result<()> {
+            self.read.push(fs::canonicalize(dir)?);
+            Ok(())
+        }
+
+        /// Allows writing and removing everything below `dir`, which must exist.
+        pub fn allow_write(&mut self, dir: &str) -> io::Result<()> {
+            self.write.push(fs::canonicalize(dir)?);
+            Ok(())
+        }
+
+        /// Canonical form of `path`, which may not exist yet, `None` when it can't be told.
+        fn resolve(path: &Path) -> Option<PathBuf> {
+            let mut missing = Vec::new();
+            let mut existing = path;
+            loop {
+                let parent = if existing.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    existing
+                };
+                if let Ok(canonical) = fs::canonicalize(parent) {
+                    let mut resolved = canonical;
+                    // `..` after a missing directory can't be followed, it is refused instead.
+                    for component in missing.iter().rev() {
+                        match component {
+                            Component::Normal(name) => resolved.push(name),
+                            Component::CurDir => {}
+                            _ => return None,
+                        }
+                    }
+                    return Some(resolved);
+                }
+                // an entry that exists but can't be canonicalized is a dangling symbolic link,
+                // writing to it would create its target wherever it points.
+                match fs::symlink_metadata(parent) {
+                    Err(why) if why.kind() == io::ErrorKind::NotFound => {}
+                    _ => return None,
+                }
+                missing.push(existing.components().next_back()?);
+                existing = existing.parent()?;
+            }
+        }
+
+        fn check(&self, path: &str, access: Access) -> Result<PathBuf, String> {
+            let roots = match access {
+                Access::Read => &self.read,
+                Access::Write => &self.write,
+            };
+            match Sandbox::resolve(Path::new(path)) {
+                Some(resolved) if roots.iter().any(|root| resolved.starts_with(root)) => {
+                    Ok(resolved)
+                }
+                _ => Err(format!(
+                    "{} access to '{}' is not allowed.",
+                    match access {
+                        Access::Read => "Read",
+                        Access::Write => "Write",
+                    },
+                    path
+                )),
+            }
+        }
+    }
+
+    /// Natives reading and writing files, each checking the sandbox of the interpreter first.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum FileNative {
+        ReadFile,
+        WriteFile,
+        AppendFile,
+        ListDir,
+        Exists,
+        RemoveFile,
+    }
+
+    impl fmt::Display for FileNative {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                FileNative::ReadFile => "readFile",
+                FileNative::WriteFile => "writeFile",
+                FileNative::AppendFile => "appendFile",
+                FileNative::ListDir => "listDir",
+                FileNative::Exists => "exists",
+                FileNative::RemoveFile => "removeFile",
+            };
+            write!(f, "{}", name)
+        }
+    }
+
+    impl FileNative {
+        pub const ALL: [FileNative; 6] = [
+            FileNative::ReadFile,
+            FileNative::WriteFile,
+            FileNative::AppendFile,
+            FileNative::ListDir,
+            FileNative::Exists,
+            FileNative::RemoveFile,
+        ];
+
+        fn access(&self) -> Access {
+            match self {
+                FileNative::ReadFile | FileNative::ListDir | FileNative::Exists => Access::Read,
+                FileNative::WriteFile | FileNative::AppendFile | FileNative::RemoveFile => {
+                    Access::Write
+                }
+            }
+        }
+
+        fn string<'a>(
+            &self,
+            interpreter: &Interpreter,
+            value: &'a LiteralValue,
+            what: &str,
+        ) -> Result<&'a str, Error> {
+            match value {
+                LiteralValue::String(str) => Ok(str),
+                _ => Err(interpreter.native_error(format!("{}() takes {}.", self, what))),
+            }
+        }
+
+        /// `path` is the resolved one, a symbolic link in it was created after the sandbox check.
+        fn run(&self, path: &Path, content: &str) -> io::Result<LiteralValue> {
+            match self {
+                FileNative::ReadFile => Ok(LiteralValue::String(fs::read_to_string(path)?)),
+                FileNative::WriteFile | FileNative::AppendFile => {
+                    let mut options = fs::OpenOptions::new();
+                    options.create(true);
+                    #[cfg(unix)]
+                    std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_NOFOLLOW);
+                    if *self == FileNative::AppendFile {
+                        options.append(true);
+                    } else {
+                        options.write(true).truncate(true);
+                    }
+                    io::Write::write_all(&mut options.open(path)?, content.as_bytes())?;
+                    Ok(LiteralValue::Nil)
+                }
+                FileNative::ListDir => {
+                    let mut names = fs::read_dir(path)?
+                        .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+                        .collect::<io::Result<Vec<String>>>()?;
+                    names.sort();
+                    Ok(LiteralValue::List(Rc::new(RefCell::new(
+                        names
+                            .into_iter()
+                            .map(|name| Rc::new(LiteralValue::String(name)))
+                            .collect(),
+                    ))))
+                }
+                FileNative::Exists => Ok(LiteralValue::Bool(path.exists())),
+                FileNative::RemoveFile => {
+                    fs::remove_file(path)?;
+                    Ok(LiteralValue::Nil)
+                }
+            }
+        }
+    }
+
+    impl RLoxCallable for FileNative {
+        fn arity(&self) -> Arity {
+            match self {
+                FileNative::WriteFile | FileNative::AppendFile => Arity::exact(2),
+                _ => Arity::exact(1),
+            }
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let path = self.string(interpreter, &args[0], "a string path")?;
+            let content = match args.get(1) {
+                Some(content) => self.string(interpreter, content, "a string as content")?,
+                None => "",
+            };
+            let resolved = interpreter
+                .sandbox
+                .check(path, self.access())
+                .map_err(|message| interpreter.native_error(message))?;
+            match self.run(&resolved, content) {
+                Ok(value) => Ok(Rc::new(value)),
+                Err(why) => Err(interpreter.native_error(format!(
+                    "{}() failed on '{}': {}.",
+                    self,
+                    path,
+                    why.kind()
+                ))),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::parser::parser::Parser;
+        use crate::resolver::resolver::Resolver;
+        use crate::scanner::scan::Scanner;
+
+        fn temp_dir(name: &str) -> PathBuf {
+            let dir = std::env::temp_dir().join(format!("rlox-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(dir.join("data")).unwrap();
+            dir
+        }
+
+        fn run(source: &str, sandbox: Sandbox) -> Result<(), Error> {
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            let mut interpreter = Interpreter::new();
+            interpreter.sandbox = sandbox;
+            let mut resolver = Resolver::new(&mut interpreter);
+            resolver.resolve(&ast).unwrap();
+            resolver.interpreter.interpret(&ast).map(|_| ())
+        }
+
+        #[test]
+        fn read_and_write_allowed_dirs_ok() {
+            let dir = temp_dir("read-write");
+            let data = dir.join("data");
+            let mut sandbox = Sandbox::default();
+            sandbox.allow_read(data.to_str().unwrap()).unwrap();
+            sandbox.allow_write(data.to_str().unwrap()).unwrap();
+            let source = format!(
+                r#"
+                var dir = "{0}";
+                writeFile(dir + "/a.txt", "one");
+                appendFile(dir + "/a.txt", " two");
+                writeFile(dir + "/b.txt", readFile(dir + "/a.txt") + " " + str(len(listDir(dir))));
+                if (exists(dir + "/c.txt")) writeFile(dir + "/c.txt", "wrong");
+                appendFile(dir + "/c.txt", "created");
+                removeFile(dir + "/c.txt");
+                "#,
+                data.display()
+            );
+            assert_eq!(run(&source, sandbox), Ok(()));
+            assert_eq!(fs::read_to_string(data.join("b.txt")).unwrap(), "one two 1");
+            assert!(!data.join("c.txt").exists());
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn deny_by_default_ok() {
+            let dir = temp_dir("deny");
+            fs::write(dir.join("data/a.txt"), "secret").unwrap();
+            let path = dir.join("data/a.txt");
+            let read = format!(r#"readFile("{}");"#, path.display());
+            assert_eq!(run(&read, Sandbox::default()), Err(Error::LoxRuntimeError));
+
+            // reading doesn't allow writing.
+            let mut sandbox = Sandbox::default();
+            sandbox.allow_read(dir.to_str().unwrap()).unwrap();
+            let remove = format!(r#"removeFile("{}");"#, path.display());
+            assert_eq!(run(&remove, sandbox), Err(Error::LoxRuntimeError));
+            assert!(path.exists());
+            fs::remove_dir_all(dir).unwrap();
+        }
+
+        #[test]
+        fn escape_allowed_dir_err() {
+            let dir = temp_dir("escape");
+            fs::write(dir.join("secret.txt"), "secret").unwrap();
+            let mut sandbox = Sandbox::default();
+            sandbox
+                .allow_read(dir.join("data").to_str().unwrap())
+                .unwrap();
+
+            let inside = dir.join("data/new.txt");
+            assert!(sandbox
+                .check(inside.to_str().unwrap(), Access::Read)
+                .is_ok());
+            let dotdot = dir.join("data/../secret.txt");
+            assert!(sandbox
+                .check(dotdot.to_str().unwrap(), Access::Read)
+                .is_err());
+            let missing = dir.join("data/missing/../../secret.txt");
+            assert!(sandbox
+                .check(missing.to_str().unwrap(), Access::Read)
+                .is_err());
+            #[cfg(unix)]
+            {
+                std::os::unix::fs::symlink(dir.join("secret.txt"), dir.join("data/link")).unwrap();
+                let link = dir.join("data/link");
+                assert!(sandbox.check(link.to_str().unwrap(), Access::Read).is_err());
+
+                std::os::unix::fs::symlink(dir.join("outside.txt"), dir.join("data/dangling"))
+                    .unwrap();
+                let dangling = dir.join("data/dangling");
+                assert!(sandbox
+                    .check(dangling.to_str().unwrap(), Access::Read)
+                    .is_err());
+                let mut sandbox = Sandbox::default();
+                sandbox
+                    .allow_write(dir.join("data").to_str().unwrap())
+                    .unwrap();
+                let write = format!(r#"writeFile("{}", "escaped");"#, dangling.display());
+                assert_eq!(run(&write, sandbox), Err(Error::LoxRuntimeError));
+                assert!(!dir.join("outside.txt").exists());
+            }
+            fs::remove_dir_all(dir).unwrap();
+        }
+    }
+}
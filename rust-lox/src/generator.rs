@@ -0,0 +1,282 @@
+/*!
+ * Generators, the value a call to a function containing `yield` returns, and the coroutine
+ * they run their body with.
+ *
+ * The interpreter is a recursive tree walker, so a function body can't be left in the middle
+ * of `execute_block` and entered again later. A coroutine runs the body itself instead, keeping
+ * the statements it is in the middle of as an explicit stack of frames: blocks, `if`, `while`
+ * and `match` statements containing a `yield` are walked frame by frame, every other statement
+ * is executed by the interpreter as usual.
+ */
+pub mod generator {
+    use crate::environment::environment::Environment;
+    use crate::error_handling::error_handling::{error, RLoxErrorType};
+    use crate::expr::expr::Expr;
+    use crate::function_name;
+    use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::scanner::scan::Token;
+    use crate::stmt::stmt::{LiteralValue, Stmt};
+    use std::{cell::RefCell, fmt, rc::Rc};
+
+    /// A statement list the coroutine is in the middle of, innermost frame last.
+    #[derive(Debug)]
+    enum Frame {
+        /// Statements run one after the other, `next` is the index of the next one to run.
+        Stmts {
+            stmts: Rc<[Stmt]>,
+            next: usize,
+            env: Rc<RefCell<Environment>>,
+        },
+        /// A `while` loop, its condition is evaluated again every time the body completes.
+        Loop {
+            condition: Expr,
+            body: Rc<[Stmt]>,
+            env: Rc<RefCell<Environment>>,
+        },
+    }
+
+    /// A function body which stops at its `yield`s and later resumes from there.
+    #[derive(Debug, Default)]
+    pub struct Coroutine {
+        name: String,
+        frames: Vec<Frame>,
+    }
+
+    impl Coroutine {
+        /// Coroutine running `body` in `env`, the environment holding the call's arguments.
+        pub fn new(name: String, body: &[Stmt], env: Rc<RefCell<Environment>>) -> Self {
+            Self {
+                name,
+                frames: vec![Frame::Stmts {
+                    stmts: Rc::from(body),
+                    next: 0,
+                    env,
+                }],
+            }
+        }
+
+        pub fn name(&self) -> &str {
+            &self.name
+        }
+
+        /**
+         * Runs the body until its next `yield`, `None` once it completes or returns.
+         * A runtime error propagates to the caller and ends the coroutine too.
+         */
+        pub fn resume(
+            &mut self,
+            interpreter: &mut Interpreter,
+        ) -> Result<Option<Rc<LiteralValue>>, Error> {
+            if let Some(profiler) = interpreter.profiler.as_mut() {
+                profiler.enter(self.name.clone());
+            }
+            interpreter.call_depth += 1;
+            let result = self.run(interpreter);
+            interpreter.call_depth -= 1;
+            if let Some(profiler) = interpreter.profiler.as_mut() {
+                profiler.exit();
+            }
+
+            match result {
+                Ok(Some(value)) => Ok(Some(value)),
+                Ok(None) | Err(Error::Return(_)) => {
+                    self.frames.clear();
+                    Ok(None)
+                }
+                Err(err) => {
+                    self.frames.clear();
+                    Err(err)
+                }
+            }
+        }
+
+        fn run(
+            &mut self,
+            interpreter: &mut Interpreter,
+        ) -> Result<Option<Rc<LiteralValue>>, Error> {
+            while let Some(frame) = self.frames.last_mut() {
+                let (stmts, idx, env) = match frame {
+                    Frame::Stmts { stmts, next, env } => {
+                        if *next == stmts.len() {
+                            self.frames.pop();
+                            continue;
+                        }
+                        *next += 1;
+                        (Rc::clone(stmts), *next - 1, Rc::clone(env))
+                    }
+                    Frame::Loop {
+                        condition,
+                        body,
+                        env,
+                    } => {
+                        let (body, env) = (Rc::clone(body), Rc::clone(env));
+                        let holds = interpreter.evaluate_in(condition, &env)?;
+                        let holds = Interpreter::is_truthy_lval(&holds);
+                        interpreter.record_branch(condition.first_token(), holds);
+                        interpreter.loop_condition();
+                        match holds {
+                            true => self.frames.push(Frame::Stmts {
+                                stmts: body,
+                                next: 0,
+                                env,
+                            }),
+                            false => {
+                                self.frames.pop();
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                let stmt = &stmts[idx];
+                if !stmt.contains_yield() {
+                    interpreter.execute_in(std::slice::from_ref(stmt), env)?;
+                    continue;
+                }
+                interpreter.before_execute(stmt)?;
+                match stmt {
+                    Stmt::YieldStmt(_, value) => {
+                        return interpreter.evaluate_in(value, &env).map(Some);
+                    }
+                    Stmt::BlockStmt(block) => self.frames.push(Frame::Stmts {
+                        stmts: Rc::from(block.as_slice()),
+                        next: 0,
+                        env: Rc::new(RefCell::new(Environment::new(env))),
+                    }),
+                    Stmt::IfStmt(condition, then_stmt, else_stmt) => {
+                        let holds = interpreter.evaluate_in(condition, &env)?;
+                        let holds = Interpreter::is_truthy_lval(&holds);
+                        interpreter.record_branch(condition.first_token(), holds);
+                        let taken = match holds {
+                            true => Some(&**then_stmt),
+                            false => else_stmt.as_deref(),
+                        };
+                        if let Some(taken) = taken {
+                            self.frames.push(Frame::Stmts {
+                                stmts: Rc::from([taken.clone()]),
+                                next: 0,
+                                env,
+                            });
+                        }
+                    }
+                    Stmt::WhileStmt(condition, body) => self.frames.push(Frame::Loop {
+                        condition: condition.clone(),
+                        body: Rc::from([(**body).clone()]),
+                        env,
+                    }),
+                    Stmt::MatchStmt(_, subject, cases, default) => {
+                        let previous = std::mem::replace(&mut interpreter.environment, env);
+                        let selected = interpreter.select_case(subject, cases, default);
+                        interpreter.environment = previous;
+                        if let Some((body, env)) = selected? {
+                            self.frames.push(Frame::Stmts {
+                                stmts: Rc::from([body.clone()]),
+                                next: 0,
+                                env,
+                            });
+                        }
+                    }
+                    _ => unreachable!("only statements containing a yield are walked"),
+                }
+            }
+            Ok(None)
+        }
+    }
+
+    #[derive(Debug)]
+    struct GeneratorState {
+        coroutine: Coroutine,
+        /// Value `hasNext()` already resumed the generator for, returned by the next `next()`.
+        peeked: Option<Rc<LiteralValue>>,
+        running: bool,
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Generator {
+        state: Rc<RefCell<GeneratorState>>,
+    }
+
+    impl PartialEq for Generator {
+        fn eq(&self, other: &Self) -> bool {
+            Rc::ptr_eq(&self.state, &other.state)
+        }
+    }
+
+    impl fmt::Display for Generator {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "<generator {}>", self.state.borrow().coroutine.name())
+        }
+    }
+
+    impl Generator {
+        /// Generator running `body` in `env`, the environment holding the call's arguments.
+        pub fn new(name: String, body: &[Stmt], env: Rc<RefCell<Environment>>) -> Self {
+            Self {
+                state: Rc::new(RefCell::new(GeneratorState {
+                    coroutine: Coroutine::new(name, body, env),
+                    peeked: None,
+                    running: false,
+                })),
+            }
+        }
+
+        /// Next yielded value, nil once the generator is done.
+        pub fn next(
+            &self,
+            interpreter: &mut Interpreter,
+            token: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            if let Some(value) = self.state.borrow_mut().peeked.take() {
+                return Ok(value);
+            }
+            Ok(self
+                .resume(interpreter, token)?
+                .unwrap_or_else(|| Rc::new(LiteralValue::Nil)))
+        }
+
+        /// Whether the generator yields another value, running it up to that `yield`.
+        pub fn has_next(
+            &self,
+            interpreter: &mut Interpreter,
+            token: &Token,
+        ) -> Result<bool, Error> {
+            if self.state.borrow().peeked.is_some() {
+                return Ok(true);
+            }
+            let value = self.resume(interpreter, token)?;
+            let has_next = value.is_some();
+            self.state.borrow_mut().peeked = value;
+            Ok(has_next)
+        }
+
+        /// Runs the body until the next `yield`, `None` when it completes or returns.
+        fn resume(
+            &self,
+            interpreter: &mut Interpreter,
+            token: &Token,
+        ) -> Result<Option<Rc<LiteralValue>>, Error> {
+            // the coroutine is taken out, the body may use the generator while it runs.
+            let mut coroutine = {
+                let mut state = self.state.borrow_mut();
+                if state.running {
+                    error(
+                        token.get_line(),
+                        token.get_column(),
+                        String::from("Generator is already running."),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    return Err(Error::LoxRuntimeError);
+                }
+                state.running = true;
+                std::mem::take(&mut state.coroutine)
+            };
+            let result = coroutine.resume(interpreter);
+
+            let mut state = self.state.borrow_mut();
+            state.running = false;
+            state.coroutine = coroutine;
+            result
+        }
+    }
+}
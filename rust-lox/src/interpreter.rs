@@ -1,90 +1,189 @@
 pub mod interpreter {
 
+    use crate::coverage::coverage::Coverage;
+    use crate::debugger::debugger::Debugger;
     use crate::environment::environment::Environment;
-    use crate::error_handling::error_handling::{error, RLoxErrorType, IS_WASM, WASM_OUTPUT};
+    use crate::error_handling::error_handling::{error, output, RLoxErrorType};
+    use crate::event_loop::event_loop::{self, EventLoop};
     use crate::expr::expr::{Expr, Visitor};
+    use crate::file_system::file_system::Sandbox;
     use crate::function_name;
+    use crate::iterator::iterator::NativeMethod;
+    use crate::profiler::profiler::{DetachedFrames, Profiler};
     use crate::rlox_callable::rlox_callable::{
-        Callable, Clock, RLoxCallable, RLoxClass, RLoxFunction, UnixTClock,
+        natives, Arity, CallArguments, Callable, RLoxCallable, RLoxClass, RLoxFunction,
+        RLoxInstance, RLoxTrait,
     };
     use crate::scanner::scan::{Token, TokenType};
-    use crate::stmt::stmt::{LiteralValue, Stmt, StmtVisitor};
+    use crate::stmt::stmt::{
+        FunctionTypes, LiteralValue, MatchCase, OptionalParams, Pattern, Stmt, StmtVisitor,
+    };
+    use crate::system::system::System;
     use rustc_hash::FxHashMap as HashMap;
     use std::cell::RefCell;
     use std::rc::Rc;
+    use std::time::Instant;
+
+    /**
+     * Deepest Lox call stack before a "Stack overflow." runtime error is reported, as
+     * `limit/stack_overflow.lox` expects. Overflowing the native stack instead would abort the
+     * whole process, the in-process test runner included.
+     */
+    pub const MAX_CALL_DEPTH: usize = 1000;
+    /// The interpreter is a recursive tree walker, `MAX_CALL_DEPTH` calls fit in this much stack.
+    pub const INTERPRETER_STACK_SIZE: usize = 256 * 1024 * 1024;
 
     pub struct Interpreter {
         pub environment: Rc<RefCell<Environment>>,
         pub locals: Vec<(Expr, usize)>,
         pub globals: Rc<RefCell<Environment>>,
+        pub debugger: Option<Debugger>,
+        pub profiler: Option<Profiler>,
+        pub coverage: Option<Coverage>,
+        pub event_loop: EventLoop,
+        pub sandbox: Sandbox,
+        pub system: System,
+        pub call_depth: usize,
+        /// Line and column of the call being evaluated, natives report their errors there.
+        call_site: (u32, u32),
+        pub deadline: Option<Instant>,
+        dynamic_lookup: bool,
+    }
+
+    /**
+     * State of the interpreter which belongs to one native call stack, a fiber runs on a stack
+     * of its own. Kept here while the stack is switched out, see `Interpreter::enter_stack`.
+     */
+    #[derive(Default)]
+    pub struct CallStack {
+        environment: Option<Rc<RefCell<Environment>>>,
+        call_depth: usize,
+        call_site: (u32, u32),
+        profiled: Option<DetachedFrames>,
+        profiler_depth: usize,
     }
 
     #[derive(Debug, PartialEq, Clone)]
     pub enum Error {
         LoxRuntimeError,
         Return(Rc<LiteralValue>),
+        Exit(i32),
     }
 
     impl Interpreter {
         pub fn new() -> Interpreter {
             let globals = Rc::new(RefCell::new(Environment::new_without_enclosing()));
-            globals.borrow_mut().define(
-                &Token::new(
-                    TokenType::Identifier("clock".to_string()),
-                    "clock".to_string(),
-                    999,
-                    999,
-                    999,
-                ),
-                Rc::new(LiteralValue::Callable(Callable::Clock(Clock {}))),
-            );
-
-            globals.borrow_mut().define(
-                &Token::new(
-                    TokenType::Identifier("unixClock".to_string()),
-                    "unixClock".to_string(),
-                    999,
-                    999,
-                    999,
-                ),
-                Rc::new(LiteralValue::Callable(Callable::UnixTClock(UnixTClock {}))),
-            );
-
             let mut locals = Vec::new();
-            locals.push((
-                Expr::Variable(Token::new(
-                    TokenType::Identifier("clock".to_string()),
-                    "".to_string(),
-                    0,
-                    0,
-                    0,
-                )),
-                0,
-            ));
-
-            locals.push((
-                Expr::Variable(Token::new(
-                    TokenType::Identifier("unixClock".to_string()),
-                    "".to_string(),
-                    0,
-                    0,
+            for (name, native) in natives() {
+                globals.borrow_mut().define(
+                    &Token::new(
+                        TokenType::Identifier(name.clone()),
+                        name.clone(),
+                        999,
+                        999,
+                        999,
+                    ),
+                    Rc::new(LiteralValue::Callable(native)),
+                );
+                locals.push((
+                    Expr::Variable(Token::new(
+                        TokenType::Identifier(name),
+                        "".to_string(),
+                        0,
+                        0,
+                        0,
+                    )),
                     0,
-                )),
-                0,
-            ));
+                ));
+            }
 
             Interpreter {
                 environment: Rc::clone(&globals),
                 locals,
                 globals: globals,
+                debugger: None,
+                profiler: None,
+                coverage: None,
+                event_loop: EventLoop::default(),
+                sandbox: Sandbox::default(),
+                system: System::default(),
+                call_depth: 0,
+                call_site: (0, 0),
+                deadline: None,
+                dynamic_lookup: false,
+            }
+        }
+
+        /// Reports a runtime error of a native function at the call being evaluated.
+        pub(crate) fn native_error(&self, message: String) -> Error {
+            error(
+                self.call_site.0,
+                self.call_site.1,
+                message,
+                function_name!(),
+                Some(RLoxErrorType::RuntimeError),
+            );
+            Error::LoxRuntimeError
+        }
+
+        /// Continues on `stack`, which keeps the state of the stack left until `leave_stack`.
+        pub(crate) fn enter_stack(&mut self, stack: &mut CallStack) {
+            self.swap_stack(stack);
+            if let Some(profiler) = self.profiler.as_mut() {
+                stack.profiler_depth = match stack.profiled.take() {
+                    Some(frames) => profiler.attach(frames),
+                    None => profiler.depth(),
+                };
             }
         }
 
+        /// Returns to the stack `enter_stack` left, `stack` keeps the state of the one left.
+        pub(crate) fn leave_stack(&mut self, stack: &mut CallStack) {
+            if let Some(profiler) = self.profiler.as_mut() {
+                stack.profiled = Some(profiler.detach(stack.profiler_depth));
+            }
+            self.swap_stack(stack);
+        }
+
+        fn swap_stack(&mut self, stack: &mut CallStack) {
+            let environment = stack
+                .environment
+                .take()
+                .unwrap_or_else(|| Rc::clone(&self.globals));
+            stack.environment = Some(std::mem::replace(&mut self.environment, environment));
+            std::mem::swap(&mut self.call_depth, &mut stack.call_depth);
+            std::mem::swap(&mut self.call_site, &mut stack.call_site);
+        }
+
+        /**
+         * Evaluates an expression which was never seen by the resolver(debugger input),
+         * variables are looked up by walking the current environment chain.
+         */
+        pub fn evaluate_in_scope(&mut self, expr: &Expr) -> Result<Rc<LiteralValue>, Error> {
+            let previous = self.dynamic_lookup;
+            self.dynamic_lookup = true;
+            let result = self.evaluate(expr);
+            self.dynamic_lookup = previous;
+            result
+        }
+
         fn evaluate(&mut self, expr: &Expr) -> Result<Rc<LiteralValue>, Error> {
             expr.accept(self)
         }
 
-        fn is_truthy_lval(l_val: &LiteralValue) -> bool {
+        /// Evaluates an expression in `env` instead of the current environment.
+        pub fn evaluate_in(
+            &mut self,
+            expr: &Expr,
+            env: &Rc<RefCell<Environment>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let previous = std::mem::replace(&mut self.environment, Rc::clone(env));
+            let result = self.evaluate(expr);
+            self.environment = previous;
+            result
+        }
+
+        pub(crate) fn is_truthy_lval(l_val: &LiteralValue) -> bool {
             match l_val {
                 LiteralValue::Bool(b) => *b,
                 LiteralValue::Nil => false,
@@ -111,10 +210,267 @@ pub mod interpreter {
                     let mut env = self.environment.as_ref().borrow_mut();
                     env.get_at(depth, token)
                 }
+                None if self.dynamic_lookup => self.environment.borrow_mut().get(token),
                 None => self.globals.borrow_mut().get(token),
             }
         }
 
+        fn matches_pattern(
+            &mut self,
+            pattern: &Pattern,
+            value: &LiteralValue,
+        ) -> Result<bool, Error> {
+            match pattern {
+                Pattern::Literal(token, literal) => {
+                    let equal = Interpreter::equal_equal(literal, value, token)?;
+                    Ok(Interpreter::is_truthy_lval(&equal))
+                }
+                Pattern::Class(name, _) => {
+                    let class = match &*self.look_up_variable(name)? {
+                        LiteralValue::Callable(Callable::Class(class)) => class.clone(),
+                        _ => {
+                            error(
+                                name.get_line(),
+                                name.get_column(),
+                                String::from("Case pattern must be a class."),
+                                function_name!(),
+                                Some(RLoxErrorType::RuntimeError),
+                            );
+                            return Err(Error::LoxRuntimeError);
+                        }
+                    };
+                    Ok(match value {
+                        LiteralValue::Callable(Callable::Instance(instance)) => {
+                            instance.borrow().rlox_class.is_subclass_of(&class)
+                        }
+                        _ => false,
+                    })
+                }
+            }
+        }
+
+        /// Environment holding the binding of a matched case when its guard holds.
+        fn case_env(
+            &mut self,
+            case: &MatchCase,
+            value: &Rc<LiteralValue>,
+        ) -> Result<Option<Rc<RefCell<Environment>>>, Error> {
+            let mut env = Environment::new(Rc::clone(&self.environment));
+            if let [Pattern::Class(_, Some(binding))] = case.patterns.as_slice() {
+                env.define(binding, Rc::clone(value));
+            }
+            let env = Rc::new(RefCell::new(env));
+
+            let holds = match &case.guard {
+                Some(guard) => Interpreter::is_truthy_lval(&*self.evaluate_in(guard, &env)?),
+                None => true,
+            };
+            self.record_branch(Some(&case.keyword), holds);
+            Ok(holds.then_some(env))
+        }
+
+        /**
+         * Evaluates the subject of a `match` and picks the statement to run, the body of the
+         * first matching case with the environment holding its binding, or the default.
+         */
+        pub(crate) fn select_case<'a>(
+            &mut self,
+            subject: &Expr,
+            cases: &'a [MatchCase],
+            default: &'a Option<Box<Stmt>>,
+        ) -> Result<Option<(&'a Stmt, Rc<RefCell<Environment>>)>, Error> {
+            let value = self.evaluate(subject)?;
+            for case in cases {
+                let mut matched = false;
+                for pattern in &case.patterns {
+                    if self.matches_pattern(pattern, &value)? {
+                        matched = true;
+                        break;
+                    }
+                }
+                if !matched {
+                    self.record_branch(Some(&case.keyword), false);
+                } else if let Some(env) = self.case_env(case, &value)? {
+                    return Ok(Some((&case.body, env)));
+                }
+            }
+
+            Ok(default
+                .as_deref()
+                .map(|default| (default, Rc::clone(&self.environment))))
+        }
+
+        /// Applies a binary operator to two evaluated operands, reporting ill-typed ones.
+        pub(crate) fn binary_operation(
+            left: &LiteralValue,
+            right: &LiteralValue,
+            operator: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            match operator.get_token_type() {
+                TokenType::Greater => Interpreter::greater(left, right, operator),
+                TokenType::GreaterEqual => Interpreter::greater_equal(left, right, operator),
+                TokenType::Less => Interpreter::less(left, right, operator),
+                TokenType::LessEqual => Interpreter::less_equal(left, right, operator),
+                TokenType::BangEqual => Interpreter::bang_equal(left, right, operator),
+                TokenType::EqualEqual => Interpreter::equal_equal(left, right, operator),
+                TokenType::Minus => Interpreter::substract(left, right, operator),
+                TokenType::Plus => Interpreter::add(left, right, operator),
+                TokenType::Slash => Interpreter::divide(left, right, operator),
+                TokenType::Star => Interpreter::multiply(left, right, operator),
+                TokenType::Percent => Interpreter::modulo(left, right, operator),
+                TokenType::StarStar => Interpreter::power(left, right, operator),
+                TokenType::Ampersand
+                | TokenType::Pipe
+                | TokenType::Caret
+                | TokenType::LessLess
+                | TokenType::GreaterGreater => Interpreter::bitwise(left, right, operator),
+                _ => Err(Error::LoxRuntimeError),
+            }
+        }
+
+        /// Bitwise operators work on numbers without a fractional part, as 64 bit integers.
+        pub(crate) fn integer(value: &LiteralValue) -> Option<i64> {
+            match value {
+                LiteralValue::Number(number)
+                    if number.fract() == 0.0 && number.abs() < i64::MAX as f64 =>
+                {
+                    Some(*number as i64)
+                }
+                _ => None,
+            }
+        }
+
+        fn modulo(
+            operand1: &LiteralValue,
+            operand2: &LiteralValue,
+            operator: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            match (operand1, operand2) {
+                (LiteralValue::Number(number1), LiteralValue::Number(number2)) => {
+                    Ok(Rc::new(LiteralValue::Number(number1 % number2)))
+                }
+                _ => {
+                    error(
+                        operator.get_line(),
+                        operator.get_column(),
+                        String::from("Operands must be numbers."),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    Err(Error::LoxRuntimeError)
+                }
+            }
+        }
+
+        fn power(
+            operand1: &LiteralValue,
+            operand2: &LiteralValue,
+            operator: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            match (operand1, operand2) {
+                (LiteralValue::Number(number1), LiteralValue::Number(number2)) => {
+                    Ok(Rc::new(LiteralValue::Number(number1.powf(*number2))))
+                }
+                _ => {
+                    error(
+                        operator.get_line(),
+                        operator.get_column(),
+                        String::from("Operands must be numbers."),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    Err(Error::LoxRuntimeError)
+                }
+            }
+        }
+
+        fn bitwise(
+            operand1: &LiteralValue,
+            operand2: &LiteralValue,
+            operator: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let (integer1, integer2) = match (
+                Interpreter::integer(operand1),
+                Interpreter::integer(operand2),
+            ) {
+                (Some(integer1), Some(integer2)) => (integer1, integer2),
+                _ => {
+                    error(
+                        operator.get_line(),
+                        operator.get_column(),
+                        String::from("Operands must be integers."),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    return Err(Error::LoxRuntimeError);
+                }
+            };
+
+            let shift = u32::try_from(integer2).ok().filter(|shift| *shift < 64);
+            let result = match operator.get_token_type() {
+                TokenType::Ampersand => Some(integer1 & integer2),
+                TokenType::Pipe => Some(integer1 | integer2),
+                TokenType::Caret => Some(integer1 ^ integer2),
+                TokenType::LessLess => shift.map(|shift| integer1 << shift),
+                _ => shift.map(|shift| integer1 >> shift),
+            };
+            match result {
+                Some(result) => Ok(Rc::new(LiteralValue::Number(result as f64))),
+                None => {
+                    error(
+                        operator.get_line(),
+                        operator.get_column(),
+                        String::from("Shift amount must be between 0 and 63."),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    Err(Error::LoxRuntimeError)
+                }
+            }
+        }
+
+        fn get_property(object: &LiteralValue, name: &Token) -> Result<Rc<LiteralValue>, Error> {
+            if let LiteralValue::Callable(Callable::Instance(instance)) = object {
+                return RLoxInstance::get(instance, name);
+            }
+            if let Some(method) = NativeMethod::bind(object, name) {
+                return Ok(Rc::new(LiteralValue::Callable(Callable::NativeMethod(
+                    method,
+                ))));
+            }
+            let message = match NativeMethod::names(object).is_empty() {
+                true => format!(
+                    "Error at '{}': Only instances can have properties.",
+                    name.get_token_type()
+                ),
+                false => format!("Undefined property '{}'.", name.get_token_type()),
+            };
+            error(
+                name.get_line(),
+                name.get_column(),
+                message,
+                function_name!(),
+                Some(RLoxErrorType::RuntimeError),
+            );
+            Err(Error::LoxRuntimeError)
+        }
+
+        fn assign_variable(&mut self, name: &Token, value: Rc<LiteralValue>) -> Result<(), Error> {
+            match self.get_depth(name) {
+                Some(depth) => {
+                    let mut env = self.environment.as_ref().borrow_mut();
+                    env.assign_at(depth, name, value);
+                }
+                None if self.dynamic_lookup => {
+                    self.environment.borrow_mut().assign(name, value)?;
+                }
+                None => {
+                    self.globals.borrow_mut().assign(name, value)?;
+                }
+            }
+            Ok(())
+        }
+
         fn substract(
             operand1: &LiteralValue,
             operand2: &LiteralValue,
@@ -340,20 +696,201 @@ pub mod interpreter {
             }
         }
 
+        fn index(
+            object: &LiteralValue,
+            bracket: &Token,
+            index: &LiteralValue,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let report = |message: &str| {
+                error(
+                    bracket.get_line(),
+                    bracket.get_column(),
+                    message.to_string(),
+                    function_name!(),
+                    Some(RLoxErrorType::RuntimeError),
+                );
+                Err(Error::LoxRuntimeError)
+            };
+            let Some(index) = Interpreter::integer(index) else {
+                return report("Index must be an integer.");
+            };
+            let element = match object {
+                LiteralValue::List(list) => usize::try_from(index)
+                    .ok()
+                    .and_then(|index| list.borrow().get(index).cloned()),
+                LiteralValue::String(str) => usize::try_from(index)
+                    .ok()
+                    .and_then(|index| str.chars().nth(index))
+                    .map(|char| Rc::new(LiteralValue::String(char.to_string()))),
+                _ => return report("Only lists and strings can be indexed."),
+            };
+            match element {
+                Some(element) => Ok(element),
+                None => report("Index out of range."),
+            }
+        }
+
+        /**
+         * Orders the arguments of a call with named ones into one value per parameter of
+         * `function`(`init` for a class) and the rest parameter values.
+         */
+        fn named_arguments(
+            function: Option<&RLoxFunction>,
+            args: Vec<Rc<LiteralValue>>,
+            names: &[Option<Token>],
+            paren: &Token,
+        ) -> Result<CallArguments, Error> {
+            let report = |token: &Token, message: String| {
+                error(
+                    token.get_line(),
+                    token.get_column(),
+                    message,
+                    function_name!(),
+                    Some(RLoxErrorType::RuntimeError),
+                );
+                Error::LoxRuntimeError
+            };
+            let no_params = OptionalParams::default();
+            let (params, optional) = match function {
+                Some(function) => function.parameters(),
+                None => (&[][..], &no_params),
+            };
+            let arity = function.map_or(Arity::exact(0), |function| function.arity());
+            let count = args.len();
+            let fixed = params.len() - optional.rest as usize;
+
+            let mut ordered = vec![None; fixed];
+            let mut rest = Vec::new();
+            let mut positional = 0;
+            for (value, name) in args.into_iter().zip(names) {
+                match name {
+                    None if positional < fixed => {
+                        ordered[positional] = Some(value);
+                        positional += 1;
+                    }
+                    None if optional.rest => rest.push(value),
+                    None => {
+                        return Err(report(
+                            paren,
+                            format!("Expected {} arguments but got {}.", arity, count),
+                        ))
+                    }
+                    Some(name) => {
+                        match params[..fixed]
+                            .iter()
+                            .position(|param| param.get_token_type() == name.get_token_type())
+                        {
+                            Some(idx) if ordered[idx].is_none() => ordered[idx] = Some(value),
+                            Some(_) => {
+                                return Err(report(
+                                    name,
+                                    format!(
+                                        "Argument for parameter '{}' given more than once.",
+                                        name.get_token_type()
+                                    ),
+                                ))
+                            }
+                            None => {
+                                return Err(report(
+                                    name,
+                                    format!("No parameter named '{}'.", name.get_token_type()),
+                                ))
+                            }
+                        }
+                    }
+                }
+            }
+
+            for (idx, param) in params[..fixed].iter().enumerate() {
+                if ordered[idx].is_none() && optional.defaults[idx].is_none() {
+                    return Err(report(
+                        paren,
+                        format!(
+                            "Missing argument for parameter '{}'.",
+                            param.get_token_type()
+                        ),
+                    ));
+                }
+            }
+            Ok((ordered, rest))
+        }
+
         pub fn execute(&mut self, stmt: &Stmt) -> Result<(), Error> {
+            self.before_execute(stmt)?;
             stmt.accept(self)
         }
 
+        /// Deadline, debugger and coverage bookkeeping done before every statement runs.
+        pub(crate) fn before_execute(&mut self, stmt: &Stmt) -> Result<(), Error> {
+            if let Some(deadline) = self.deadline {
+                if Instant::now() >= deadline {
+                    error(
+                        stmt.line().unwrap_or(0),
+                        0,
+                        String::from("Execution timed out."),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    return Err(Error::LoxRuntimeError);
+                }
+            }
+            if self.debugger.is_some() {
+                self.debug_hook(stmt)?;
+            }
+            if let Some(coverage) = self.coverage.as_mut() {
+                if !matches!(stmt, Stmt::BlockStmt(_)) {
+                    if let Some(line) = stmt.line() {
+                        coverage.hit_line(line);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Called each time a loop evaluates its condition.
+        pub(crate) fn loop_condition(&mut self) {
+            if let Some(debugger) = self.debugger.as_mut() {
+                debugger.on_loop_condition();
+            }
+        }
+
+        pub(crate) fn record_branch(&mut self, token: Option<&Token>, taken: bool) {
+            if let (Some(coverage), Some(token)) = (self.coverage.as_mut(), token) {
+                coverage.hit_branch((token.get_line(), token.get_column()), taken);
+            }
+        }
+
+        fn debug_hook(&mut self, stmt: &Stmt) -> Result<(), Error> {
+            // take the debugger out so that statements executed from the prompt
+            // (e.g. a function called while evaluating an expression) do not pause.
+            let mut debugger = match self.debugger.take() {
+                Some(debugger) => debugger,
+                None => return Ok(()),
+            };
+            let result = debugger.on_statement(self, stmt);
+            self.debugger = Some(debugger);
+            result
+        }
+
+        /// Runs the program, then the fibers and timers it started until none is left.
         pub fn interpret(&mut self, statements: &Vec<Stmt>) -> Result<(), Error> {
             for stmt in statements {
                 self.execute(stmt)?;
             }
-            Ok(())
+            event_loop::run(self)
         }
 
         pub fn execute_block(&mut self, stmts: &Vec<Stmt>, env: Environment) -> Result<(), Error> {
+            self.execute_in(stmts, Rc::new(RefCell::new(env)))
+        }
+
+        pub fn execute_in(
+            &mut self,
+            stmts: &[Stmt],
+            env: Rc<RefCell<Environment>>,
+        ) -> Result<(), Error> {
             let previous = Rc::clone(&self.environment);
-            self.environment = Rc::new(RefCell::new(env));
+            self.environment = env;
 
             let result = stmts.iter().try_for_each(|stmt| self.execute(stmt));
 
@@ -377,19 +914,7 @@ pub mod interpreter {
             let left = self.evaluate(left)?;
             let right = self.evaluate(right)?;
 
-            match operator.get_token_type() {
-                TokenType::Greater => Interpreter::greater(&left, &right, &operator),
-                TokenType::GreaterEqual => Interpreter::greater_equal(&left, &right, &operator),
-                TokenType::Less => Interpreter::less(&left, &right, &operator),
-                TokenType::LessEqual => Interpreter::less_equal(&left, &right, &operator),
-                TokenType::BangEqual => Interpreter::bang_equal(&left, &right, &operator),
-                TokenType::EqualEqual => Interpreter::equal_equal(&left, &right, &operator),
-                TokenType::Minus => Interpreter::substract(&left, &right, &operator),
-                TokenType::Plus => Interpreter::add(&left, &right, &operator),
-                TokenType::Slash => Interpreter::divide(&left, &right, &operator),
-                TokenType::Star => Interpreter::multiply(&left, &right, &operator),
-                _ => Err(Error::LoxRuntimeError),
-            }
+            Interpreter::binary_operation(&left, &right, operator)
         }
 
         fn visit_grouping_expr(&mut self, expr: &Expr) -> Result<Rc<LiteralValue>, Error> {
@@ -419,6 +944,19 @@ pub mod interpreter {
                 TokenType::Bang => Ok(Rc::new(LiteralValue::Bool(!Interpreter::is_truthy_lval(
                     &right_l,
                 )))),
+                TokenType::Tilde => match Interpreter::integer(&right_l) {
+                    Some(integer) => Ok(Rc::new(LiteralValue::Number(!integer as f64))),
+                    None => {
+                        error(
+                            operator.get_line(),
+                            operator.get_column(),
+                            String::from("Operand must be an integer."),
+                            function_name!(),
+                            Some(RLoxErrorType::RuntimeError),
+                        );
+                        Err(Error::LoxRuntimeError)
+                    }
+                },
                 _ => Err(Error::LoxRuntimeError),
             }
         }
@@ -433,19 +971,7 @@ pub mod interpreter {
             value: &Expr,
         ) -> Result<Rc<LiteralValue>, Error> {
             let value_evaluated = self.evaluate(value)?;
-            let distance = self.get_depth(name);
-
-            match distance {
-                Some(depth) => {
-                    let mut env = self.environment.as_ref().borrow_mut();
-                    env.assign_at(depth, name, value_evaluated.into());
-                }
-                None => {
-                    self.globals
-                        .borrow_mut()
-                        .assign(name, value_evaluated.into())?;
-                }
-            }
+            self.assign_variable(name, value_evaluated)?;
 
             return self.visit_variable_expr(name);
         }
@@ -459,11 +985,14 @@ pub mod interpreter {
             let left_val = self.evaluate(left)?;
             let is_truthy = Interpreter::is_truthy_lval(&left_val);
 
-            if let TokenType::Or = operator.get_token_type() {
-                if is_truthy {
-                    return Ok(left_val);
-                }
-            } else if !is_truthy {
+            let short_circuit = match operator.get_token_type() {
+                TokenType::Or => is_truthy,
+                TokenType::QuestionQuestion => !matches!(*left_val, LiteralValue::Nil),
+                _ => !is_truthy,
+            };
+            self.record_branch(Some(operator), short_circuit);
+
+            if short_circuit {
                 return Ok(left_val);
             }
 
@@ -475,16 +1004,62 @@ pub mod interpreter {
             callee: &Expr,
             parent: &Token,
             arguments: &Vec<Expr>,
+            names: &Vec<Option<Token>>,
         ) -> Result<Rc<LiteralValue>, Error> {
-            let calle_local = self.evaluate(callee)?;
+            let calle_local = match callee {
+                // the object is evaluated once, a nil one skips the call and its arguments.
+                Expr::OptionalGet(object, name) => match &*self.evaluate(object)? {
+                    LiteralValue::Nil => return Ok(Rc::new(LiteralValue::Nil)),
+                    object => Interpreter::get_property(object, name)?,
+                },
+                _ => self.evaluate(callee)?,
+            };
             let mut args = Vec::new();
 
             for arg in arguments {
                 args.push(self.evaluate(arg)?);
             }
 
-            let handle_arity = |arguments: usize, arity: usize| -> Result<(), Error> {
-                if arguments != arity {
+            self.call_site = (parent.get_line(), parent.get_column());
+            if self.call_depth >= MAX_CALL_DEPTH {
+                error(
+                    parent.get_line(),
+                    parent.get_column(),
+                    String::from("Stack overflow."),
+                    function_name!(),
+                    Some(RLoxErrorType::RuntimeError),
+                );
+                return Err(Error::LoxRuntimeError);
+            }
+
+            if names.iter().any(Option::is_some) {
+                return match &*calle_local {
+                    LiteralValue::Callable(Callable::Function(function)) => {
+                        let (args, rest) =
+                            Interpreter::named_arguments(Some(function), args, names, parent)?;
+                        function.call_with(self, args, rest)
+                    }
+                    LiteralValue::Callable(Callable::Class(class)) => {
+                        let init = class.find_method("init");
+                        let (args, rest) =
+                            Interpreter::named_arguments(init.as_ref(), args, names, parent)?;
+                        class.call_with(self, args, rest)
+                    }
+                    _ => {
+                        error(
+                            parent.get_line(),
+                            parent.get_column(),
+                            String::from("Only functions and classes take named arguments."),
+                            function_name!(),
+                            Some(RLoxErrorType::RuntimeError),
+                        );
+                        Err(Error::LoxRuntimeError)
+                    }
+                };
+            }
+
+            let handle_arity = |arguments: usize, arity: Arity| -> Result<(), Error> {
+                if !arity.accepts(arguments) {
                     error(
                         parent.get_line(),
                         parent.get_column(),
@@ -524,6 +1099,55 @@ pub mod interpreter {
                 }
             }
 
+            if let LiteralValue::Callable(Callable::Str(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let LiteralValue::Callable(Callable::Len(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let LiteralValue::Callable(Callable::Iterate(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let LiteralValue::Callable(Callable::EventLoop(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let LiteralValue::Callable(Callable::File(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let LiteralValue::Callable(Callable::System(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let LiteralValue::Callable(Callable::NativeMethod(function)) = &*calle_local {
+                match handle_arity(arguments.len(), function.arity()) {
+                    Ok(_) => return function.call(self, &mut args),
+                    Err(err) => return Err(err),
+                }
+            }
+
             if let LiteralValue::Callable(callable_box) = &*calle_local {
                 if let Callable::Class(function) = callable_box {
                     match handle_arity(arguments.len(), function.arity()) {
@@ -548,27 +1172,8 @@ pub mod interpreter {
             object: &Expr,
             name: &Token,
         ) -> Result<Rc<LiteralValue>, Error> {
-            match &*self.evaluate(object)? {
-                LiteralValue::Callable(call_box) => {
-                    if let Callable::Instance(instance) = call_box {
-                        return instance.borrow_mut().get(name);
-                    }
-                }
-                _ => {
-                    error(
-                        name.get_line(),
-                        name.get_column(),
-                        format!(
-                            "Error at '{}': Only instances can have properties.",
-                            name.get_token_type()
-                        ),
-                        function_name!(),
-                        Some(RLoxErrorType::RuntimeError),
-                    );
-                    return Err(Error::LoxRuntimeError);
-                }
-            };
-            Err(Error::LoxRuntimeError)
+            let object = self.evaluate(object)?;
+            Interpreter::get_property(&object, name)
         }
 
         fn visit_set_expr(
@@ -600,6 +1205,90 @@ pub mod interpreter {
             }
         }
 
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+            postfix: bool,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let binary_operator = operator.update_operator().ok_or(Error::LoxRuntimeError)?;
+            let (old, new) = match target {
+                Expr::Variable(name) => {
+                    let old = self.look_up_variable(name)?;
+                    let right = self.evaluate(value)?;
+                    let new = Interpreter::binary_operation(&old, &right, &binary_operator)?;
+                    self.assign_variable(name, Rc::clone(&new))?;
+                    (old, new)
+                }
+                Expr::Get(object, name) => {
+                    let obj_l = self.evaluate(object)?;
+                    let instance = match &*obj_l {
+                        LiteralValue::Callable(Callable::Instance(instance)) => instance,
+                        _ => {
+                            error(
+                                name.get_line(),
+                                name.get_column(),
+                                format!(
+                                    "Error at '{}': Only instances have fields.",
+                                    name.get_token_type()
+                                ),
+                                function_name!(),
+                                Some(RLoxErrorType::RuntimeError),
+                            );
+                            return Err(Error::LoxRuntimeError);
+                        }
+                    };
+                    let old = RLoxInstance::get(instance, name)?;
+                    let right = self.evaluate(value)?;
+                    let new = Interpreter::binary_operation(&old, &right, &binary_operator)?;
+                    instance.borrow_mut().set(name, Rc::clone(&new));
+                    (old, new)
+                }
+                _ => return Err(Error::LoxRuntimeError),
+            };
+            Ok(if postfix { old } else { new })
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let condition = self.evaluate(condition)?;
+            let is_truthy = Interpreter::is_truthy_lval(&condition);
+            self.record_branch(Some(question), is_truthy);
+
+            match is_truthy {
+                true => self.evaluate(then_expr),
+                false => self.evaluate(else_expr),
+            }
+        }
+
+        fn visit_optional_get_expr(
+            &mut self,
+            object: &Expr,
+            name: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            match &*self.evaluate(object)? {
+                LiteralValue::Nil => Ok(Rc::new(LiteralValue::Nil)),
+                object => Interpreter::get_property(object, name),
+            }
+        }
+
+        fn visit_index_expr(
+            &mut self,
+            object: &Expr,
+            bracket: &Token,
+            index: &Expr,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let object = self.evaluate(object)?;
+            let index = self.evaluate(index)?;
+            Interpreter::index(&object, bracket, &index)
+        }
+
         fn visit_this_expr(&mut self, keyword: &Token) -> Result<Rc<LiteralValue>, Error> {
             self.look_up_variable(keyword)
         }
@@ -643,6 +1332,20 @@ pub mod interpreter {
                         return Err(Error::LoxRuntimeError);
                     }
                 },
+                // a trait method of a class with no superclass.
+                (LiteralValue::Nil, _) => {
+                    error(
+                        keyword.get_line(),
+                        keyword.get_column(),
+                        format!(
+                            "Error at '{}': Can't use 'super' in a class with no superclass.",
+                            keyword.get_token_type()
+                        ),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    Err(Error::LoxRuntimeError)
+                }
                 (_, _) => {
                     return Err(Error::LoxRuntimeError);
                 }
@@ -658,10 +1361,7 @@ pub mod interpreter {
 
         fn visit_print_stmt(&mut self, expr: &Expr) -> Result<(), Error> {
             let value = self.evaluate(expr)?;
-            println!("{}", value);
-            if *IS_WASM.read().unwrap() == true {
-                WASM_OUTPUT.write().unwrap().push(value.to_string());
-            }
+            output(value.to_string());
             Ok(())
         }
 
@@ -683,12 +1383,33 @@ pub mod interpreter {
             Err(Error::Return(return_val))
         }
 
+        /// Generators run their `yield` statements themselves, reaching one here is a bug.
+        fn visit_yield_stmt(&mut self, keyword: &Token, _expr: &Expr) -> Result<(), Error> {
+            error(
+                keyword.get_line(),
+                keyword.get_column(),
+                String::from("Can't yield outside of a generator."),
+                function_name!(),
+                Some(RLoxErrorType::RuntimeError),
+            );
+            Err(Error::LoxRuntimeError)
+        }
+
         fn visit_var_stmt(&mut self, name: &Token, initializer: &Expr) -> Result<(), Error> {
             let value = self.evaluate(initializer)?;
             self.environment.as_ref().borrow_mut().define(name, value);
             Ok(())
         }
 
+        fn visit_const_stmt(&mut self, name: &Token, initializer: &Expr) -> Result<(), Error> {
+            let value = self.evaluate(initializer)?;
+            self.environment
+                .as_ref()
+                .borrow_mut()
+                .define_const(name, value);
+            Ok(())
+        }
+
         fn visit_block_stmt(&mut self, stmts: &Vec<Stmt>) -> Result<(), Error> {
             let env = Environment::new(Rc::clone(&self.environment));
             self.execute_block(stmts, env)
@@ -698,6 +1419,7 @@ pub mod interpreter {
             &mut self,
             name: &Token,
             superclass: &Option<Expr>,
+            traits: &Vec<Expr>,
             statements: &Vec<Stmt>,
         ) -> Result<(), Error> {
             let mut super_class = None;
@@ -728,6 +1450,29 @@ pub mod interpreter {
                 }
             }
 
+            let mut used_traits = Vec::new();
+            for used_trait in traits {
+                match &*self.evaluate(used_trait)? {
+                    LiteralValue::Callable(Callable::Trait(rlox_trait)) => {
+                        used_traits.push(rlox_trait.clone())
+                    }
+                    _ => {
+                        error(
+                            name.get_line(),
+                            name.get_column(),
+                            format!(
+                                "Error at '{}': '{}' is not a trait.",
+                                name.get_token_type(),
+                                used_trait
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::RuntimeError),
+                        );
+                        return Err(Error::LoxRuntimeError);
+                    }
+                }
+            }
+
             self.environment
                 .as_ref()
                 .borrow_mut()
@@ -742,9 +1487,42 @@ pub mod interpreter {
                 );
             }
 
-            let mut methods = HashMap::default();
+            // the methods of the class override the trait ones, two traits can't both provide one.
+            let mut methods: HashMap<String, RLoxFunction> = HashMap::default();
+            let mut providers: HashMap<String, String> = HashMap::default();
+            let overridden = |method: &String| {
+                statements.iter().any(|stmt| {
+                    matches!(stmt, Stmt::Function(fn_name, _, _, _, _)
+                        if fn_name.get_token_type().to_string() == *method)
+                })
+            };
+            for used_trait in &used_traits {
+                for (method, function) in used_trait.methods_for(&super_class) {
+                    if overridden(&method) {
+                        continue;
+                    }
+                    if let Some(provider) = providers.get(&method) {
+                        error(
+                            name.get_line(),
+                            name.get_column(),
+                            format!(
+                                "Error at '{}': Method '{}' is provided by both traits '{}' and '{}'.",
+                                name.get_token_type(),
+                                method,
+                                provider,
+                                used_trait.name
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::RuntimeError),
+                        );
+                        return Err(Error::LoxRuntimeError);
+                    }
+                    providers.insert(method.clone(), used_trait.name.clone());
+                    methods.insert(method, function);
+                }
+            }
             for method in statements {
-                if let Stmt::Function(fn_name, _, _) = method {
+                if let Stmt::Function(fn_name, _, _, _, _) = method {
                     let lox_fun: RLoxFunction = RLoxFunction::new(
                         method.clone(),
                         Rc::clone(&self.environment),
@@ -755,7 +1533,7 @@ pub mod interpreter {
             }
 
             let klass: RLoxClass = RLoxClass::new(
-                name.get_token_type().to_string(),
+                name,
                 methods,
                 match super_class.clone() {
                     Some(super_klass) => Some(super_klass),
@@ -775,14 +1553,41 @@ pub mod interpreter {
             Ok(())
         }
 
+        fn visit_trait_stmt(&mut self, name: &Token, methods: &Vec<Stmt>) -> Result<(), Error> {
+            let mut trait_methods = HashMap::default();
+            for method in methods {
+                if let Stmt::Function(fn_name, _, _, _, _) = method {
+                    let lox_fun =
+                        RLoxFunction::new(method.clone(), Rc::clone(&self.environment), false);
+                    trait_methods.insert(fn_name.get_token_type().to_string(), lox_fun);
+                }
+            }
+
+            self.environment.as_ref().borrow_mut().define(
+                name,
+                Rc::new(LiteralValue::Callable(Callable::Trait(RLoxTrait::new(
+                    name,
+                    trait_methods,
+                )))),
+            );
+            Ok(())
+        }
+
         fn visit_function_stmt(
             &mut self,
             name: &Token,
             params: &Vec<Token>,
             body: &Vec<Stmt>,
+            optional: &OptionalParams,
         ) -> Result<(), Error> {
             let func: RLoxFunction = RLoxFunction::new(
-                Stmt::Function(name.clone(), params.clone(), body.clone()),
+                Stmt::Function(
+                    name.clone(),
+                    params.clone(),
+                    body.clone(),
+                    FunctionTypes::default(),
+                    optional.clone(),
+                ),
                 Rc::clone(&self.environment),
                 false,
             );
@@ -800,7 +1605,9 @@ pub mod interpreter {
             else_stmt: &Option<Box<Stmt>>,
         ) -> Result<(), Error> {
             let value = self.evaluate(expr)?;
-            if Interpreter::is_truthy_lval(&value) {
+            let is_truthy = Interpreter::is_truthy_lval(&value);
+            self.record_branch(expr.first_token(), is_truthy);
+            if is_truthy {
                 return self.execute(stmt);
             } else if let Some(else_) = else_stmt {
                 return self.execute(&else_);
@@ -810,12 +1617,29 @@ pub mod interpreter {
         }
 
         fn visit_while_stmt(&mut self, expr: &Expr, stmt: &Stmt) -> Result<(), Error> {
-            let mut l_val = self.evaluate(expr)?;
-            while Interpreter::is_truthy_lval(&l_val) {
+            loop {
+                let l_val = self.evaluate(expr)?;
+                let is_truthy = Interpreter::is_truthy_lval(&l_val);
+                self.record_branch(expr.first_token(), is_truthy);
+                self.loop_condition();
+                if !is_truthy {
+                    return Ok(());
+                }
                 self.execute(stmt)?;
-                l_val = self.evaluate(expr)?;
             }
-            Ok(())
+        }
+
+        fn visit_match_stmt(
+            &mut self,
+            _keyword: &Token,
+            subject: &Expr,
+            cases: &Vec<MatchCase>,
+            default: &Option<Box<Stmt>>,
+        ) -> Result<(), Error> {
+            match self.select_case(subject, cases, default)? {
+                Some((body, env)) => self.execute_in(std::slice::from_ref(body), env),
+                None => Ok(()),
+            }
         }
     }
 }
@@ -0,0 +1,219 @@
+/*!
+ * The iteration protocol `for (var x in iterable)` loops use: `iterable.iterator()` returns an
+ * object whose `hasNext()` tells whether `next()` has another value to return.
+ * Classes implement it with methods, lists, strings and generators with the native methods here.
+ */
+pub mod iterator {
+    use crate::error_handling::error_handling::{error, RLoxErrorType};
+    use crate::function_name;
+    use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::rlox_callable::rlox_callable::{Arity, Callable, RLoxCallable, RLoxInstance};
+    use crate::scanner::scan::{Token, TokenType};
+    use crate::stmt::stmt::LiteralValue;
+    use std::{cell::Cell, fmt, rc::Rc};
+
+    /// Iterator over the elements of a list or the characters of a string.
+    #[derive(Clone, Debug)]
+    pub struct SequenceIterator {
+        sequence: Rc<LiteralValue>,
+        /// Index of the next list element, byte offset of the next character of a string.
+        next: Rc<Cell<usize>>,
+    }
+
+    impl PartialEq for SequenceIterator {
+        fn eq(&self, other: &Self) -> bool {
+            Rc::ptr_eq(&self.next, &other.next)
+        }
+    }
+
+    impl fmt::Display for SequenceIterator {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "<iterator>")
+        }
+    }
+
+    impl SequenceIterator {
+        pub fn new(sequence: Rc<LiteralValue>) -> Self {
+            Self {
+                sequence,
+                next: Rc::new(Cell::new(0)),
+            }
+        }
+
+        /// Element at `position` and the position after it.
+        fn element(&self, position: usize) -> Option<(Rc<LiteralValue>, usize)> {
+            match &*self.sequence {
+                LiteralValue::List(list) => list
+                    .borrow()
+                    .get(position)
+                    .map(|element| (Rc::clone(element), position + 1)),
+                LiteralValue::String(str) => str[position..].chars().next().map(|char| {
+                    (
+                        Rc::new(LiteralValue::String(char.to_string())),
+                        position + char.len_utf8(),
+                    )
+                }),
+                _ => None,
+            }
+        }
+
+        /// A list growing while it is iterated yields its new elements too.
+        pub fn has_next(&self) -> bool {
+            self.element(self.next.get()).is_some()
+        }
+
+        /// Next element, nil once the sequence is exhausted.
+        pub fn next(&self) -> Rc<LiteralValue> {
+            match self.element(self.next.get()) {
+                Some((element, next)) => {
+                    self.next.set(next);
+                    element
+                }
+                None => Rc::new(LiteralValue::Nil),
+            }
+        }
+    }
+
+    /**
+     * The native a `for (var x in iterable)` loop is lowered to a call of, it returns the iterator
+     * of `iterable`, or reports that it can't be iterated where the loop names it.
+     */
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Iterate {}
+
+    impl fmt::Display for Iterate {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "iterate")
+        }
+    }
+
+    impl RLoxCallable for Iterate {
+        fn arity(&self) -> Arity {
+            Arity::exact(1)
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let iterable = &args[0];
+            match &**iterable {
+                LiteralValue::List(_) | LiteralValue::String(_) => Ok(Rc::new(
+                    LiteralValue::Callable(Callable::SequenceIterator(SequenceIterator::new(
+                        Rc::clone(iterable),
+                    ))),
+                )),
+                LiteralValue::Callable(Callable::Generator(_))
+                | LiteralValue::Callable(Callable::SequenceIterator(_)) => Ok(Rc::clone(iterable)),
+                LiteralValue::Callable(Callable::Instance(instance))
+                    if instance.borrow().has_property("iterator") =>
+                {
+                    let name = Token::new(
+                        TokenType::Identifier(String::from("iterator")),
+                        String::from("iterator"),
+                        0,
+                        0,
+                        0,
+                    );
+                    match &*RLoxInstance::get(instance, &name)? {
+                        LiteralValue::Callable(Callable::Function(function))
+                            if function.arity().accepts(0) =>
+                        {
+                            function.call(interpreter, &mut Vec::new())
+                        }
+                        _ => Err(interpreter.native_error(String::from(
+                            "iterator() must be a method taking no arguments.",
+                        ))),
+                    }
+                }
+                _ => Err(interpreter.native_error(String::from(
+                    "Can only iterate over strings, lists, generators and instances with an iterator() method.",
+                ))),
+            }
+        }
+    }
+
+    /// A native method of a built-in value, bound to it.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct NativeMethod {
+        receiver: Rc<LiteralValue>,
+        name: Token,
+    }
+
+    impl NativeMethod {
+        /// Names of the native methods of `object`, empty when it has none.
+        pub fn names(object: &LiteralValue) -> &'static [&'static str] {
+            match object {
+                LiteralValue::List(_) | LiteralValue::String(_) => &["iterator"],
+                LiteralValue::Callable(Callable::Generator(_))
+                | LiteralValue::Callable(Callable::SequenceIterator(_)) => {
+                    &["iterator", "hasNext", "next"]
+                }
+                _ => &[],
+            }
+        }
+
+        /// The method `name` of `object`, `None` when it has no such native method.
+        pub fn bind(object: &LiteralValue, name: &Token) -> Option<Self> {
+            let method = name.get_token_type().to_string();
+            NativeMethod::names(object)
+                .contains(&method.as_str())
+                .then(|| Self {
+                    receiver: Rc::new(object.clone()),
+                    name: name.clone(),
+                })
+        }
+    }
+
+    impl fmt::Display for NativeMethod {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "<native fn {}>", self.name.get_token_type())
+        }
+    }
+
+    impl RLoxCallable for NativeMethod {
+        fn arity(&self) -> Arity {
+            Arity::exact(0)
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            _args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let method = self.name.get_token_type().to_string();
+            match (&*self.receiver, method.as_str()) {
+                (LiteralValue::List(_) | LiteralValue::String(_), "iterator") => {
+                    Ok(Rc::new(LiteralValue::Callable(Callable::SequenceIterator(
+                        SequenceIterator::new(Rc::clone(&self.receiver)),
+                    ))))
+                }
+                // iterators are iterable themselves, so a loop can go over one directly.
+                (_, "iterator") => Ok(Rc::clone(&self.receiver)),
+                (LiteralValue::Callable(Callable::Generator(generator)), "hasNext") => Ok(Rc::new(
+                    LiteralValue::Bool(generator.has_next(interpreter, &self.name)?),
+                )),
+                (LiteralValue::Callable(Callable::Generator(generator)), "next") => {
+                    generator.next(interpreter, &self.name)
+                }
+                (LiteralValue::Callable(Callable::SequenceIterator(iterator)), "hasNext") => {
+                    Ok(Rc::new(LiteralValue::Bool(iterator.has_next())))
+                }
+                (LiteralValue::Callable(Callable::SequenceIterator(iterator)), "next") => {
+                    Ok(iterator.next())
+                }
+                _ => {
+                    error(
+                        self.name.get_line(),
+                        self.name.get_column(),
+                        format!("Undefined property '{}'.", method),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    Err(Error::LoxRuntimeError)
+                }
+            }
+        }
+    }
+}
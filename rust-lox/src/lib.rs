@@ -1,13 +1,27 @@
 pub mod args_parser;
+pub mod ast_json;
+pub mod checker;
+pub mod coverage;
+pub mod debugger;
 pub mod environment;
 pub mod error_handling;
+pub mod event_loop;
 pub mod expr;
+pub mod fiber;
+pub mod file_system;
+pub mod generator;
 pub mod interpreter;
+pub mod iterator;
+pub mod optimizer;
 pub mod parser;
+pub mod profiler;
 pub mod resolver;
 pub mod rlox_callable;
 pub mod scanner;
 pub mod stmt;
+pub mod system;
+pub mod test_runner;
+pub mod tree_printer;
 pub mod utils;
 
 use crate::resolver::resolver::Resolver;
@@ -1,78 +1,252 @@
 pub mod args_parser;
+pub mod ast_json;
+pub mod checker;
+pub mod coverage;
+pub mod debugger;
 pub mod environment;
 pub mod error_handling;
+pub mod event_loop;
 pub mod expr;
+pub mod fiber;
+pub mod file_system;
+pub mod generator;
 pub mod interpreter;
+pub mod iterator;
+pub mod optimizer;
 pub mod parser;
+pub mod profiler;
 pub mod resolver;
 pub mod rlox_callable;
 pub mod scanner;
 pub mod stmt;
+pub mod system;
+pub mod test_runner;
+pub mod tree_printer;
 pub mod utils;
 
 use crate::resolver::resolver::Resolver;
-use args_parser::args_parser::Args;
+use args_parser::args_parser::{Args, AstFormat, Command};
+use checker::checker::Checker;
 use clap::Parser;
-use error_handling::error_handling::LOGGER;
-use interpreter::interpreter::Interpreter;
+use coverage::coverage::Coverage;
+use debugger::debugger::Debugger;
+use error_handling::error_handling::{
+    reported_error_count, EXIT_COMPILE_ERROR, EXIT_IO_ERROR, EXIT_RUNTIME_ERROR, EXIT_USAGE, LOGGER,
+};
+use event_loop::event_loop::EventLoop;
+use interpreter::interpreter::{Error, Interpreter, INTERPRETER_STACK_SIZE};
 use log::error;
 use log::LevelFilter;
+use profiler::profiler::Profiler;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use stmt::stmt::StmtGraphvizPrinter;
+use std::time::Duration;
+use stmt::stmt::{Stmt, StmtGraphvizPrinter};
+use test_runner::test_runner::TestOptions;
+use tree_printer::tree_printer::TreePrinter;
 
-fn run(source: String, args: &Args) {
-    let mut scanner = scanner::scan::Scanner::new(source);
+/// Why a run failed, already reported, each kind exiting with its own code.
+enum Failure {
+    Usage,
+    Compile,
+    Runtime,
+    Io,
+    /// Code given to `exit()` or the debugger.
+    Exit(i32),
+}
+
+impl Failure {
+    fn code(&self) -> i32 {
+        match self {
+            Failure::Usage => EXIT_USAGE,
+            Failure::Compile => EXIT_COMPILE_ERROR,
+            Failure::Runtime => EXIT_RUNTIME_ERROR,
+            Failure::Io => EXIT_IO_ERROR,
+            Failure::Exit(code) => *code,
+        }
+    }
+}
+
+fn run(source: String, args: &Args) -> Result<(), Failure> {
+    if let Some(format) = args.dump_tokens {
+        print!("{}", scanner::scan::dump_tokens(source, format));
+        return Ok(());
+    }
+
+    let mut scanner = scanner::scan::Scanner::new(source.clone());
     let tokens = scanner.scan_tokens();
 
+    // the parser reports some errors without giving up.
+    let errors = reported_error_count();
     let mut parser = parser::parser::Parser::new(tokens);
-    let ast = parser.parse().unwrap();
+    let ast = match parser.parse() {
+        Ok(ast) if !scanner.had_error() && reported_error_count() == errors => ast,
+        _ => return Err(Failure::Compile),
+    };
+
+    if let Some(AstFormat::Json) = args.dump_ast {
+        return match ast_json::ast_json::to_json_string(&ast) {
+            Ok(json) => {
+                println!("{}", json);
+                Ok(())
+            }
+            Err(why) => {
+                error!("Could not dump the AST: {}", why);
+                Err(Failure::Runtime)
+            }
+        };
+    }
 
+    run_ast(ast, &source, args)
+}
+
+fn run_ast(ast: Vec<Stmt>, source: &str, args: &Args) -> Result<(), Failure> {
+    if args.check && Checker::new().check(&ast).is_err() {
+        return Err(Failure::Compile);
+    }
+
+    let ast = if args.optimize {
+        optimizer::optimizer::optimize(ast)
+    } else {
+        ast
+    };
+
+    // reports which can't be written don't stop the program, only its exit code tells.
+    let mut io_failed = false;
     if args.graphviz == true {
-        StmtGraphvizPrinter::generate(&ast);
+        let name = match Path::new(&args.src_path).file_stem() {
+            Some(stem) => stem.to_string_lossy().to_string(),
+            None => String::from("ast"),
+        };
+        if let Err(why) =
+            StmtGraphvizPrinter::generate(&ast, &args.out_dir, &name, args.graph_format)
+        {
+            error!(
+                "Could not render the AST graph to {}: {}",
+                args.out_dir, why
+            );
+            io_failed = true;
+        }
     }
 
-    if args.cli_graph == true {
-        // TO BE IMPLEMENTED
+    // scope depths are only known once the resolver ran.
+    if args.cli_graph && !args.scope_depth {
+        print!("{}", TreePrinter::new().print(&ast));
     }
 
     let mut interpreter = Interpreter::new();
+    if args.debug {
+        interpreter.debugger = Some(Debugger::new(source, args.breakpoints.clone()));
+    }
+    if args.profile || args.profile_folded.is_some() {
+        interpreter.profiler = Some(Profiler::new());
+    }
+    if args.coverage {
+        interpreter.coverage = Some(Coverage::new(&ast));
+    }
+    if !args.virtual_clock {
+        interpreter.event_loop = EventLoop::real_time();
+    }
+    interpreter.system.args = args.script_args.clone();
+    interpreter.system.allow_env = args.allow_env;
+    for dir in &args.allow_read {
+        if let Err(why) = interpreter.sandbox.allow_read(dir) {
+            error!("Could not allow reading {}: {}", dir, why);
+            return Err(Failure::Usage);
+        }
+    }
+    for dir in &args.allow_write {
+        if let Err(why) = interpreter.sandbox.allow_write(dir) {
+            error!("Could not allow writing {}: {}", dir, why);
+            return Err(Failure::Usage);
+        }
+    }
+
+    // the resolver reports some errors without giving up.
+    let errors = reported_error_count();
     let mut resolver = Resolver::new(&mut interpreter);
-    match resolver.resolve(&ast) {
-        Ok(_) => {}
-        Err(_) => {
-            std::process::exit(1);
+    if resolver.resolve(&ast).is_err() || reported_error_count() > errors {
+        return Err(Failure::Compile);
+    }
+
+    if args.cli_graph && args.scope_depth {
+        print!(
+            "{}",
+            TreePrinter::with_scope_depths(resolver.interpreter).print(&ast)
+        );
+    }
+
+    let result = resolver.interpreter.interpret(&ast);
+
+    if let Some(profiler) = resolver.interpreter.profiler.as_mut() {
+        profiler.finish();
+        eprint!("{}", profiler.report());
+        if let Some(path) = &args.profile_folded {
+            if let Err(why) = profiler.write_folded_stacks(path) {
+                error!("Could not write folded stacks to {}: {}", path, why);
+                io_failed = true;
+            }
         }
     }
 
-    match resolver.interpreter.interpret(&ast) {
-        Ok(_) => {}
-        Err(_) => {
-            std::process::exit(1);
+    if let Some(coverage) = &resolver.interpreter.coverage {
+        if let Err(why) = coverage.write_reports(&args.src_path, source, &args.coverage_dir) {
+            error!(
+                "Could not write coverage reports to {}: {}",
+                args.coverage_dir, why
+            );
+            io_failed = true;
         }
     }
+
+    match result {
+        Ok(_) if io_failed => Err(Failure::Io),
+        Ok(_) => Ok(()),
+        Err(Error::Exit(code)) => Err(Failure::Exit(code)),
+        Err(_) => Err(Failure::Runtime),
+    }
 }
 
-fn run_file(args: &Args) {
-    let path = Path::new(&args.src_path);
-    if path.exists() {
-        run(
-            fs::read_to_string(path).expect("Given path does not contain an OK file!!"),
-            args,
-        );
+fn run_file(args: &Args) -> Result<(), Failure> {
+    let source = match fs::read_to_string(&args.src_path) {
+        Ok(source) => source,
+        Err(why) => {
+            error!("Could not read {}: {}", args.src_path, why);
+            return Err(Failure::Io);
+        }
+    };
+
+    if args.load_ast {
+        match ast_json::ast_json::from_json_str(&source) {
+            Ok(ast) => run_ast(ast, "", args),
+            Err(why) => {
+                error!("Could not load the AST from {}: {}", args.src_path, why);
+                Err(Failure::Compile)
+            }
+        }
     } else {
-        panic!("Given path: {}, does not exist!!", &args.src_path);
+        run(source, args)
     }
 }
 
-fn run_prompt(args: &Args) {
+/// Runs one line at a time until the end of the input, errors don't stop it but `exit()` does.
+fn run_prompt(args: &Args) -> Result<(), Failure> {
     loop {
         print!("> ");
+        let _ = std::io::stdout().flush();
         let mut input = String::new();
-        std::io::stdin()
-            .read_line(&mut input)
-            .expect("[run_prompt] Failed to read line");
-        run(input, args);
+        match std::io::stdin().read_line(&mut input) {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(why) => {
+                error!("Could not read the input: {}", why);
+                return Err(Failure::Io);
+            }
+        }
+        if let Err(Failure::Exit(code)) = run(input, args) {
+            return Err(Failure::Exit(code));
+        }
     }
 }
 
@@ -90,15 +264,48 @@ fn main() {
             log_level.to_string()
         ));
 
-    // delete old generated files
-    if !utils::utils::clean_folder(utils::utils::GENERATED_FOLDER_PATH) {
-        error!("Could not clean generated folder");
+    let mut args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(why) => {
+            // --help and --version end up here too, printed to stdout.
+            let _ = why.print();
+            std::process::exit(if why.use_stderr() { EXIT_USAGE } else { 0 });
+        }
+    };
+    // `#!/usr/bin/env rlox` runs the file as `rlox <file> <args>...`.
+    if args.src_path.is_empty() && !args.script_args.is_empty() {
+        args.src_path = args.script_args.remove(0);
     }
+    if let Some(Command::Test(test_args)) = &args.command {
+        let mut options = TestOptions::default();
+        if let Some(jobs) = test_args.jobs {
+            options.jobs = jobs;
+        }
+        options.timeout = Duration::from_millis(test_args.timeout);
+        options.interpreter = test_args.interpreter.clone();
 
-    let args = Args::parse();
-    if args.src_path.is_empty() {
-        run_prompt(&args);
-    } else {
-        run_file(&args);
+        let passed = test_runner::test_runner::run_tests(&test_args.path, &options);
+        std::process::exit(if passed { 0 } else { 1 });
+    }
+
+    // deep Lox recursion needs more stack than the main thread has.
+    let interpreter_thread = std::thread::Builder::new()
+        .stack_size(INTERPRETER_STACK_SIZE)
+        .spawn(move || {
+            if args.src_path.is_empty() {
+                run_prompt(&args)
+            } else {
+                run_file(&args)
+            }
+        })
+        .expect("I should be able to spawn the interpreter thread!");
+
+    match interpreter_thread.join() {
+        Ok(Ok(())) => {}
+        Ok(Err(failure)) => std::process::exit(failure.code()),
+        Err(_) => {
+            eprintln!("The interpreter crashed, this is a bug of rlox.");
+            std::process::exit(EXIT_RUNTIME_ERROR);
+        }
     }
 }
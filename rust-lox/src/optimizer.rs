@@ -0,0 +1,277 @@
+pub mod optimizer {
+    use crate::expr::expr::Expr;
+    use crate::interpreter::interpreter::Interpreter;
+    use crate::scanner::scan::{Token, TokenType};
+    use crate::stmt::stmt::{LiteralValue, MatchCase, OptionalParams, Stmt};
+
+    /**
+     * AST pass run between the parser and the resolver when `-O` is given.
+     * Folds operators whose operands are constants and drops `if`/`while` branches
+     * whose condition is constant. Ill-typed constants such as `-"a"` or `1 < nil` are
+     * left untouched so they still fail at runtime, with the same message and position.
+     */
+    pub fn optimize(ast: Vec<Stmt>) -> Vec<Stmt> {
+        ast.into_iter().flat_map(optimize_stmt).collect()
+    }
+
+    /// A statement can disappear entirely, e.g. `while (false) ...`, hence the Vec.
+    fn optimize_stmt(stmt: Stmt) -> Vec<Stmt> {
+        match stmt {
+            Stmt::ExprStmt(expr) => vec![Stmt::ExprStmt(fold(expr))],
+            Stmt::PrintStmt(keyword, expr) => vec![Stmt::PrintStmt(keyword, fold(expr))],
+            Stmt::ReturnStmt(keyword, expr) => vec![Stmt::ReturnStmt(keyword, fold(expr))],
+            Stmt::YieldStmt(keyword, expr) => vec![Stmt::YieldStmt(keyword, fold(expr))],
+            Stmt::VarStmt(name, var_type, expr) => vec![Stmt::VarStmt(name, var_type, fold(expr))],
+            Stmt::ConstStmt(name, var_type, expr) => {
+                vec![Stmt::ConstStmt(name, var_type, fold(expr))]
+            }
+            Stmt::BlockStmt(stmts) => vec![Stmt::BlockStmt(optimize(stmts))],
+            Stmt::ClassStmt(name, superclass, traits, methods, fields) => {
+                vec![Stmt::ClassStmt(
+                    name,
+                    superclass,
+                    traits,
+                    optimize(methods),
+                    fields,
+                )]
+            }
+            Stmt::TraitStmt(name, methods) => vec![Stmt::TraitStmt(name, optimize(methods))],
+            Stmt::Function(name, params, body, types, optional) => {
+                let optional = OptionalParams {
+                    defaults: optional
+                        .defaults
+                        .into_iter()
+                        .map(|default| default.map(fold))
+                        .collect(),
+                    rest: optional.rest,
+                };
+                vec![Stmt::Function(
+                    name,
+                    params,
+                    optimize(body),
+                    types,
+                    optional,
+                )]
+            }
+            Stmt::IfStmt(condition, then_stmt, else_stmt) => match fold(condition) {
+                Expr::Literal(value) => {
+                    if Interpreter::is_truthy_lval(&value) {
+                        optimize_stmt(*then_stmt)
+                    } else {
+                        else_stmt.map_or_else(Vec::new, |else_stmt| optimize_stmt(*else_stmt))
+                    }
+                }
+                condition => vec![Stmt::IfStmt(
+                    condition,
+                    Box::new(single_stmt(optimize_stmt(*then_stmt))),
+                    else_stmt.map(|else_stmt| Box::new(single_stmt(optimize_stmt(*else_stmt)))),
+                )],
+            },
+            Stmt::WhileStmt(condition, body) => match fold(condition) {
+                Expr::Literal(value) if !Interpreter::is_truthy_lval(&value) => Vec::new(),
+                condition => vec![Stmt::WhileStmt(
+                    condition,
+                    Box::new(single_stmt(optimize_stmt(*body))),
+                )],
+            },
+            Stmt::MatchStmt(keyword, subject, cases, default) => vec![Stmt::MatchStmt(
+                keyword,
+                fold(subject),
+                cases
+                    .into_iter()
+                    .map(|case| MatchCase {
+                        guard: case.guard.map(fold),
+                        body: single_stmt(optimize_stmt(case.body)),
+                        ..case
+                    })
+                    .collect(),
+                default.map(|default| Box::new(single_stmt(optimize_stmt(*default)))),
+            )],
+        }
+    }
+
+    /// Branch bodies hold exactly one statement, an empty block stands in for a pruned one.
+    fn single_stmt(mut stmts: Vec<Stmt>) -> Stmt {
+        match stmts.len() {
+            1 => stmts.remove(0),
+            _ => Stmt::BlockStmt(stmts),
+        }
+    }
+
+    fn fold(expr: Expr) -> Expr {
+        match expr {
+            Expr::Binary(left, operator, right) => match (fold(*left), fold(*right)) {
+                (Expr::Literal(left), Expr::Literal(right))
+                    if is_well_typed(&left, &operator, &right) =>
+                {
+                    match Interpreter::binary_operation(&left, &right, &operator) {
+                        Ok(value) => Expr::Literal((*value).clone()),
+                        Err(_) => Expr::Binary(
+                            Box::new(Expr::Literal(left)),
+                            operator,
+                            Box::new(Expr::Literal(right)),
+                        ),
+                    }
+                }
+                (left, right) => Expr::Binary(Box::new(left), operator, Box::new(right)),
+            },
+            Expr::Logical(left, operator, right) => match fold(*left) {
+                Expr::Literal(value) => {
+                    let is_truthy = Interpreter::is_truthy_lval(&value);
+                    let short_circuit = match operator.get_token_type() {
+                        TokenType::Or => is_truthy,
+                        TokenType::QuestionQuestion => value != LiteralValue::Nil,
+                        _ => !is_truthy,
+                    };
+                    if short_circuit {
+                        Expr::Literal(value)
+                    } else {
+                        fold(*right)
+                    }
+                }
+                left => Expr::Logical(Box::new(left), operator, Box::new(fold(*right))),
+            },
+            Expr::Grouping(expression) => match fold(*expression) {
+                Expr::Literal(value) => Expr::Literal(value),
+                expression => Expr::Grouping(Box::new(expression)),
+            },
+            Expr::Unary(operator, right) => match (operator.get_token_type(), fold(*right)) {
+                (TokenType::Minus, Expr::Literal(LiteralValue::Number(number))) => {
+                    Expr::Literal(LiteralValue::Number(-number))
+                }
+                (TokenType::Bang, Expr::Literal(value)) => {
+                    Expr::Literal(LiteralValue::Bool(!Interpreter::is_truthy_lval(&value)))
+                }
+                (_, right) => Expr::Unary(operator, Box::new(right)),
+            },
+            Expr::Call(callee, paren, arguments, names) => Expr::Call(
+                Box::new(fold(*callee)),
+                paren,
+                arguments.into_iter().map(fold).collect(),
+                names,
+            ),
+            Expr::Index(object, bracket, index) => {
+                Expr::Index(Box::new(fold(*object)), bracket, Box::new(fold(*index)))
+            }
+            Expr::Assign(name, value) => Expr::Assign(name, Box::new(fold(*value))),
+            Expr::Get(object, name) => Expr::Get(Box::new(fold(*object)), name),
+            Expr::Set(object, name, value) => {
+                Expr::Set(Box::new(fold(*object)), name, Box::new(fold(*value)))
+            }
+            Expr::Conditional(condition, question, then_expr, else_expr) => {
+                match fold(*condition) {
+                    Expr::Literal(value) => match Interpreter::is_truthy_lval(&value) {
+                        true => fold(*then_expr),
+                        false => fold(*else_expr),
+                    },
+                    condition => Expr::Conditional(
+                        Box::new(condition),
+                        question,
+                        Box::new(fold(*then_expr)),
+                        Box::new(fold(*else_expr)),
+                    ),
+                }
+            }
+            Expr::OptionalGet(object, name) => Expr::OptionalGet(Box::new(fold(*object)), name),
+            Expr::Update(target, operator, value, postfix) => Expr::Update(
+                Box::new(fold(*target)),
+                operator,
+                Box::new(fold(*value)),
+                postfix,
+            ),
+            Expr::Literal(_) | Expr::Variable(_) | Expr::This(_) | Expr::Super(_, _) => expr,
+        }
+    }
+
+    /// Whether the interpreter would evaluate the operator without a runtime error.
+    fn is_well_typed(left: &LiteralValue, operator: &Token, right: &LiteralValue) -> bool {
+        use LiteralValue::{Number, String};
+        match operator.get_token_type() {
+            TokenType::EqualEqual | TokenType::BangEqual => true,
+            TokenType::Plus => matches!(
+                (left, right),
+                (Number(_), Number(_))
+                    | (String(_), String(_))
+                    | (String(_), Number(_))
+                    | (Number(_), String(_))
+            ),
+            TokenType::Ampersand | TokenType::Pipe | TokenType::Caret => {
+                Interpreter::integer(left).is_some() && Interpreter::integer(right).is_some()
+            }
+            TokenType::LessLess | TokenType::GreaterGreater => {
+                Interpreter::integer(left).is_some()
+                    && matches!(Interpreter::integer(right), Some(0..=63))
+            }
+            _ => matches!((left, right), (Number(_), Number(_))),
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::parser::parser::Parser;
+        use crate::scanner::scan::Scanner;
+
+        fn optimize_source(source: &str) -> Vec<Stmt> {
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            optimize(ast)
+        }
+
+        fn printed(stmt: &Stmt) -> &Expr {
+            match stmt {
+                Stmt::PrintStmt(_, expr) => expr,
+                _ => panic!("expected a print statement, got {:?}", stmt),
+            }
+        }
+
+        #[test]
+        fn fold_constant_expressions_ok() {
+            let ast = optimize_source(
+                "print 1 + 2 * (3 - 1);\nprint \"a\" + \"b\";\nprint !(1 < 2) or 4 >= 4;\nprint -(2 / 4) == nil;",
+            );
+            let folded: Vec<&Expr> = ast.iter().map(printed).collect();
+            assert_eq!(
+                folded,
+                vec![
+                    &Expr::Literal(LiteralValue::Number(5.0)),
+                    &Expr::Literal(LiteralValue::String("ab".to_string())),
+                    &Expr::Literal(LiteralValue::Bool(true)),
+                    &Expr::Literal(LiteralValue::Bool(false)),
+                ]
+            );
+        }
+
+        #[test]
+        fn keep_ill_typed_and_variable_operands_ok() {
+            let ast =
+                optimize_source("var a = 1;\nprint -\"a\";\nprint 1 < nil;\nprint a + 2 * 3;");
+            assert!(matches!(printed(&ast[1]), Expr::Unary(_, _)));
+            assert!(matches!(printed(&ast[2]), Expr::Binary(_, _, _)));
+            match printed(&ast[3]) {
+                Expr::Binary(left, _, right) => {
+                    assert!(matches!(**left, Expr::Variable(_)));
+                    assert_eq!(**right, Expr::Literal(LiteralValue::Number(6.0)));
+                }
+                expr => panic!("expected a binary expression, got {:?}", expr),
+            }
+        }
+
+        #[test]
+        fn prune_constant_branches_ok() {
+            let ast = optimize_source(
+                "if (1 > 2) print 1; else print 2;\nif (nil) print 3;\nwhile (false) print 4;\nif (true and \"yes\") { print 5; }",
+            );
+            assert_eq!(ast.len(), 2);
+            assert_eq!(printed(&ast[0]), &Expr::Literal(LiteralValue::Number(2.0)));
+            match &ast[1] {
+                Stmt::BlockStmt(stmts) => assert_eq!(
+                    printed(&stmts[0]),
+                    &Expr::Literal(LiteralValue::Number(5.0))
+                ),
+                stmt => panic!("expected the then block, got {:?}", stmt),
+            }
+        }
+    }
+}
@@ -3,6 +3,8 @@ pub mod parser {
     use crate::error_handling::error_handling::{error, RLoxErrorType};
     use crate::expr::expr::Expr;
     use crate::function_name;
+    use crate::iterator::iterator::Iterate;
+    use crate::rlox_callable::rlox_callable::{Callable, Str};
     /**
      * ! Notes to my self:
      * ! No. 1:
@@ -13,7 +15,9 @@ pub mod parser {
      * *
      */
     use crate::scanner::scan::{Token, TokenType};
-    use crate::stmt::stmt::{LiteralValue, Stmt};
+    use crate::stmt::stmt::{
+        FunctionTypes, LiteralValue, MatchCase, OptionalParams, Pattern, Stmt,
+    };
     use log::debug;
 
     pub struct Parser {
@@ -22,19 +26,38 @@ pub mod parser {
     }
 
     impl Parser {
-        pub fn new(tokens_vec: Vec<Token>) -> Parser {
+        pub fn new(mut tokens_vec: Vec<Token>) -> Parser {
+            // every lookup relies on a closing EOF, tokens which didn't come from the scanner may lack it.
+            if tokens_vec
+                .last()
+                .is_none_or(|token| token.get_token_type() != TokenType::EOF)
+            {
+                let (line, column) = tokens_vec
+                    .last()
+                    .map_or((1, 1), |token| (token.get_line(), token.get_column()));
+                tokens_vec.push(Token::new(TokenType::EOF, String::new(), line, column, 0));
+            }
             Parser {
                 tokens: tokens_vec,
                 current: 0,
             }
         }
 
+        /// Token at `index`, the closing EOF past the end.
+        fn token_at(&self, index: usize) -> Token {
+            match self.tokens.get(index) {
+                Some(token) => token.clone(),
+                None => self.tokens[self.tokens.len() - 1].clone(),
+            }
+        }
+
+        /// Last consumed token, the first one before anything was consumed.
         fn previous(&self) -> Token {
-            self.tokens[self.current as usize - 1].clone()
+            self.token_at((self.current as usize).saturating_sub(1))
         }
 
         fn peek(&self) -> Token {
-            self.tokens[self.current as usize].clone()
+            self.token_at(self.current as usize)
         }
 
         fn is_at_end(&self) -> bool {
@@ -48,6 +71,13 @@ pub mod parser {
             self.peek().token_type_value() == token_type.to_string()
         }
 
+        fn check_next(&self, token_type: TokenType) -> bool {
+            match self.tokens.get(self.current as usize + 1) {
+                Some(token) => token.token_type_value() == token_type.to_string(),
+                None => false,
+            }
+        }
+
         fn advance(&mut self) -> Token {
             if !self.is_at_end() {
                 self.current += 1;
@@ -99,6 +129,32 @@ pub mod parser {
             self.assignment()
         }
 
+        /// `cond ? a : b`, both branches are full expressions so it nests to the right.
+        fn conditional(&mut self) -> Result<Expr, RLoxErrorType> {
+            let condition = self.coalesce()?;
+
+            if self.match_token(vec![TokenType::Question]) {
+                let question = self.previous();
+                let then_expr = self.expression()?;
+                self.consume(
+                    TokenType::Colon,
+                    "Expect ':' after then branch of conditional expression.".to_string(),
+                )?;
+                let else_expr = self.expression()?;
+                return Ok(Expr::Conditional(
+                    Box::new(condition),
+                    question,
+                    Box::new(then_expr),
+                    Box::new(else_expr),
+                ));
+            }
+            Ok(condition)
+        }
+
+        fn coalesce(&mut self) -> Result<Expr, RLoxErrorType> {
+            self.logical_expr_loop(vec![TokenType::QuestionQuestion], Self::or)
+        }
+
         fn or(&mut self) -> Result<Expr, RLoxErrorType> {
             self.logical_expr_loop(vec![TokenType::Or], Self::and)
         }
@@ -122,6 +178,25 @@ pub mod parser {
                     TokenType::Less,
                     TokenType::LessEqual,
                 ],
+                Self::bit_or,
+            )
+        }
+
+        fn bit_or(&mut self) -> Result<Expr, RLoxErrorType> {
+            self.binary_expr_loop(vec![TokenType::Pipe], Self::bit_xor)
+        }
+
+        fn bit_xor(&mut self) -> Result<Expr, RLoxErrorType> {
+            self.binary_expr_loop(vec![TokenType::Caret], Self::bit_and)
+        }
+
+        fn bit_and(&mut self) -> Result<Expr, RLoxErrorType> {
+            self.binary_expr_loop(vec![TokenType::Ampersand], Self::shift)
+        }
+
+        fn shift(&mut self) -> Result<Expr, RLoxErrorType> {
+            self.binary_expr_loop(
+                vec![TokenType::LessLess, TokenType::GreaterGreater],
                 Self::term,
             )
         }
@@ -131,11 +206,14 @@ pub mod parser {
         }
 
         fn factor(&mut self) -> Result<Expr, RLoxErrorType> {
-            self.binary_expr_loop(vec![TokenType::Slash, TokenType::Star], Self::unary)
+            self.binary_expr_loop(
+                vec![TokenType::Slash, TokenType::Star, TokenType::Percent],
+                Self::unary,
+            )
         }
 
         fn unary(&mut self) -> Result<Expr, RLoxErrorType> {
-            if self.match_token(vec![TokenType::Bang, TokenType::Minus]) {
+            if self.match_token(vec![TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
                 let operator = self.previous();
                 let right = match self.unary() {
                     Ok(right_val) => right_val,
@@ -143,7 +221,62 @@ pub mod parser {
                 };
                 return Ok(Expr::Unary(operator, Box::new(right)));
             }
-            self.call()
+            if self.match_token(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+                let operator = self.previous();
+                let target = self.unary()?;
+                return Self::update(target, operator, false);
+            }
+            self.power()
+        }
+
+        /// `**` binds tighter than a unary minus on its left and is right associative.
+        fn power(&mut self) -> Result<Expr, RLoxErrorType> {
+            let expr = self.postfix()?;
+            if self.match_token(vec![TokenType::StarStar]) {
+                let operator = self.previous();
+                let right = self.unary()?;
+                return Ok(Expr::Binary(Box::new(expr), operator, Box::new(right)));
+            }
+            Ok(expr)
+        }
+
+        fn postfix(&mut self) -> Result<Expr, RLoxErrorType> {
+            let expr = self.call()?;
+            if self.match_token(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+                let operator = self.previous();
+                return Self::update(expr, operator, true);
+            }
+            Ok(expr)
+        }
+
+        /// Builds `++`/`--`, only variables and properties can be incremented.
+        fn update(target: Expr, operator: Token, postfix: bool) -> Result<Expr, RLoxErrorType> {
+            let target = Self::update_target(target, &operator)?;
+            Ok(Expr::Update(
+                Box::new(target),
+                operator,
+                Box::new(Expr::Literal(LiteralValue::Number(1.0))),
+                postfix,
+            ))
+        }
+
+        fn update_target(target: Expr, operator: &Token) -> Result<Expr, RLoxErrorType> {
+            match target {
+                Expr::Variable(_) | Expr::Get(_, _) => Ok(target),
+                _ => {
+                    error(
+                        operator.get_line(),
+                        operator.get_column(),
+                        format!(
+                            "Error at '{}': Invalid assignment target.",
+                            operator.get_token_type()
+                        ),
+                        function_name!(),
+                        Some(RLoxErrorType::ParseError),
+                    );
+                    Err(RLoxErrorType::ParseError)
+                }
+            }
         }
 
         fn call(&mut self) -> Result<Expr, RLoxErrorType> {
@@ -155,6 +288,17 @@ pub mod parser {
                 } else if self.match_token(vec![TokenType::Dot]) {
                     let name = self.consume_any_identifier(String::from("property"))?;
                     expr = Expr::Get(Box::new(expr), name);
+                } else if self.match_token(vec![TokenType::QuestionDot]) {
+                    let name = self.consume_any_identifier(String::from("property"))?;
+                    expr = Expr::OptionalGet(Box::new(expr), name);
+                } else if self.match_token(vec![TokenType::LeftBracket]) {
+                    let bracket = self.previous();
+                    let index = self.expression()?;
+                    self.consume(
+                        TokenType::RightBracket,
+                        "Expect ']' after index.".to_string(),
+                    )?;
+                    expr = Expr::Index(Box::new(expr), bracket, Box::new(index));
                 } else {
                     break;
                 }
@@ -162,8 +306,13 @@ pub mod parser {
             Ok(expr)
         }
 
+        /**
+         * Arguments are positional or named, `f(1, b: 2)`, a named argument is an identifier
+         * followed by `:`, positional ones can't come after it.
+         */
         fn finish_call(&mut self, expr: Expr) -> Result<Expr, RLoxErrorType> {
             let mut arguments: Vec<Expr> = Vec::new();
+            let mut names: Vec<Option<Token>> = Vec::new();
             if !self.check(TokenType::RightParen) {
                 loop {
                     if arguments.len() >= 255 {
@@ -179,6 +328,26 @@ pub mod parser {
                         );
                         return Err(RLoxErrorType::ParseError);
                     }
+                    let is_named = matches!(self.peek().get_token_type(), TokenType::Identifier(_))
+                        && self.check_next(TokenType::Colon);
+                    if is_named {
+                        names.push(Some(self.advance()));
+                        self.advance();
+                    } else if names.iter().any(Option::is_some) {
+                        error(
+                            self.peek().get_line(),
+                            self.peek().get_column(),
+                            format!(
+                                "Error at '{}': Positional argument can't follow a named one.",
+                                self.peek().get_token_type()
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::ParseError),
+                        );
+                        return Err(RLoxErrorType::ParseError);
+                    } else {
+                        names.push(None);
+                    }
                     arguments.push(self.expression()?);
                     if !self.match_token(vec![TokenType::Comma]) {
                         break;
@@ -189,7 +358,7 @@ pub mod parser {
                 TokenType::RightParen,
                 "Expect ')' after arguments.".to_string(),
             )?;
-            Ok(Expr::Call(Box::new(expr), paren, arguments))
+            Ok(Expr::Call(Box::new(expr), paren, arguments, names))
         }
 
         fn match_any_number_or_string(&mut self) -> bool {
@@ -221,7 +390,11 @@ pub mod parser {
             error(
                 self.peek().get_line(),
                 self.peek().get_column(),
-                format!("Expect {} name.", kind),
+                format!(
+                    "Error at '{}': Expect {} name.",
+                    self.peek().get_token_type(),
+                    kind
+                ),
                 function_name!(),
                 Some(RLoxErrorType::ParseError),
             );
@@ -245,6 +418,70 @@ pub mod parser {
             Err(RLoxErrorType::ParseError)
         }
 
+        /**
+         * Lowers `"a ${x} b"` to `"a " + str(x) + " b"`, the scanner split the string into
+         * `Interpolation` parts followed by an expression and a final `String` part.
+         * `str` is referenced directly, a user defined `str` does not change interpolation.
+         */
+        fn interpolation(&mut self) -> Result<Expr, RLoxErrorType> {
+            // each part keeps the string token it belongs to, for the position of its `+`.
+            let mut parts: Vec<(Token, Expr)> = Vec::new();
+            loop {
+                let part = self.peek();
+                match part.get_token_type() {
+                    TokenType::Interpolation(text) => {
+                        self.advance();
+                        if !text.is_empty() {
+                            parts.push((part.clone(), Expr::Literal(LiteralValue::String(text))));
+                        }
+                        let str_fun = Expr::Literal(LiteralValue::Callable(Callable::Str(Str {})));
+                        let expr = self.expression()?;
+                        parts.push((
+                            part.clone(),
+                            Expr::Call(Box::new(str_fun), part, vec![expr], vec![None]),
+                        ));
+                    }
+                    TokenType::String(text) => {
+                        self.advance();
+                        if !text.is_empty() {
+                            parts.push((part, Expr::Literal(LiteralValue::String(text))));
+                        }
+                        break;
+                    }
+                    _ => {
+                        error(
+                            part.get_line(),
+                            part.get_column(),
+                            format!(
+                                "Error at '{}': Expect '}}' after interpolated expression.",
+                                part.get_token_type()
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::ParseError),
+                        );
+                        return Err(RLoxErrorType::ParseError);
+                    }
+                }
+            }
+
+            let mut parts = parts.into_iter();
+            let mut expr = match parts.next() {
+                Some((_, first)) => first,
+                None => Expr::Literal(LiteralValue::String(String::new())),
+            };
+            for (token, part) in parts {
+                let plus = Token::new(
+                    TokenType::Plus,
+                    String::from("+"),
+                    token.get_line(),
+                    token.get_column(),
+                    0,
+                );
+                expr = Expr::Binary(Box::new(expr), plus, Box::new(part));
+            }
+            Ok(expr)
+        }
+
         fn primary(&mut self) -> Result<Expr, RLoxErrorType> {
             debug!("{}", self.peek().get_token_type());
 
@@ -274,6 +511,10 @@ pub mod parser {
                 return Ok(Expr::This(self.previous()));
             }
 
+            if let TokenType::Interpolation(_) = self.peek().get_token_type() {
+                return self.interpolation();
+            }
+
             if self.match_any_number_or_string() {
                 match self.previous().get_token_type() {
                     TokenType::String(str) => {
@@ -332,7 +573,7 @@ pub mod parser {
                 self.advance();
                 return Ok(self.previous());
             }
-            let current_token = self.tokens.get(self.current as usize).unwrap();
+            let current_token = self.peek();
             error(
                 current_token.get_line(),
                 current_token.get_column(),
@@ -351,6 +592,25 @@ pub mod parser {
             Ok(expressions)
         }
 
+        /// Parses a single expression spanning all tokens, e.g. debugger input.
+        pub fn parse_expression(&mut self) -> Result<Expr, RLoxErrorType> {
+            let expr = self.expression()?;
+            if !self.is_at_end() {
+                error(
+                    self.peek().get_line(),
+                    self.peek().get_column(),
+                    format!(
+                        "Error at '{}': Expect end of expression.",
+                        self.peek().get_token_type()
+                    ),
+                    function_name!(),
+                    Some(RLoxErrorType::ParseError),
+                );
+                return Err(RLoxErrorType::ParseError);
+            }
+            Ok(expr)
+        }
+
         pub fn statement(&mut self) -> Result<Stmt, RLoxErrorType> {
             match self.peek().get_token_type() {
                 TokenType::Print => self.print_statement(),
@@ -358,9 +618,11 @@ pub mod parser {
                     self.advance();
                     self.return_statement()
                 }
+                TokenType::Yield => self.yield_statement(),
                 TokenType::While => self.while_statement(),
                 TokenType::For => self.for_statement(),
                 TokenType::If => self.if_statement(),
+                TokenType::Match => self.match_statement(),
                 TokenType::LeftBrace => {
                     self.advance();
                     Ok(Stmt::BlockStmt(self.block_statement()?))
@@ -379,10 +641,10 @@ pub mod parser {
         }
 
         pub fn print_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
-            self.advance();
+            let keyword = self.advance();
             let value = self.expression()?;
             self.consume(TokenType::Semicolon, "Expect ';' after value.".to_string())?;
-            Ok(Stmt::PrintStmt(value))
+            Ok(Stmt::PrintStmt(keyword, value))
         }
 
         pub fn return_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
@@ -398,6 +660,23 @@ pub mod parser {
             Ok(Stmt::ReturnStmt(keyword, value))
         }
 
+        /**
+         * `yield value;` suspends the generator the function call returned, `next()` resumes
+         * it after the statement. `yield;` yields nil.
+         */
+        fn yield_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
+            let keyword = self.advance();
+            let mut value = Expr::Literal(LiteralValue::Nil);
+            if !self.check(TokenType::Semicolon) {
+                value = self.expression()?;
+            }
+            self.consume(
+                TokenType::Semicolon,
+                "Expect ';' after yield value.".to_string(),
+            )?;
+            Ok(Stmt::YieldStmt(keyword, value))
+        }
+
         fn while_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
             self.consume(
                 TokenType::While,
@@ -420,6 +699,19 @@ pub mod parser {
             self.consume(TokenType::For, "Expect 'for' after 'for'.".to_string())?;
             self.consume(TokenType::LeftParen, "Expect '(' after 'for'.".to_string())?;
 
+            // `for (var item in items)`, the `var` being optional there.
+            let name_at = self.current as usize + self.check(TokenType::Var) as usize;
+            let is_for_in = matches!(
+                self.tokens.get(name_at).map(Token::get_token_type),
+                Some(TokenType::Identifier(_))
+            ) && self
+                .tokens
+                .get(name_at + 1)
+                .is_some_and(|token| token.get_token_type() == TokenType::In);
+            if is_for_in {
+                return self.for_in_statement();
+            }
+
             let initializer = if self.match_token(vec![TokenType::Semicolon]) {
                 None
             } else if self.match_token(vec![TokenType::Var]) {
@@ -476,6 +768,79 @@ pub mod parser {
             Ok(body)
         }
 
+        /**
+         * `for (var item in items) body` is run as
+         * `{ var iterator = iterate(items); while (iterator.hasNext()) { var item = iterator.next(); body } }`,
+         * `item` being declared again in every iteration, closures capture each element separately.
+         * `iterate` is the native calling `items.iterator()`, which reports a value that can't be
+         * iterated at `items`. The iterator variable has a name no identifier can have, so the
+         * body can't see it.
+         */
+        fn for_in_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
+            self.match_token(vec![TokenType::Var]);
+            let name = self.consume_any_identifier("variable".to_string())?;
+            let keyword = self.consume(
+                TokenType::In,
+                "Expect 'in' after loop variable.".to_string(),
+            )?;
+            let iterable = self.expression()?;
+            self.consume(
+                TokenType::RightParen,
+                "Expect ')' after loop iterable.".to_string(),
+            )?;
+            let body = self.statement()?;
+
+            // the resolver tells variables apart by their position, uses with different depths get different ones.
+            let identifier = |lexeme: &str, at: &Token| {
+                Token::new(
+                    TokenType::Identifier(lexeme.to_string()),
+                    lexeme.to_string(),
+                    at.get_line(),
+                    at.get_column(),
+                    0,
+                )
+            };
+            let call = |object: Expr, method: &str, at: &Token| {
+                Expr::Call(
+                    Box::new(Expr::Get(Box::new(object), identifier(method, at))),
+                    Token::new(
+                        TokenType::RightParen,
+                        String::from(")"),
+                        at.get_line(),
+                        at.get_column(),
+                        0,
+                    ),
+                    vec![],
+                    vec![],
+                )
+            };
+            let iterator = |at: &Token| Expr::Variable(identifier("for-in iterator", at));
+            let at = iterable.first_token().unwrap_or(&keyword).clone();
+            let iterate = Expr::Call(
+                Box::new(Expr::Literal(LiteralValue::Callable(Callable::Iterate(
+                    Iterate {},
+                )))),
+                Token::new(
+                    TokenType::RightParen,
+                    String::from(")"),
+                    at.get_line(),
+                    at.get_column(),
+                    0,
+                ),
+                vec![iterable],
+                vec![],
+            );
+
+            let next = Stmt::VarStmt(name.clone(), None, call(iterator(&name), "next", &name));
+            Ok(Stmt::BlockStmt(vec![
+                Stmt::VarStmt(identifier("for-in iterator", &keyword), None, iterate),
+                Stmt::WhileStmt(
+                    call(iterator(&keyword), "hasNext", &keyword),
+                    Box::new(Stmt::BlockStmt(vec![next, body])),
+                ),
+            ]))
+        }
+
         fn if_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
             self.consume(TokenType::If, "Expect 'if' after 'if'.".to_string())?;
             self.consume(TokenType::LeftParen, "Expect '(' after 'if'.".to_string())?;
@@ -498,6 +863,123 @@ pub mod parser {
             return Ok(Stmt::IfStmt(expr, Box::new(then_branch), else_branch));
         }
 
+        fn match_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
+            let keyword = self.consume(TokenType::Match, "Expect 'match'.".to_string())?;
+            self.consume(
+                TokenType::LeftParen,
+                "Expect '(' after 'match'.".to_string(),
+            )?;
+            let subject = self.expression()?;
+            self.consume(
+                TokenType::RightParen,
+                "Expect ')' after match value.".to_string(),
+            )?;
+            self.consume(
+                TokenType::LeftBrace,
+                "Expect '{' before match cases.".to_string(),
+            )?;
+
+            let mut cases = Vec::new();
+            while self.match_token(vec![TokenType::Case]) {
+                let case_keyword = self.previous();
+                let mut patterns = vec![self.pattern()?];
+                while self.match_token(vec![TokenType::Comma]) {
+                    patterns.push(self.pattern()?);
+                }
+                // a binding has no value when another pattern of the case matched.
+                if patterns.len() > 1 {
+                    if let Some(Pattern::Class(_, Some(binding))) = patterns
+                        .iter()
+                        .find(|pattern| matches!(pattern, Pattern::Class(_, Some(_))))
+                    {
+                        error(
+                            binding.get_line(),
+                            binding.get_column(),
+                            format!(
+                                "Error at '{}': Can't bind a name in a case with several patterns.",
+                                binding.get_token_type()
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::ParseError),
+                        );
+                        return Err(RLoxErrorType::ParseError);
+                    }
+                }
+
+                let guard = if self.match_token(vec![TokenType::If]) {
+                    Some(self.expression()?)
+                } else {
+                    None
+                };
+                self.consume(
+                    TokenType::EqualGreater,
+                    "Expect '=>' after case pattern.".to_string(),
+                )?;
+                cases.push(MatchCase {
+                    keyword: case_keyword,
+                    patterns,
+                    guard,
+                    body: self.statement()?,
+                });
+            }
+
+            let default = if self.match_token(vec![TokenType::Default]) {
+                self.consume(
+                    TokenType::EqualGreater,
+                    "Expect '=>' after 'default'.".to_string(),
+                )?;
+                Some(Box::new(self.statement()?))
+            } else {
+                None
+            };
+            // the default case comes last, it would hide the cases after it.
+            self.consume(
+                TokenType::RightBrace,
+                "Expect '}' after match cases.".to_string(),
+            )?;
+
+            Ok(Stmt::MatchStmt(keyword, subject, cases, default))
+        }
+
+        /// A literal, possibly negated number, or a class name with an optional binding.
+        fn pattern(&mut self) -> Result<Pattern, RLoxErrorType> {
+            if self.match_any_identifier() {
+                let class = self.previous();
+                let binding = if self.match_any_identifier() {
+                    Some(self.previous())
+                } else {
+                    None
+                };
+                return Ok(Pattern::Class(class, binding));
+            }
+
+            let negated = self.match_token(vec![TokenType::Minus]);
+            let token = self.peek();
+            let value = match token.get_token_type() {
+                TokenType::Number(num) if negated => LiteralValue::Number(-num),
+                TokenType::Number(num) => LiteralValue::Number(num),
+                TokenType::String(str) if !negated => LiteralValue::String(str),
+                TokenType::True if !negated => LiteralValue::Bool(true),
+                TokenType::False if !negated => LiteralValue::Bool(false),
+                TokenType::Nil if !negated => LiteralValue::Nil,
+                _ => {
+                    error(
+                        token.get_line(),
+                        token.get_column(),
+                        format!(
+                            "Error at '{}': Expect case pattern.",
+                            token.get_token_type()
+                        ),
+                        function_name!(),
+                        Some(RLoxErrorType::ParseError),
+                    );
+                    return Err(RLoxErrorType::ParseError);
+                }
+            };
+            self.advance();
+            Ok(Pattern::Literal(token, value))
+        }
+
         pub fn expression_statement(&mut self) -> Result<Stmt, RLoxErrorType> {
             let expr = self.expression()?;
             self.consume(
@@ -512,6 +994,10 @@ pub mod parser {
                 return self.class_declaration();
             }
 
+            if self.match_token(vec![TokenType::Trait]) {
+                return self.trait_declaration();
+            }
+
             if self.match_token(vec![TokenType::Fun]) {
                 return self.function("function".to_string());
             }
@@ -519,6 +1005,10 @@ pub mod parser {
             if self.match_token(vec![TokenType::Var]) {
                 return self.var_declaration();
             }
+
+            if self.match_token(vec![TokenType::Const]) {
+                return self.const_declaration();
+            }
             self.statement()
         }
 
@@ -531,14 +1021,31 @@ pub mod parser {
                 superclass = Some(Expr::Variable(self.previous()));
             }
 
+            let mut traits = Vec::new();
+            if self.match_token(vec![TokenType::With]) {
+                loop {
+                    traits.push(Expr::Variable(
+                        self.consume_any_identifier("trait".to_string())?,
+                    ));
+                    if !self.match_token(vec![TokenType::Comma]) {
+                        break;
+                    }
+                }
+            }
+
             self.consume(
                 TokenType::LeftBrace,
                 "Expect '{' before class body.".to_string(),
             )?;
 
             let mut methods: Vec<Stmt> = Vec::new();
+            let mut fields: Vec<(Token, Token)> = Vec::new();
             while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-                methods.push(self.function("method".to_string())?);
+                if self.check_next(TokenType::Colon) {
+                    fields.push(self.field_declaration()?);
+                } else {
+                    methods.push(self.function("method".to_string())?);
+                }
             }
 
             self.consume(
@@ -546,7 +1053,56 @@ pub mod parser {
                 "Expect '}' after class body.".to_string(),
             )?;
 
-            Ok(Stmt::ClassStmt(name, superclass, methods))
+            Ok(Stmt::ClassStmt(name, superclass, traits, methods, fields))
+        }
+
+        /// `trait Name { methods }`, a trait only holds methods, they are copied into classes.
+        fn trait_declaration(&mut self) -> Result<Stmt, RLoxErrorType> {
+            let name: Token = self.consume_any_identifier("trait".to_string())?;
+            self.consume(
+                TokenType::LeftBrace,
+                "Expect '{' before trait body.".to_string(),
+            )?;
+
+            let mut methods: Vec<Stmt> = Vec::new();
+            while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+                methods.push(self.function("method".to_string())?);
+            }
+
+            self.consume(
+                TokenType::RightBrace,
+                "Expect '}' after trait body.".to_string(),
+            )?;
+
+            Ok(Stmt::TraitStmt(name, methods))
+        }
+
+        /// Typed field of a class body, `name: type;`.
+        fn field_declaration(&mut self) -> Result<(Token, Token), RLoxErrorType> {
+            let name = self.consume_any_identifier("field".to_string())?;
+            self.consume(TokenType::Colon, "Expect ':' after field name.".to_string())?;
+            let field_type = self.type_name()?;
+            self.consume(
+                TokenType::Semicolon,
+                "Expect ';' after field type.".to_string(),
+            )?;
+            Ok((name, field_type))
+        }
+
+        /// Optional `: type` after a variable, parameter or parameter list.
+        fn type_annotation(&mut self) -> Result<Option<Token>, RLoxErrorType> {
+            if !self.match_token(vec![TokenType::Colon]) {
+                return Ok(None);
+            }
+            self.type_name().map(Some)
+        }
+
+        /// A type is named by a class name, one of the builtin names, `nil` or `fun`.
+        fn type_name(&mut self) -> Result<Token, RLoxErrorType> {
+            if self.match_token(vec![TokenType::Nil, TokenType::Fun]) {
+                return Ok(self.previous());
+            }
+            self.consume_any_identifier("type".to_string())
         }
 
         fn function(&mut self, kind: String) -> Result<Stmt, RLoxErrorType> {
@@ -556,6 +1112,8 @@ pub mod parser {
                 format!("Expect '(' after {} name.", kind.clone()),
             )?;
             let mut parameters: Vec<Token> = Vec::new();
+            let mut types = FunctionTypes::default();
+            let mut optional = OptionalParams::default();
             if !self.check(TokenType::RightParen) {
                 loop {
                     if parameters.len() >= 255 {
@@ -567,23 +1125,53 @@ pub mod parser {
                             Some(RLoxErrorType::ParseError),
                         );
                     }
-                    parameters.push(self.consume_any_identifier("parameter".to_string())?);
+                    optional.rest = self.match_token(vec![TokenType::DotDotDot]);
+                    let parameter = self.consume_any_identifier("parameter".to_string())?;
+                    types.params.push(self.type_annotation()?);
+                    let default = match optional.rest {
+                        false if self.match_token(vec![TokenType::Equal]) => {
+                            Some(self.expression()?)
+                        }
+                        _ => None,
+                    };
+                    if default.is_none()
+                        && !optional.rest
+                        && optional.defaults.iter().any(Option::is_some)
+                    {
+                        error(
+                            parameter.get_line(),
+                            parameter.get_column(),
+                            format!(
+                                "Error at '{}': Parameter without a default value can't follow one with a default.",
+                                parameter.get_token_type()
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::ParseError),
+                        );
+                        return Err(RLoxErrorType::ParseError);
+                    }
+                    parameters.push(parameter);
+                    optional.defaults.push(default);
 
-                    if !self.match_token(vec![TokenType::Comma]) {
+                    if optional.rest || !self.match_token(vec![TokenType::Comma]) {
                         break;
                     }
                 }
             }
             self.consume(
                 TokenType::RightParen,
-                "Expect ')' after parameters.".to_string(),
+                match optional.rest {
+                    true => "Expect ')' after rest parameter.".to_string(),
+                    false => "Expect ')' after parameters.".to_string(),
+                },
             )?;
+            types.result = self.type_annotation()?;
             self.consume(
                 TokenType::LeftBrace,
                 format!("Expect '{{' before {} body.", kind.clone()),
             )?;
             let body = self.block_statement()?;
-            Ok(Stmt::Function(name, parameters, body))
+            Ok(Stmt::Function(name, parameters, body, types, optional))
         }
 
         fn var_declaration(&mut self) -> Result<Stmt, RLoxErrorType> {
@@ -601,6 +1189,7 @@ pub mod parser {
                 return Err(RLoxErrorType::ParseError);
             }
             let name = self.previous();
+            let var_type = self.type_annotation()?;
             let mut initializer = Expr::Literal(LiteralValue::Nil);
             if self.match_token(vec![TokenType::Equal]) {
                 initializer = self.expression()?;
@@ -610,11 +1199,30 @@ pub mod parser {
                 TokenType::Semicolon,
                 "Expect ';' after variable declaration.".to_string(),
             )?;
-            Ok(Stmt::VarStmt(name, initializer))
+            Ok(Stmt::VarStmt(name, var_type, initializer))
+        }
+
+        /**
+         * Unlike `var`, a constant has to be initialized, it can't be assigned afterwards.
+         */
+        fn const_declaration(&mut self) -> Result<Stmt, RLoxErrorType> {
+            let name = self.consume_any_identifier("constant".to_string())?;
+            let const_type = self.type_annotation()?;
+            self.consume(
+                TokenType::Equal,
+                "Expect '=' after constant name.".to_string(),
+            )?;
+            let initializer = self.expression()?;
+
+            self.consume(
+                TokenType::Semicolon,
+                "Expect ';' after constant declaration.".to_string(),
+            )?;
+            Ok(Stmt::ConstStmt(name, const_type, initializer))
         }
 
         fn assignment(&mut self) -> Result<Expr, RLoxErrorType> {
-            let expr = self.or()?;
+            let expr = self.conditional()?;
 
             /*
              * * * Note: we enter the if statement only if we have an assignment,
@@ -645,7 +1253,41 @@ pub mod parser {
                     }
                 }
             }
+
+            if self.match_token(vec![
+                TokenType::PlusEqual,
+                TokenType::MinusEqual,
+                TokenType::StarEqual,
+                TokenType::SlashEqual,
+                TokenType::PercentEqual,
+            ]) {
+                let operator = self.previous();
+                let value = self.assignment()?;
+                let target = Self::update_target(expr, &operator)?;
+                return Ok(Expr::Update(
+                    Box::new(target),
+                    operator,
+                    Box::new(value),
+                    false,
+                ));
+            }
             Ok(expr)
         }
     }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn parse_without_eof_ok() {
+            assert!(Parser::new(Vec::new()).parse().unwrap().is_empty());
+
+            let var = Token::new(TokenType::Var, String::from("var"), 1, 1, 3);
+            assert_eq!(
+                Parser::new(vec![var]).parse(),
+                Err(RLoxErrorType::ParseError)
+            );
+        }
+    }
 }
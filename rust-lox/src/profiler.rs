@@ -0,0 +1,233 @@
+pub mod profiler {
+    use rustc_hash::FxHashMap as HashMap;
+    use std::fs::File;
+    use std::io::prelude::*;
+    use std::time::{Duration, Instant};
+
+    /// Name of the frame which accounts for the time spent in top-level code.
+    pub const SCRIPT_FRAME: &str = "<script>";
+
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct FunctionStats {
+        pub calls: u64,
+        pub inclusive: Duration,
+        pub exclusive: Duration,
+    }
+
+    struct Frame {
+        name: String,
+        start: Instant,
+        children: Duration,
+    }
+
+    /// Open frames of a call stack which is switched out, see `Profiler::detach`.
+    pub struct DetachedFrames {
+        frames: Vec<Frame>,
+        since: Instant,
+    }
+
+    /**
+     * Records call counts and timings of Lox functions, methods and classes.
+     * Inclusive time contains the time spent in callees, exclusive time does not.
+     */
+    pub struct Profiler {
+        stats: HashMap<String, FunctionStats>,
+        folded: HashMap<String, Duration>,
+        stack: Vec<Frame>,
+    }
+
+    impl Default for Profiler {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Profiler {
+        pub fn new() -> Self {
+            let mut profiler = Self {
+                stats: HashMap::default(),
+                folded: HashMap::default(),
+                stack: Vec::new(),
+            };
+            profiler.enter(String::from(SCRIPT_FRAME));
+            profiler
+        }
+
+        pub fn enter(&mut self, name: String) {
+            self.stack.push(Frame {
+                name,
+                start: Instant::now(),
+                children: Duration::ZERO,
+            });
+        }
+
+        pub fn exit(&mut self) {
+            let frame = match self.stack.pop() {
+                Some(frame) => frame,
+                None => return,
+            };
+            let elapsed = frame.start.elapsed();
+            let exclusive = elapsed.saturating_sub(frame.children);
+
+            let stack_path = self
+                .stack
+                .iter()
+                .map(|parent| parent.name.as_str())
+                .chain(std::iter::once(frame.name.as_str()))
+                .collect::<Vec<&str>>()
+                .join(";");
+            *self.folded.entry(stack_path).or_default() += exclusive;
+
+            // recursive calls are already accounted for by the outermost frame.
+            let is_recursive = self.stack.iter().any(|parent| parent.name == frame.name);
+            let stats = self.stats.entry(frame.name).or_default();
+            stats.calls += 1;
+            stats.exclusive += exclusive;
+            if !is_recursive {
+                stats.inclusive += elapsed;
+            }
+
+            if let Some(parent) = self.stack.last_mut() {
+                parent.children += elapsed;
+            }
+        }
+
+        /// Number of open frames.
+        pub fn depth(&self) -> usize {
+            self.stack.len()
+        }
+
+        /**
+         * Takes the frames above the first `depth` ones off the stack, for a call stack which is
+         * switched out(a suspended fiber). The time until `attach` isn't accounted to them.
+         */
+        pub fn detach(&mut self, depth: usize) -> DetachedFrames {
+            DetachedFrames {
+                frames: self.stack.split_off(depth.min(self.stack.len())),
+                since: Instant::now(),
+            }
+        }
+
+        /// Puts detached frames back on top of the stack and returns the depth they start at.
+        pub fn attach(&mut self, detached: DetachedFrames) -> usize {
+            let depth = self.stack.len();
+            let paused = detached.since.elapsed();
+            self.stack
+                .extend(detached.frames.into_iter().map(|frame| Frame {
+                    start: frame.start + paused,
+                    ..frame
+                }));
+            depth
+        }
+
+        /// Closes every frame which is still open, including the script frame.
+        pub fn finish(&mut self) {
+            while !self.stack.is_empty() {
+                self.exit();
+            }
+        }
+
+        pub fn stats(&self) -> &HashMap<String, FunctionStats> {
+            &self.stats
+        }
+
+        /// Table of all recorded functions sorted by exclusive time.
+        pub fn report(&self) -> String {
+            let mut entries: Vec<(&String, &FunctionStats)> = self.stats.iter().collect();
+            entries.sort_by(|(left_name, left), (right_name, right)| {
+                right
+                    .exclusive
+                    .cmp(&left.exclusive)
+                    .then_with(|| left_name.cmp(right_name))
+            });
+
+            let mut report = format!(
+                "{:>10} {:>15} {:>15}  {}\n",
+                "calls", "inclusive(ms)", "exclusive(ms)", "function"
+            );
+            for (name, stats) in entries {
+                report.push_str(&format!(
+                    "{:>10} {:>15.3} {:>15.3}  {}\n",
+                    stats.calls,
+                    stats.inclusive.as_secs_f64() * 1000.0,
+                    stats.exclusive.as_secs_f64() * 1000.0,
+                    name
+                ));
+            }
+            report
+        }
+
+        /// Folded stacks(one `frame;frame;frame microseconds` per line) for flame graph tools.
+        pub fn folded_stacks(&self) -> String {
+            let mut stacks: Vec<(&String, &Duration)> = self.folded.iter().collect();
+            stacks.sort();
+
+            let mut folded = String::new();
+            for (stack, duration) in stacks {
+                folded.push_str(&format!("{} {}\n", stack, duration.as_micros()));
+            }
+            folded
+        }
+
+        pub fn write_folded_stacks(&self, path: &str) -> std::io::Result<()> {
+            let mut file = File::create(path)?;
+            file.write_all(self.folded_stacks().as_bytes())
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn count_calls_and_nesting_ok() {
+            let mut profiler = Profiler::new();
+            profiler.enter(String::from("outer"));
+            profiler.enter(String::from("inner"));
+            profiler.exit();
+            profiler.enter(String::from("inner"));
+            profiler.exit();
+            profiler.exit();
+            profiler.finish();
+
+            assert_eq!(profiler.stats()["outer"].calls, 1);
+            assert_eq!(profiler.stats()["inner"].calls, 2);
+            assert!(profiler.stats()["outer"].inclusive >= profiler.stats()["inner"].inclusive);
+
+            let folded = profiler.folded_stacks();
+            assert!(folded.contains("<script>;outer;inner "));
+            assert!(folded.contains("<script>;outer "));
+        }
+
+        #[test]
+        fn recursive_inclusive_time_counted_once_ok() {
+            let mut profiler = Profiler::new();
+            profiler.enter(String::from("fib"));
+            profiler.enter(String::from("fib"));
+            std::thread::sleep(Duration::from_millis(2));
+            profiler.exit();
+            profiler.exit();
+            profiler.finish();
+
+            let fib = &profiler.stats()["fib"];
+            assert_eq!(fib.calls, 2);
+            assert!(fib.inclusive <= profiler.stats()[SCRIPT_FRAME].inclusive);
+        }
+
+        #[test]
+        fn detached_frames_exclude_paused_time_ok() {
+            let mut profiler = Profiler::new();
+            profiler.enter(String::from("fiber"));
+            let detached = profiler.detach(1);
+            assert_eq!(profiler.depth(), 1);
+            std::thread::sleep(Duration::from_millis(5));
+            assert_eq!(profiler.attach(detached), 1);
+            profiler.exit();
+            profiler.finish();
+
+            let fiber = &profiler.stats()["fiber"];
+            assert!(fiber.inclusive < Duration::from_millis(5));
+            assert!(profiler.folded_stacks().contains("<script>;fiber "));
+        }
+    }
+}
@@ -1,19 +1,23 @@
 pub mod resolver {
 
+    use crate::environment::environment::report_constant_assignment;
     use crate::error_handling::error_handling::{error, RLoxErrorType};
     use crate::expr::expr::Expr;
     use crate::expr::expr::Visitor;
     use crate::function_name;
     use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::rlox_callable::rlox_callable::natives;
     use crate::scanner::scan::Token;
     use crate::stmt::stmt::Stmt;
-    use crate::stmt::stmt::{LiteralValue, StmtVisitor};
+    use crate::stmt::stmt::{LiteralValue, MatchCase, OptionalParams, Pattern, StmtVisitor};
 
     #[derive(Clone, Debug, PartialEq)]
     pub enum ClassType {
         None,
         Class,
         SubClass,
+        /// `super` in a trait method refers to the superclass of the class using the trait.
+        Trait,
     }
 
     #[derive(Clone, Debug, PartialEq)]
@@ -27,22 +31,23 @@ pub mod resolver {
     pub struct Resolver<'a> {
         pub interpreter: &'a mut Interpreter,
         scopes: Vec<Vec<(String, bool)>>,
+        /// Declarations of the constants of each scope in `scopes`.
+        constants: Vec<Vec<Token>>,
         current_class: ClassType,
         current_fn: FunctionType,
     }
 
     impl<'a> Resolver<'a> {
         pub fn new(interpreter: &'a mut Interpreter) -> Self {
-            let mut scopes_local = Vec::new();
-            let clock_fun = (String::from("clock"), true);
-            let unix_clock_fun = (String::from("unixClock"), true);
-            scopes_local.push(Vec::new());
-            scopes_local.last_mut().unwrap().push(clock_fun);
-            scopes_local.last_mut().unwrap().push(unix_clock_fun);
+            let scopes_local = vec![natives()
+                .into_iter()
+                .map(|(name, _)| (name, true))
+                .collect()];
 
             Self {
                 interpreter,
                 scopes: scopes_local,
+                constants: vec![Vec::new()],
                 current_class: ClassType::None,
                 current_fn: FunctionType::None,
             }
@@ -50,10 +55,12 @@ pub mod resolver {
 
         fn begin_scope(&mut self) {
             self.scopes.push(Vec::new());
+            self.constants.push(Vec::new());
         }
 
         fn end_scope(&mut self) {
             self.scopes.pop();
+            self.constants.pop();
         }
 
         fn resolve_expr(&mut self, expr: &Expr) -> Result<(), Error> {
@@ -93,6 +100,27 @@ pub mod resolver {
                 }
             }
 
+            // globals may be redeclared, but not over a constant.
+            let key = name.get_token_type().to_string();
+            if let Some(constants) = self.constants.last() {
+                if constants
+                    .iter()
+                    .any(|constant| constant.get_token_type().to_string() == key)
+                {
+                    error(
+                        name.get_line(),
+                        name.get_column(),
+                        format!(
+                            "Error at '{}': Already a constant with this name in this scope.",
+                            key
+                        ),
+                        function_name!(),
+                        Some(RLoxErrorType::RuntimeError),
+                    );
+                    return Err(Error::LoxRuntimeError);
+                }
+            }
+
             if let Some(scope) = self.scopes.last_mut() {
                 scope.push((name.get_token_type().to_string(), false));
             }
@@ -153,18 +181,43 @@ pub mod resolver {
             }
         }
 
+        /// Rejects an assignment when the innermost declaration of `name` is a constant.
+        fn check_assignable(&self, name: &Token) -> Result<(), Error> {
+            let key = name.get_token_type().to_string();
+            for (i, scope) in self.scopes.iter().enumerate().rev() {
+                if self.contains_key(name, scope) {
+                    return match self.constants[i]
+                        .iter()
+                        .find(|constant| constant.get_token_type().to_string() == key)
+                    {
+                        Some(declaration) => {
+                            report_constant_assignment(name, declaration);
+                            Err(Error::LoxRuntimeError)
+                        }
+                        None => Ok(()),
+                    };
+                }
+            }
+            Ok(())
+        }
+
         fn resolve_function(
             &mut self,
             _name: &Token,
             params: &Vec<Token>,
             body: &Vec<Stmt>,
+            optional: &OptionalParams,
             fn_type: FunctionType,
         ) -> Result<(), Error> {
             let enclosing_fn = self.current_fn.clone();
             self.current_fn = fn_type;
 
             self.begin_scope();
-            for param in params {
+            for (param, default) in params.iter().zip(&optional.defaults) {
+                // a default value only sees the parameters before its own.
+                if let Some(default) = default {
+                    self.resolve_expr(default)?;
+                }
                 self.declare(param)?;
                 self.define(param);
             }
@@ -179,6 +232,7 @@ pub mod resolver {
     impl Visitor<Result<(), Error>> for Resolver<'_> {
         fn visit_assign_expr(&mut self, token: &Token, expr: &Expr) -> Result<(), Error> {
             self.resolve_expr(expr)?;
+            self.check_assignable(token)?;
             self.resolve_local(token);
             Ok(())
         }
@@ -199,6 +253,7 @@ pub mod resolver {
             callee: &Expr,
             _paren: &Token,
             arguments: &Vec<Expr>,
+            _names: &Vec<Option<Token>>,
         ) -> Result<(), Error> {
             self.resolve_expr(callee)?;
 
@@ -239,6 +294,50 @@ pub mod resolver {
             Ok(())
         }
 
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            _operator: &Token,
+            value: &Expr,
+            _postfix: bool,
+        ) -> Result<(), Error> {
+            self.resolve_expr(value)?;
+            if let Expr::Variable(name) = target {
+                self.check_assignable(name)?;
+            }
+            self.resolve_expr(target)?;
+            Ok(())
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            _question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> Result<(), Error> {
+            self.resolve_expr(condition)?;
+            self.resolve_expr(then_expr)?;
+            self.resolve_expr(else_expr)?;
+            Ok(())
+        }
+
+        fn visit_optional_get_expr(&mut self, object: &Expr, _name: &Token) -> Result<(), Error> {
+            self.resolve_expr(object)?;
+            Ok(())
+        }
+
+        fn visit_index_expr(
+            &mut self,
+            object: &Expr,
+            _bracket: &Token,
+            index: &Expr,
+        ) -> Result<(), Error> {
+            self.resolve_expr(object)?;
+            self.resolve_expr(index)?;
+            Ok(())
+        }
+
         fn visit_this_expr(&mut self, keyword: &Token) -> Result<(), Error> {
             if self.current_class == ClassType::None {
                 error(
@@ -270,7 +369,9 @@ pub mod resolver {
                     Some(RLoxErrorType::RuntimeError),
                 );
                 return Err(Error::LoxRuntimeError);
-            } else if self.current_class != ClassType::SubClass {
+            } else if self.current_class != ClassType::SubClass
+                && self.current_class != ClassType::Trait
+            {
                 error(
                     keyword.get_line(),
                     keyword.get_column(),
@@ -334,6 +435,7 @@ pub mod resolver {
             &mut self,
             name: &Token,
             superclass: &Option<Expr>,
+            traits: &Vec<Expr>,
             methods: &Vec<Stmt>,
         ) -> Result<(), Error> {
             let enclosing_class = self.current_class.clone();
@@ -341,6 +443,33 @@ pub mod resolver {
             self.declare(name)?;
             self.define(name);
 
+            for (idx, used_trait) in traits.iter().enumerate() {
+                if let Expr::Variable(trait_name) = used_trait {
+                    let trait_str = trait_name.get_token_type().to_string();
+                    let message = if trait_str == name.get_token_type().to_string() {
+                        Some("A class can't use itself as a trait.")
+                    } else if traits[..idx].iter().any(|previous| {
+                        matches!(previous, Expr::Variable(previous)
+                            if previous.get_token_type().to_string() == trait_str)
+                    }) {
+                        Some("A class can't use the same trait twice.")
+                    } else {
+                        None
+                    };
+                    if let Some(message) = message {
+                        error(
+                            trait_name.get_line(),
+                            trait_name.get_column(),
+                            format!("Error at '{}': {}", trait_str, message),
+                            function_name!(),
+                            Some(RLoxErrorType::RuntimeError),
+                        );
+                        return Err(Error::LoxRuntimeError);
+                    }
+                }
+                self.resolve_expr(used_trait)?;
+            }
+
             if let Some(supper) = superclass {
                 if let Expr::Variable(supper_var) = supper {
                     if supper_var.get_token_type().to_string() == name.get_token_type().to_string()
@@ -376,12 +505,12 @@ pub mod resolver {
 
             for method in methods {
                 match method {
-                    Stmt::Function(fn_name, fn_params, fn_body) => {
+                    Stmt::Function(fn_name, fn_params, fn_body, _, optional) => {
                         let mut declaration = FunctionType::Method;
                         if fn_name.get_token_type().to_string() == "init" {
                             declaration = FunctionType::Initializer;
                         }
-                        self.resolve_function(fn_name, fn_params, fn_body, declaration)?;
+                        self.resolve_function(fn_name, fn_params, fn_body, optional, declaration)?;
                     }
                     _ => (),
                 }
@@ -397,6 +526,58 @@ pub mod resolver {
             Ok(())
         }
 
+        /**
+         * Trait methods are resolved like the methods of a subclass, with a `super` scope
+         * around the `this` one, the interpreter binds both when copying them into a class.
+         */
+        fn visit_trait_stmt(&mut self, name: &Token, methods: &Vec<Stmt>) -> Result<(), Error> {
+            let enclosing_class = self.current_class.clone();
+            self.current_class = ClassType::Trait;
+            self.declare(name)?;
+            self.define(name);
+
+            self.begin_scope();
+            self.scopes
+                .last_mut()
+                .unwrap()
+                .push((String::from("super"), true));
+            self.begin_scope();
+            self.scopes
+                .last_mut()
+                .unwrap()
+                .push((String::from("this"), true));
+
+            for method in methods {
+                if let Stmt::Function(fn_name, fn_params, fn_body, _, optional) = method {
+                    if fn_name.get_token_type().to_string() == "init" {
+                        error(
+                            fn_name.get_line(),
+                            fn_name.get_column(),
+                            String::from(
+                                "Error at 'init': Can't define an initializer in a trait.",
+                            ),
+                            function_name!(),
+                            Some(RLoxErrorType::RuntimeError),
+                        );
+                        return Err(Error::LoxRuntimeError);
+                    }
+                    self.resolve_function(
+                        fn_name,
+                        fn_params,
+                        fn_body,
+                        optional,
+                        FunctionType::Method,
+                    )?;
+                }
+            }
+
+            self.end_scope();
+            self.end_scope();
+            self.current_class = enclosing_class;
+
+            Ok(())
+        }
+
         fn visit_expr_stmt(&mut self, expr: &Expr) -> Result<(), Error> {
             self.resolve_expr(expr)?;
             Ok(())
@@ -407,10 +588,11 @@ pub mod resolver {
             name: &Token,
             params: &Vec<Token>,
             body: &Vec<Stmt>,
+            optional: &OptionalParams,
         ) -> Result<(), Error> {
             self.declare(name)?;
             self.define(name);
-            self.resolve_function(name, params, body, FunctionType::Function)?;
+            self.resolve_function(name, params, body, optional, FunctionType::Function)?;
             Ok(())
         }
 
@@ -429,6 +611,21 @@ pub mod resolver {
         }
 
         fn visit_return_stmt(&mut self, keyword: &Token, expr: &Expr) -> Result<(), Error> {
+            // a static error like in the book, `return/at_top_level.lox` expects it before running.
+            if self.current_fn == FunctionType::None {
+                error(
+                    keyword.get_line(),
+                    keyword.get_column(),
+                    format!(
+                        "Error at '{}': Can't return from top-level code.",
+                        keyword.get_token_type()
+                    ),
+                    function_name!(),
+                    Some(RLoxErrorType::RuntimeError),
+                );
+                return Err(Error::LoxRuntimeError);
+            }
+
             match self.resolve_expr(expr) {
                 Ok(_) => {
                     if self.current_fn == FunctionType::Initializer {
@@ -450,6 +647,25 @@ pub mod resolver {
             Ok(())
         }
 
+        fn visit_yield_stmt(&mut self, keyword: &Token, expr: &Expr) -> Result<(), Error> {
+            let message = match self.current_fn {
+                FunctionType::None => Some("Can't yield from top-level code."),
+                FunctionType::Initializer => Some("Can't yield from an initializer."),
+                _ => None,
+            };
+            if let Some(message) = message {
+                error(
+                    keyword.get_line(),
+                    keyword.get_column(),
+                    format!("Error at '{}': {}", keyword.get_token_type(), message),
+                    function_name!(),
+                    Some(RLoxErrorType::RuntimeError),
+                );
+                return Err(Error::LoxRuntimeError);
+            }
+            self.resolve_expr(expr)
+        }
+
         fn visit_print_stmt(&mut self, expr: &Expr) -> Result<(), Error> {
             self.resolve_expr(expr)?;
             Ok(())
@@ -464,7 +680,7 @@ pub mod resolver {
         fn visit_var_stmt(&mut self, token: &Token, expr: &Expr) -> Result<(), Error> {
             self.declare(token)?;
             match expr {
-                Expr::Call(_, _, _) => {
+                Expr::Call(_, _, _, _) => {
                     self.resolve_expr(expr)?;
                 }
                 _ => self.resolve_expr(expr)?,
@@ -473,11 +689,53 @@ pub mod resolver {
             Ok(())
         }
 
+        fn visit_const_stmt(&mut self, token: &Token, expr: &Expr) -> Result<(), Error> {
+            self.declare(token)?;
+            self.resolve_expr(expr)?;
+            self.define(token);
+            if let Some(constants) = self.constants.last_mut() {
+                constants.push(token.clone());
+            }
+            Ok(())
+        }
+
         fn visit_while_stmt(&mut self, expr: &Expr, stmt: &Stmt) -> Result<(), Error> {
             self.resolve_expr(expr)?;
             self.resolve_stmt(stmt)?;
 
             Ok(())
         }
+
+        fn visit_match_stmt(
+            &mut self,
+            _keyword: &Token,
+            subject: &Expr,
+            cases: &Vec<MatchCase>,
+            default: &Option<Box<Stmt>>,
+        ) -> Result<(), Error> {
+            self.resolve_expr(subject)?;
+            for case in cases {
+                // patterns are tested before entering the scope of the case, holding the binding.
+                for pattern in &case.patterns {
+                    if let Pattern::Class(class, _) = pattern {
+                        self.resolve_local(class);
+                    }
+                }
+                self.begin_scope();
+                if let [Pattern::Class(_, Some(binding))] = case.patterns.as_slice() {
+                    self.declare(binding)?;
+                    self.define(binding);
+                }
+                if let Some(guard) = &case.guard {
+                    self.resolve_expr(guard)?;
+                }
+                self.resolve_stmt(&case.body)?;
+                self.end_scope();
+            }
+            if let Some(default) = default {
+                self.resolve_stmt(default)?;
+            }
+            Ok(())
+        }
     }
 }
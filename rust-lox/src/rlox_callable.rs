@@ -1,9 +1,14 @@
 pub mod rlox_callable {
     use crate::environment::environment::Environment;
     use crate::error_handling::error_handling::{error, RLoxErrorType};
+    use crate::event_loop::event_loop::EventLoopNative;
+    use crate::file_system::file_system::FileNative;
     use crate::function_name;
+    use crate::generator::generator::Generator;
+    use crate::iterator::iterator::{Iterate, NativeMethod, SequenceIterator};
     use crate::scanner::scan::{Token, TokenType};
-    use crate::stmt::stmt::LiteralValue;
+    use crate::stmt::stmt::{LiteralValue, OptionalParams};
+    use crate::system::system::SystemNative;
     use crate::{
         interpreter::interpreter::{Error, Interpreter},
         stmt::stmt::Stmt,
@@ -11,15 +16,25 @@ pub mod rlox_callable {
     use chrono;
     use rustc_hash::FxHashMap as HashMap;
     use std::borrow::Borrow;
-    use std::{borrow::BorrowMut, cell::RefCell, fmt, rc::Rc};
+    use std::{cell::RefCell, fmt, rc::Rc};
 
     #[derive(Debug, PartialEq)]
     pub enum Callable {
         Class(RLoxClass),
+        Trait(RLoxTrait),
         Instance(Rc<RefCell<RLoxInstance>>),
         Function(RLoxFunction),
         Clock(Clock),
         UnixTClock(UnixTClock),
+        Str(Str),
+        Len(Len),
+        Iterate(Iterate),
+        Generator(Generator),
+        SequenceIterator(SequenceIterator),
+        NativeMethod(NativeMethod),
+        EventLoop(EventLoopNative),
+        File(FileNative),
+        System(SystemNative),
     }
 
     impl Clone for Callable {
@@ -28,8 +43,20 @@ pub mod rlox_callable {
                 Callable::Function(lox_function) => Callable::Function(lox_function.clone()),
                 Callable::Instance(rlox_instance) => Callable::Instance(rlox_instance.clone()),
                 Callable::Class(class) => Callable::Class(class.clone()),
+                Callable::Trait(used_trait) => Callable::Trait(used_trait.clone()),
                 Callable::Clock(clock) => Callable::Clock(clock.clone()),
                 Callable::UnixTClock(unix_t_clock) => Callable::UnixTClock(unix_t_clock.clone()),
+                Callable::Str(str) => Callable::Str(str.clone()),
+                Callable::Len(len) => Callable::Len(len.clone()),
+                Callable::Iterate(iterate) => Callable::Iterate(iterate.clone()),
+                Callable::Generator(generator) => Callable::Generator(generator.clone()),
+                Callable::SequenceIterator(iterator) => {
+                    Callable::SequenceIterator(iterator.clone())
+                }
+                Callable::NativeMethod(method) => Callable::NativeMethod(method.clone()),
+                Callable::EventLoop(native) => Callable::EventLoop(*native),
+                Callable::File(native) => Callable::File(*native),
+                Callable::System(native) => Callable::System(*native),
             }
         }
     }
@@ -38,6 +65,7 @@ pub mod rlox_callable {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             match self {
                 Callable::Class(rlox_clas) => write!(f, "{}", rlox_clas.to_string()),
+                Callable::Trait(rlox_trait) => write!(f, "{}", rlox_trait.to_string()),
                 Callable::Instance(rlox_instance) => write!(
                     f,
                     "{}",
@@ -46,12 +74,77 @@ pub mod rlox_callable {
                 Callable::Function(rlox_fun) => write!(f, "{}", rlox_fun.to_string()),
                 Callable::Clock(clock) => write!(f, "{}", clock.to_string()),
                 Callable::UnixTClock(unix_tclock) => write!(f, "{}", unix_tclock.to_string()),
+                Callable::Str(str) => write!(f, "{}", str),
+                Callable::Len(len) => write!(f, "{}", len),
+                Callable::Iterate(iterate) => write!(f, "{}", iterate),
+                Callable::Generator(generator) => write!(f, "{}", generator),
+                Callable::SequenceIterator(iterator) => write!(f, "{}", iterator),
+                Callable::NativeMethod(method) => write!(f, "{}", method),
+                Callable::EventLoop(native) => write!(f, "{}", native),
+                Callable::File(native) => write!(f, "{}", native),
+                Callable::System(native) => write!(f, "{}", native),
+            }
+        }
+    }
+
+    /// Native functions defined in the global scope, with their names.
+    pub fn natives() -> Vec<(String, Callable)> {
+        let mut natives = vec![
+            (String::from("clock"), Callable::Clock(Clock {})),
+            (
+                String::from("unixClock"),
+                Callable::UnixTClock(UnixTClock {}),
+            ),
+            (String::from("str"), Callable::Str(Str {})),
+            (String::from("len"), Callable::Len(Len {})),
+        ];
+        for native in EventLoopNative::ALL {
+            natives.push((native.to_string(), Callable::EventLoop(native)));
+        }
+        for native in FileNative::ALL {
+            natives.push((native.to_string(), Callable::File(native)));
+        }
+        for native in SystemNative::ALL {
+            natives.push((native.to_string(), Callable::System(native)));
+        }
+        natives
+    }
+
+    /// Accepted argument counts of a callable, `max` is `None` with a rest parameter.
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub struct Arity {
+        pub min: usize,
+        pub max: Option<usize>,
+    }
+
+    impl Arity {
+        pub fn exact(count: usize) -> Self {
+            Self {
+                min: count,
+                max: Some(count),
+            }
+        }
+
+        pub fn accepts(&self, count: usize) -> bool {
+            count >= self.min && self.max.is_none_or(|max| count <= max)
+        }
+    }
+
+    impl fmt::Display for Arity {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self.max {
+                Some(max) if max == self.min => write!(f, "{}", max),
+                Some(max) => write!(f, "{} to {}", self.min, max),
+                None => write!(f, "at least {}", self.min),
             }
         }
     }
 
+    /// One value per fixed parameter, `None` takes the default value, and the rest arguments.
+    pub type CallArguments = (Vec<Option<Rc<LiteralValue>>>, Vec<Rc<LiteralValue>>);
+
     pub trait RLoxCallable {
-        fn arity(&self) -> usize;
+        fn arity(&self) -> Arity;
         fn call(
             &self,
             interpreter: &mut Interpreter,
@@ -69,8 +162,8 @@ pub mod rlox_callable {
     }
 
     impl RLoxCallable for Clock {
-        fn arity(&self) -> usize {
-            0
+        fn arity(&self) -> Arity {
+            Arity::exact(0)
         }
 
         fn call(
@@ -94,8 +187,8 @@ pub mod rlox_callable {
     }
 
     impl RLoxCallable for UnixTClock {
-        fn arity(&self) -> usize {
-            0
+        fn arity(&self) -> Arity {
+            Arity::exact(0)
         }
 
         fn call(
@@ -109,6 +202,59 @@ pub mod rlox_callable {
         }
     }
 
+    /// Converts any value to the string `print` would show, string interpolation calls it.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Str {}
+
+    impl fmt::Display for Str {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "str")
+        }
+    }
+
+    impl RLoxCallable for Str {
+        fn arity(&self) -> Arity {
+            Arity::exact(1)
+        }
+
+        fn call(
+            &self,
+            _interpreter: &mut Interpreter,
+            args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            Ok(Rc::new(LiteralValue::String(args[0].to_string())))
+        }
+    }
+
+    /// Number of elements of a list or characters of a string.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Len {}
+
+    impl fmt::Display for Len {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "len")
+        }
+    }
+
+    impl RLoxCallable for Len {
+        fn arity(&self) -> Arity {
+            Arity::exact(1)
+        }
+
+        fn call(
+            &self,
+            _interpreter: &mut Interpreter,
+            args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let len = match &*args[0] {
+                LiteralValue::List(list) => list.as_ref().borrow().len(),
+                LiteralValue::String(str) => str.chars().count(),
+                _ => return Ok(Rc::new(LiteralValue::Nil)),
+            };
+            Ok(Rc::new(LiteralValue::Number(len as f64)))
+        }
+    }
+
     #[derive(Clone, Debug, PartialEq)]
     pub struct RLoxFunction {
         pub declaration: Box<Stmt>,
@@ -131,11 +277,123 @@ pub mod rlox_callable {
 
         pub fn to_string(&self) -> String {
             match self.declaration.borrow() {
-                Stmt::Function(name, _, _) => format!("<fn {}>", name.get_token_type()),
+                Stmt::Function(name, _, _, _, _) => format!("<fn {}>", name.get_token_type()),
+                _ => panic!("Cannot call non-function"),
+            }
+        }
+
+        /// Name used by the profiler, methods are prefixed with the class of `this`.
+        pub fn profile_name(&self) -> String {
+            let name = match self.declaration.borrow() {
+                Stmt::Function(name, _, _, _, _) => name.get_token_type().to_string(),
+                _ => String::from("<unknown>"),
+            };
+
+            match self.closure.as_ref().borrow().values().get("this") {
+                Some(this) => match this.as_ref() {
+                    LiteralValue::Callable(Callable::Instance(instance)) => {
+                        format!("{}.{}", instance.as_ref().borrow().rlox_class.name, name)
+                    }
+                    _ => name,
+                },
+                None => name,
+            }
+        }
+
+        pub fn parameters(&self) -> (&[Token], &OptionalParams) {
+            match self.declaration.borrow() {
+                Stmt::Function(_, params, _, _, optional) => (params, optional),
                 _ => panic!("Cannot call non-function"),
             }
         }
 
+        /// Splits positional arguments into one value per parameter and the rest parameter ones.
+        pub fn positional_args(&self, args: &mut Vec<Rc<LiteralValue>>) -> CallArguments {
+            let (params, optional) = self.parameters();
+            let fixed = params.len() - optional.rest as usize;
+            let rest = args.split_off(fixed.min(args.len()));
+            (args.drain(..).map(Some).collect(), rest)
+        }
+
+        pub fn body(&self) -> &[Stmt] {
+            match self.declaration.borrow() {
+                Stmt::Function(_, _, body, _, _) => body,
+                _ => panic!("Cannot call non-function"),
+            }
+        }
+
+        /**
+         * Environment of a call with one optional value per parameter, a missing one takes its
+         * default value, evaluated at call time in the environment of the call, so it can
+         * refer to the parameters before it. The rest parameter is a list of `rest`.
+         */
+        pub fn environment(
+            &self,
+            interpreter: &mut Interpreter,
+            args: Vec<Option<Rc<LiteralValue>>>,
+            rest: Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<RefCell<Environment>>, Error> {
+            let (params, optional) = self.parameters();
+            let env = Rc::new(RefCell::new(Environment::new(self.closure.clone())));
+            let fixed = params.len() - optional.rest as usize;
+            for (idx, param) in params[..fixed].iter().enumerate() {
+                let value = match (args.get(idx).cloned().flatten(), &optional.defaults[idx]) {
+                    (Some(value), _) => value,
+                    (None, Some(default)) => interpreter.evaluate_in(default, &env)?,
+                    (None, None) => Rc::new(LiteralValue::Nil),
+                };
+                env.as_ref().borrow_mut().define(param, value);
+            }
+            if optional.rest {
+                env.as_ref().borrow_mut().define(
+                    &params[fixed],
+                    Rc::new(LiteralValue::List(Rc::new(RefCell::new(rest)))),
+                );
+            }
+            Ok(env)
+        }
+
+        /// Calls the function, see `environment`. A function containing `yield` returns a generator.
+        pub fn call_with(
+            &self,
+            interpreter: &mut Interpreter,
+            args: Vec<Option<Rc<LiteralValue>>>,
+            rest: Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            let body = self.body();
+            let env = self.environment(interpreter, args, rest)?;
+            if body.iter().any(Stmt::contains_yield) {
+                return Ok(Rc::new(LiteralValue::Callable(Callable::Generator(
+                    Generator::new(self.profile_name(), body, env),
+                ))));
+            }
+
+            if let Some(profiler) = interpreter.profiler.as_mut() {
+                profiler.enter(self.profile_name());
+            }
+            interpreter.call_depth += 1;
+            let result = interpreter.execute_in(body, env);
+            interpreter.call_depth -= 1;
+            if let Some(profiler) = interpreter.profiler.as_mut() {
+                profiler.exit();
+            }
+
+            match result {
+                Ok(_) => (),
+                Err(Error::Return(ret_val)) => return Ok(ret_val),
+                Err(err) => return Err(err),
+            };
+
+            if self.is_initializer {
+                return self.closure.as_ref().borrow_mut().get_at(
+                    0,
+                    &Token::new(TokenType::This, String::from("this"), 0, 0, 0),
+                );
+            }
+
+            Ok(Rc::new(LiteralValue::Nil))
+        }
+
         pub fn bind(&mut self, instance: Rc<RefCell<RLoxInstance>>) -> RLoxFunction {
             let env = Rc::new(RefCell::new(Environment::new(Rc::clone(&self.closure))));
             env.as_ref().borrow_mut().define_str(
@@ -151,10 +409,15 @@ pub mod rlox_callable {
     }
 
     impl RLoxCallable for RLoxFunction {
-        fn arity(&self) -> usize {
-            match self.declaration.borrow() {
-                Stmt::Function(_, params, _) => params.len(),
-                _ => 0,
+        fn arity(&self) -> Arity {
+            let (params, optional) = self.parameters();
+            let fixed = params.len() - optional.rest as usize;
+            Arity {
+                min: optional.defaults[..fixed]
+                    .iter()
+                    .filter(|default| default.is_none())
+                    .count(),
+                max: (!optional.rest).then_some(fixed),
             }
         }
 
@@ -163,52 +426,29 @@ pub mod rlox_callable {
             interpreter: &mut Interpreter,
             args: &mut Vec<Rc<LiteralValue>>,
         ) -> Result<Rc<LiteralValue>, Error> {
-            let mut env = Environment::new(self.closure.clone());
-            match self.declaration.borrow() {
-                Stmt::Function(_, params, body) => {
-                    for (idx, param) in params.iter().enumerate() {
-                        env.borrow_mut().define(param, Rc::clone(&args[idx]));
-                    }
-
-                    match interpreter.execute_block(&body, env) {
-                        Ok(_) => (),
-                        Err(err) => match err {
-                            Error::Return(ret_val) => {
-                                return Ok(ret_val);
-                            }
-                            _ => return Err(err),
-                        },
-                    };
-                }
-                _ => (),
-            }
-
-            if self.is_initializer {
-                return self.closure.as_ref().borrow_mut().get_at(
-                    0,
-                    &Token::new(TokenType::This, String::from("this"), 0, 0, 0),
-                );
-            }
-
-            Ok(Rc::new(LiteralValue::Nil))
+            let (args, rest) = self.positional_args(args);
+            self.call_with(interpreter, args, rest)
         }
     }
 
     #[derive(Clone, Debug, PartialEq)]
     pub struct RLoxClass {
         pub name: String,
+        /// Name token of the class declaration, classes are copied around and compared with it.
+        pub declaration: Token,
         pub super_class: Rc<Option<RLoxClass>>,
         pub methods: HashMap<String, RLoxFunction>,
     }
 
     impl RLoxClass {
         pub fn new(
-            name: String,
+            declaration: &Token,
             methods: HashMap<String, RLoxFunction>,
             super_class: Option<RLoxClass>,
         ) -> Self {
             Self {
-                name,
+                name: declaration.get_token_type().to_string(),
+                declaration: declaration.clone(),
                 methods,
                 super_class: Rc::new(super_class),
             }
@@ -226,17 +466,59 @@ pub mod rlox_callable {
             None
         }
 
+        /// Whether this class is `class` or inherits from it.
+        pub fn is_subclass_of(&self, class: &RLoxClass) -> bool {
+            if self.declaration == class.declaration {
+                return true;
+            }
+
+            match &*self.super_class {
+                Some(superclass) => superclass.is_subclass_of(class),
+                None => false,
+            }
+        }
+
         pub fn to_string(&self) -> String {
             format!("<class {}>", self.name)
         }
+
+        /// Creates an instance, `init` gets the arguments like `RLoxFunction::call_with`.
+        pub fn call_with(
+            &self,
+            interpreter: &mut Interpreter,
+            args: Vec<Option<Rc<LiteralValue>>>,
+            rest: Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            if let Some(profiler) = interpreter.profiler.as_mut() {
+                profiler.enter(self.name.clone());
+            }
+            let instance = Rc::new(RefCell::new(RLoxInstance::new(Rc::new(self.clone()))));
+
+            let c_tor = self.find_method("init");
+            let result = match c_tor {
+                Some(mut ctor) => ctor
+                    .bind(Rc::clone(&instance))
+                    .call_with(interpreter, args, rest)
+                    .map(|_| ()),
+                None => Ok(()),
+            };
+            if let Some(profiler) = interpreter.profiler.as_mut() {
+                profiler.exit();
+            }
+            result?;
+
+            Ok(Rc::new(LiteralValue::Callable(Callable::Instance(
+                Rc::clone(&instance),
+            ))))
+        }
     }
 
     impl RLoxCallable for RLoxClass {
-        fn arity(&self) -> usize {
+        fn arity(&self) -> Arity {
             if let Some(ctor) = self.find_method("init") {
                 return ctor.arity();
             }
-            0
+            Arity::exact(0)
         }
 
         fn call(
@@ -244,16 +526,56 @@ pub mod rlox_callable {
             interpreter: &mut Interpreter,
             args: &mut Vec<Rc<LiteralValue>>,
         ) -> Result<Rc<LiteralValue>, Error> {
-            let instance = Rc::new(RefCell::new(RLoxInstance::new(Rc::new(self.clone()))));
+            let (args, rest) = match self.find_method("init") {
+                Some(ctor) => ctor.positional_args(args),
+                None => (Vec::new(), Vec::new()),
+            };
+            self.call_with(interpreter, args, rest)
+        }
+    }
 
-            let c_tor = self.find_method("init");
-            if let Some(mut ctor) = c_tor {
-                ctor.bind(Rc::clone(&instance)).call(interpreter, args)?;
+    /// Methods shared by classes, a class using the trait gets its own copy of each method.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct RLoxTrait {
+        pub name: String,
+        pub methods: HashMap<String, RLoxFunction>,
+    }
+
+    impl RLoxTrait {
+        pub fn new(declaration: &Token, methods: HashMap<String, RLoxFunction>) -> Self {
+            Self {
+                name: declaration.get_token_type().to_string(),
+                methods,
             }
+        }
 
-            Ok(Rc::new(LiteralValue::Callable(Callable::Instance(
-                Rc::clone(&instance),
-            ))))
+        /**
+         * Copies of the methods for a class with `super_class`, their `super` is that
+         * superclass, or `nil` when the class has none.
+         */
+        pub fn methods_for(
+            &self,
+            super_class: &Option<RLoxClass>,
+        ) -> HashMap<String, RLoxFunction> {
+            let super_value = match super_class {
+                Some(super_class) => LiteralValue::Callable(Callable::Class(super_class.clone())),
+                None => LiteralValue::Nil,
+            };
+            self.methods
+                .iter()
+                .map(|(name, method)| {
+                    let env = Rc::new(RefCell::new(Environment::new(Rc::clone(&method.closure))));
+                    env.as_ref()
+                        .borrow_mut()
+                        .define_str("super", Rc::new(super_value.clone()));
+                    let method = RLoxFunction::new(*method.declaration.clone(), env, false);
+                    (name.clone(), method)
+                })
+                .collect()
+        }
+
+        pub fn to_string(&self) -> String {
+            format!("<trait {}>", self.name)
         }
     }
 
@@ -271,15 +593,24 @@ pub mod rlox_callable {
             }
         }
 
-        pub fn get(&mut self, name: &Token) -> Result<Rc<LiteralValue>, Error> {
+        /**
+         * Methods are bound to `instance` itself, so the fields they set are the instance's.
+         * Binding them to a copy of the instance lost every `this.x = ...` done in a method
+         * once the call returned.
+         */
+        pub fn get(
+            instance: &Rc<RefCell<RLoxInstance>>,
+            name: &Token,
+        ) -> Result<Rc<LiteralValue>, Error> {
             let name_str = &name.get_token_type().to_string();
-            if self.fields.contains_key(name_str) {
-                return Ok(self.fields.get(name_str).unwrap().clone());
+            let this = instance.as_ref().borrow();
+            if this.fields.contains_key(name_str) {
+                return Ok(this.fields.get(name_str).unwrap().clone());
             }
 
-            if let Some(mut method) = self.rlox_class.find_method(name_str) {
+            if let Some(mut method) = this.rlox_class.find_method(name_str) {
                 return Ok(Rc::new(LiteralValue::Callable(Callable::Function(
-                    method.bind(Rc::new(RefCell::new(self.to_owned()))),
+                    method.bind(Rc::clone(instance)),
                 ))));
             }
 
@@ -293,6 +624,11 @@ pub mod rlox_callable {
             Err(Error::LoxRuntimeError)
         }
 
+        /// Whether `get` finds a field or a method named `name`.
+        pub fn has_property(&self, name: &str) -> bool {
+            self.fields.contains_key(name) || self.rlox_class.find_method(name).is_some()
+        }
+
         pub fn set(&mut self, name: &Token, value: Rc<LiteralValue>) {
             self.fields.insert(name.get_token_type().to_string(), value);
         }
@@ -1,9 +1,10 @@
 pub mod scan {
     #![allow(dead_code)]
 
-    use crate::error_handling::error_handling::{error, RLoxErrorType};
+    use crate::error_handling::error_handling::{error, with_capture, RLoxErrorType};
     use crate::function_name;
     use rustc_hash::FxHashMap as HashMap;
+    use serde_json::{json, Map, Value};
     use std::fmt;
 
     #[derive(Clone, PartialEq, Debug)]
@@ -13,45 +14,78 @@ pub mod scan {
         RightParen,
         LeftBrace,
         RightBrace,
+        LeftBracket,
+        RightBracket,
         Comma,
+        Colon,
         Dot,
         Minus,
         Plus,
         Semicolon,
         Slash,
         Star,
+        Percent,
+        Ampersand,
+        Pipe,
+        Caret,
+        Tilde,
+        Question,
 
         // One or two character tokens.
         Bang,
         BangEqual,
         Equal,
         EqualEqual,
+        EqualGreater,
         Greater,
         GreaterEqual,
         Less,
         LessEqual,
+        StarStar,
+        LessLess,
+        GreaterGreater,
+        PlusPlus,
+        MinusMinus,
+        PlusEqual,
+        MinusEqual,
+        StarEqual,
+        SlashEqual,
+        PercentEqual,
+        QuestionQuestion,
+        QuestionDot,
+        DotDotDot,
 
         Identifier(String),
         String(String),
+        /// Part of a string literal that is followed by an interpolated `${expression}`.
+        Interpolation(String),
         Number(f64),
 
         // Keywords.
         And,
+        Case,
         Class,
+        Const,
+        Default,
         Else,
         False,
         Fun,
         For,
         If,
+        In,
+        Match,
         Nil,
         Or,
         Print,
         Return,
         Super,
         This,
+        Trait,
         True,
         Var,
         While,
+        With,
+        Yield,
 
         EOF,
     }
@@ -68,8 +102,8 @@ pub mod scan {
 
     fn keywords() -> HashMap<String, TokenType> {
         create_map!(
-            And, Class, Else, False, Fun, For, If, Nil, Or, Print, Return, Super, This, True, Var,
-            While
+            And, Case, Class, Const, Default, Else, False, Fun, For, If, In, Match, Nil, Or, Print,
+            Return, Super, This, Trait, True, Var, While, With, Yield
         )
     }
 
@@ -80,13 +114,22 @@ pub mod scan {
                 TokenType::RightParen => write!(f, ")"),
                 TokenType::LeftBrace => write!(f, "{{"),
                 TokenType::RightBrace => write!(f, "}}"),
+                TokenType::LeftBracket => write!(f, "["),
+                TokenType::RightBracket => write!(f, "]"),
                 TokenType::Comma => write!(f, ","),
+                TokenType::Colon => write!(f, ":"),
                 TokenType::Dot => write!(f, "."),
                 TokenType::Minus => write!(f, "-"),
                 TokenType::Plus => write!(f, "+"),
                 TokenType::Semicolon => write!(f, ";"),
                 TokenType::Slash => write!(f, "/"),
                 TokenType::Star => write!(f, "*"),
+                TokenType::Percent => write!(f, "%"),
+                TokenType::Ampersand => write!(f, "&"),
+                TokenType::Pipe => write!(f, "|"),
+                TokenType::Caret => write!(f, "^"),
+                TokenType::Tilde => write!(f, "~"),
+                TokenType::Question => write!(f, "?"),
                 TokenType::Bang => write!(f, "!"),
                 TokenType::BangEqual => write!(f, "!="),
                 TokenType::Greater => write!(f, ">"),
@@ -95,25 +138,48 @@ pub mod scan {
                 TokenType::LessEqual => write!(f, "<="),
                 TokenType::Equal => write!(f, "="),
                 TokenType::EqualEqual => write!(f, "=="),
+                TokenType::EqualGreater => write!(f, "=>"),
+                TokenType::StarStar => write!(f, "**"),
+                TokenType::LessLess => write!(f, "<<"),
+                TokenType::GreaterGreater => write!(f, ">>"),
+                TokenType::PlusPlus => write!(f, "++"),
+                TokenType::MinusMinus => write!(f, "--"),
+                TokenType::PlusEqual => write!(f, "+="),
+                TokenType::MinusEqual => write!(f, "-="),
+                TokenType::StarEqual => write!(f, "*="),
+                TokenType::SlashEqual => write!(f, "/="),
+                TokenType::PercentEqual => write!(f, "%="),
+                TokenType::QuestionQuestion => write!(f, "??"),
+                TokenType::QuestionDot => write!(f, "?."),
+                TokenType::DotDotDot => write!(f, "..."),
                 TokenType::Identifier(ident) => write!(f, "{}", ident),
                 TokenType::String(str) => write!(f, "\"{}\"", str),
+                TokenType::Interpolation(str) => write!(f, "\"{}${{", str),
                 TokenType::Number(num) => write!(f, "{}", num),
                 TokenType::And => write!(f, "and"),
+                TokenType::Case => write!(f, "case"),
                 TokenType::Class => write!(f, "class"),
+                TokenType::Const => write!(f, "const"),
+                TokenType::Default => write!(f, "default"),
                 TokenType::Else => write!(f, "else"),
                 TokenType::False => write!(f, "false"),
                 TokenType::True => write!(f, "true"),
                 TokenType::Fun => write!(f, "fun"),
                 TokenType::For => write!(f, "for"),
                 TokenType::If => write!(f, "if"),
+                TokenType::In => write!(f, "in"),
+                TokenType::Match => write!(f, "match"),
                 TokenType::Nil => write!(f, "nil"),
                 TokenType::Or => write!(f, "or"),
                 TokenType::Print => write!(f, "print"),
                 TokenType::Return => write!(f, "return"),
                 TokenType::Super => write!(f, "super"),
                 TokenType::This => write!(f, "this"),
+                TokenType::Trait => write!(f, "trait"),
                 TokenType::Var => write!(f, "var"),
                 TokenType::While => write!(f, "while"),
+                TokenType::With => write!(f, "with"),
+                TokenType::Yield => write!(f, "yield"),
                 TokenType::EOF => write!(f, "EOF"),
             }
         }
@@ -152,6 +218,36 @@ pub mod scan {
             )
         }
 
+        /// Name of the token type without its value, e.g. `Identifier` or `Plus`.
+        pub fn kind_name(&self) -> String {
+            match &self.t_type {
+                TokenType::Identifier(_) => String::from("Identifier"),
+                TokenType::String(_) => String::from("String"),
+                TokenType::Interpolation(_) => String::from("Interpolation"),
+                TokenType::Number(_) => String::from("Number"),
+                t_type => format!("{:?}", t_type),
+            }
+        }
+
+        /// Binary operator applied by `+=`, `++` and the like, placed where they are.
+        pub fn update_operator(&self) -> Option<Token> {
+            let t_type = match self.t_type {
+                TokenType::PlusEqual | TokenType::PlusPlus => TokenType::Plus,
+                TokenType::MinusEqual | TokenType::MinusMinus => TokenType::Minus,
+                TokenType::StarEqual => TokenType::Star,
+                TokenType::SlashEqual => TokenType::Slash,
+                TokenType::PercentEqual => TokenType::Percent,
+                _ => return None,
+            };
+            Some(Token::new(
+                t_type.clone(),
+                t_type.to_string(),
+                self.line,
+                self.column,
+                self.length,
+            ))
+        }
+
         pub fn token_type_value(&self) -> String {
             self.t_type.to_string()
         }
@@ -177,36 +273,169 @@ pub mod scan {
         }
     }
 
+    /// Error found while scanning, `position` is the number of tokens scanned before it.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ScanError {
+        pub line: u32,
+        pub column: u32,
+        pub message: String,
+        pub position: usize,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+    pub enum TokenDumpFormat {
+        Table,
+        Jsonl,
+    }
+
+    /// A token as the `--dump-tokens=jsonl` lines and the AST JSON export write it.
+    pub fn token_to_json(token: &Token) -> Value {
+        let mut object = Map::new();
+        let value = match token.get_token_type() {
+            TokenType::Identifier(name) => Some(json!(name)),
+            TokenType::String(string) | TokenType::Interpolation(string) => Some(json!(string)),
+            TokenType::Number(number) => Some(json!(number)),
+            _ => None,
+        };
+        object.insert(String::from("kind"), json!(token.kind_name()));
+        if let Some(value) = value {
+            object.insert(String::from("value"), value);
+        }
+        object.insert(String::from("lexeme"), json!(token.get_lexeme()));
+        object.insert(String::from("line"), json!(token.get_line()));
+        object.insert(String::from("column"), json!(token.get_column()));
+        object.insert(String::from("length"), json!(token.get_length()));
+        Value::Object(object)
+    }
+
+    /**
+     * Scans `source` and renders every token, either as an aligned table or as one JSON
+     * object per line. Scanner errors show up in the stream where they occurred.
+     */
+    pub fn dump_tokens(source: String, format: TokenDumpFormat) -> String {
+        let mut scanner = Scanner::new(source);
+        // the errors are part of the dump, keep them out of the log.
+        let (tokens, _) = with_capture(|| scanner.scan_tokens());
+
+        let mut errors = scanner.errors().iter().peekable();
+        let mut dump = match format {
+            TokenDumpFormat::Table => format!(
+                "{:>5} {:>4} {:>4}  {:<13} {}\n",
+                "LINE", "COL", "LEN", "TYPE", "LEXEME"
+            ),
+            TokenDumpFormat::Jsonl => String::new(),
+        };
+
+        for (idx, token) in tokens.iter().enumerate() {
+            while let Some(scan_error) = errors.next_if(|scan_error| scan_error.position <= idx) {
+                dump.push_str(&match format {
+                    TokenDumpFormat::Table => format!(
+                        "{:>5} {:>4} {:>4}  {:<13} {}\n",
+                        scan_error.line, scan_error.column, "-", "ERROR", scan_error.message
+                    ),
+                    TokenDumpFormat::Jsonl => format!(
+                        "{}\n",
+                        serde_json::json!({
+                            "kind": "Error",
+                            "message": scan_error.message,
+                            "line": scan_error.line,
+                            "column": scan_error.column,
+                        })
+                    ),
+                });
+            }
+
+            dump.push_str(&match format {
+                TokenDumpFormat::Table => format!(
+                    "{:>5} {:>4} {:>4}  {:<13} {}\n",
+                    token.line,
+                    token.column,
+                    token.length,
+                    token.kind_name(),
+                    token
+                        .lexeme
+                        .replace('\n', "\\n")
+                        .replace('\r', "\\r")
+                        .replace('\t', "\\t")
+                ),
+                TokenDumpFormat::Jsonl => format!("{}\n", token_to_json(token)),
+            });
+        }
+        dump
+    }
+
     pub struct Scanner {
-        source: String,
+        /// Indexed by character, so that `start` and `current` never split one.
+        source: Vec<char>,
         tokens: Vec<Token>,
         start: u32,
         current: u32,
         line: u32,
         column: u32,
+        errors: Vec<ScanError>,
+        /// Unclosed `{` inside each `${` being scanned, the innermost last.
+        interpolations: Vec<u32>,
     }
 
     impl Scanner {
         pub fn new(source: String) -> Scanner {
-            if source.len() > u32::MAX as usize {
-                panic!("Given code's length goes past U32 max value!");
-            }
+            let source: Vec<char> = source.chars().collect();
+            let too_long = source.len() > u32::MAX as usize;
 
-            Scanner {
-                source,
+            let mut scanner = Scanner {
+                source: if too_long { Vec::new() } else { source },
                 tokens: Vec::new(),
                 start: 0,
                 current: 0,
                 line: 1,
                 column: 1,
+                errors: Vec::new(),
+                interpolations: Vec::new(),
+            };
+            if too_long {
+                scanner.report(
+                    String::from("Source is too long to be scanned."),
+                    function_name!(),
+                );
             }
+            scanner
+        }
+
+        pub fn had_error(&self) -> bool {
+            !self.errors.is_empty()
+        }
+
+        pub fn errors(&self) -> &Vec<ScanError> {
+            &self.errors
+        }
+
+        fn report(&mut self, message: String, location: Option<String>) {
+            error(
+                self.line,
+                self.column,
+                message.clone(),
+                location,
+                Some(RLoxErrorType::ScannerError),
+            );
+            self.errors.push(ScanError {
+                line: self.line,
+                column: self.column,
+                message,
+                position: self.tokens.len(),
+            });
         }
 
         fn is_last(&self) -> bool {
-            self.current >= self.source.len() as u32
+            self.current as usize >= self.source.len()
         }
 
         pub fn scan_tokens(&mut self) -> Vec<Token> {
+            // a `#!` first line lets the file be run as an executable.
+            if self.current == 0 && self.source.starts_with(&['#', '!']) {
+                while self.peek() != '\n' && !self.is_last() {
+                    self.advance_token();
+                }
+            }
             while !self.is_last() {
                 self.start = self.current;
                 self.scan_token();
@@ -217,7 +446,7 @@ pub mod scan {
                 String::new(),
                 self.line,
                 self.column,
-                self.current,
+                0,
             ));
             self.tokens.clone()
         }
@@ -227,14 +456,92 @@ pub mod scan {
             match c {
                 c if c == '(' => self.add_token(TokenType::LeftParen),
                 c if c == ')' => self.add_token(TokenType::RightParen),
-                c if c == '{' => self.add_token(TokenType::LeftBrace),
-                c if c == '}' => self.add_token(TokenType::RightBrace),
+                c if c == '{' => {
+                    if let Some(braces) = self.interpolations.last_mut() {
+                        *braces += 1;
+                    }
+                    self.add_token(TokenType::LeftBrace);
+                }
+                c if c == '}' => match self.interpolations.last_mut() {
+                    // closes the `${`, the rest of the string follows.
+                    Some(0) => {
+                        self.interpolations.pop();
+                        self.string();
+                    }
+                    Some(braces) => {
+                        *braces -= 1;
+                        self.add_token(TokenType::RightBrace);
+                    }
+                    None => self.add_token(TokenType::RightBrace),
+                },
+                c if c == '[' => self.add_token(TokenType::LeftBracket),
+                c if c == ']' => self.add_token(TokenType::RightBracket),
                 c if c == ',' => self.add_token(TokenType::Comma),
-                c if c == '.' => self.add_token(TokenType::Dot),
-                c if c == '-' => self.add_token(TokenType::Minus),
-                c if c == '+' => self.add_token(TokenType::Plus),
+                c if c == ':' => self.add_token(TokenType::Colon),
+                c if c == '.' => {
+                    if self.peek() == '.' && self.peek_n(1) == '.' {
+                        self.advance_token();
+                        self.advance_token();
+                        self.add_token(TokenType::DotDotDot);
+                    } else {
+                        self.add_token(TokenType::Dot);
+                    }
+                }
+                c if c == '-' => {
+                    let t_type: TokenType = if self.match_token('-') {
+                        TokenType::MinusMinus
+                    } else if self.match_token('=') {
+                        TokenType::MinusEqual
+                    } else {
+                        TokenType::Minus
+                    };
+                    self.add_token(t_type);
+                }
+                c if c == '+' => {
+                    let t_type: TokenType = if self.match_token('+') {
+                        TokenType::PlusPlus
+                    } else if self.match_token('=') {
+                        TokenType::PlusEqual
+                    } else {
+                        TokenType::Plus
+                    };
+                    self.add_token(t_type);
+                }
                 c if c == ';' => self.add_token(TokenType::Semicolon),
-                c if c == '*' => self.add_token(TokenType::Star),
+                c if c == '*' => {
+                    let t_type: TokenType = if self.match_token('*') {
+                        TokenType::StarStar
+                    } else if self.match_token('=') {
+                        TokenType::StarEqual
+                    } else {
+                        TokenType::Star
+                    };
+                    self.add_token(t_type);
+                }
+                c if c == '%' => {
+                    let t_type: TokenType = if self.match_token('=') {
+                        TokenType::PercentEqual
+                    } else {
+                        TokenType::Percent
+                    };
+                    self.add_token(t_type);
+                }
+                c if c == '&' => self.add_token(TokenType::Ampersand),
+                c if c == '|' => self.add_token(TokenType::Pipe),
+                c if c == '^' => self.add_token(TokenType::Caret),
+                c if c == '~' => self.add_token(TokenType::Tilde),
+                c if c == '?' => {
+                    // `a ?.5 : 1` is a conditional, not an optional property access.
+                    let t_type: TokenType = if self.match_token('?') {
+                        TokenType::QuestionQuestion
+                    } else if self.peek() == '.' && !self.peek_n(1).is_ascii_digit() {
+                        self.advance_token();
+                        TokenType::QuestionDot
+                    } else {
+                        TokenType::Question
+                    };
+                    self.add_token(t_type);
+                }
                 c if c == '!' => {
                     let t_type: TokenType = if self.match_token('=') {
                         TokenType::BangEqual
@@ -246,6 +553,8 @@ pub mod scan {
                 c if c == '=' => {
                     let t_type: TokenType = if self.match_token('=') {
                         TokenType::EqualEqual
+                    } else if self.match_token('>') {
+                        TokenType::EqualGreater
                     } else {
                         TokenType::Equal
                     };
@@ -254,6 +563,8 @@ pub mod scan {
                 c if c == '<' => {
                     let t_type: TokenType = if self.match_token('=') {
                         TokenType::LessEqual
+                    } else if self.match_token('<') {
+                        TokenType::LessLess
                     } else {
                         TokenType::Less
                     };
@@ -262,17 +573,22 @@ pub mod scan {
                 c if c == '>' => {
                     let t_type: TokenType = if self.match_token('=') {
                         TokenType::GreaterEqual
+                    } else if self.match_token('>') {
+                        TokenType::GreaterGreater
                     } else {
                         TokenType::Greater
                     };
                     self.add_token(t_type);
                 }
-                // TODO: add support for C-style comments.
                 c if c == '/' => {
                     if self.match_token('/') {
                         while self.peek() != '\n' && !self.is_last() {
                             self.advance_token();
                         }
+                    } else if self.match_token('*') {
+                        self.block_comment();
+                    } else if self.match_token('=') {
+                        self.add_token(TokenType::SlashEqual);
                     } else {
                         self.add_token(TokenType::Slash);
                     }
@@ -296,27 +612,31 @@ pub mod scan {
                     } else if c.is_alphabetic() || c == '_' {
                         self.identifier();
                     } else {
-                        error(
-                            self.line,
-                            self.column,
-                            format!("Unexpected character: {}", c),
-                            function_name!(),
-                            Some(RLoxErrorType::ScannerError),
-                        );
+                        self.report(format!("Unexpected character: {}", c), function_name!());
+                        self.column += 1;
                     }
                 }
             }
         }
 
+        /// Consumes the current character, `'\0'` once the source is exhausted.
         fn advance_token(&mut self) -> char {
-            assert_eq!(
-                self.current <= self.source.len() as u32,
-                true,
-                "[advance_token] Current index is out of bounds!"
-            );
+            match self.source.get(self.current as usize) {
+                Some(&c) => {
+                    self.current += 1;
+                    c
+                }
+                None => '\0',
+            }
+        }
 
-            self.current += 1;
-            self.source.chars().nth(self.current as usize - 1).unwrap()
+        /// Source text of the token being scanned.
+        fn lexeme(&self) -> String {
+            self.source
+                .get(self.start as usize..self.current as usize)
+                .unwrap_or_default()
+                .iter()
+                .collect()
         }
 
         fn identifier(&mut self) {
@@ -324,10 +644,7 @@ pub mod scan {
                 self.advance_token();
             }
 
-            let text: String = match self.source.get(self.start as usize..self.current as usize) {
-                Some(text) => text.to_string(),
-                None => panic!("[identifier] Could not get text from source!"),
-            };
+            let text = self.lexeme();
             let clone_text = text.clone();
 
             let token_type = match keywords().get(&clone_text) {
@@ -339,59 +656,139 @@ pub mod scan {
         }
 
         fn peek(&self) -> char {
-            if self.is_last() {
-                return '\0';
-            }
-            assert_eq!(
-                self.current < self.source.len() as u32,
-                true,
-                "[peek] Current index is out of bounds!"
-            );
-            self.source.chars().nth(self.current as usize).unwrap()
+            self.peek_n(0)
         }
 
-        // write a peak function that has a lookahead of n characters
+        /// Character `lookahead` positions after the current one, `'\0'` past the end.
         fn peek_n(&self, lookahead: u32) -> char {
-            if self.current + lookahead >= self.source.len() as u32 {
-                return '\0';
+            self.source
+                .get(self.current as usize + lookahead as usize)
+                .copied()
+                .unwrap_or('\0')
+        }
+
+        /// Skips a `/* */` comment. Comments nest, so code that already contains one can be
+        /// commented out as a whole.
+        fn block_comment(&mut self) {
+            let (line, column) = (self.line, self.column);
+            let mut depth = 1;
+            self.column += 2;
+            while depth > 0 {
+                if self.is_last() {
+                    self.line = line;
+                    self.column = column;
+                    self.report(
+                        String::from("Unterminated block comment."),
+                        function_name!(),
+                    );
+                    return;
+                }
+
+                match self.advance_token() {
+                    '/' if self.peek() == '*' => {
+                        self.advance_token();
+                        self.column += 2;
+                        depth += 1;
+                    }
+                    '*' if self.peek() == '/' => {
+                        self.advance_token();
+                        self.column += 2;
+                        depth -= 1;
+                    }
+                    '\n' => {
+                        self.line += 1;
+                        self.column = 1;
+                    }
+                    _ => self.column += 1,
+                }
             }
-            assert_eq!(
-                self.current + lookahead < self.source.len() as u32,
-                true,
-                "[peek_n] Current index is out of bounds!"
-            );
-            self.source.chars().nth(self.current as usize + 1).unwrap()
         }
 
+        /// Scans the rest of a string literal, up to its closing quote or the next `${`.
         fn string(&mut self) {
+            let mut value = String::new();
             while self.peek() != '"' && !self.is_last() {
-                if self.peek() == '\n' {
-                    self.line += 1;
+                if self.peek() == '$' && self.peek_n(1) == '{' {
+                    self.advance_token();
+                    self.advance_token();
+                    self.interpolations.push(0);
+                    self.add_token(TokenType::Interpolation(value));
+                    return;
+                }
+
+                match self.advance_token() {
+                    '\\' => {
+                        if let Some(c) = self.escape_sequence() {
+                            value.push(c);
+                        }
+                    }
+                    c => {
+                        if c == '\n' {
+                            self.line += 1;
+                        }
+                        value.push(c);
+                    }
                 }
-                self.advance_token();
             }
 
             if self.is_last() {
-                error(
-                    self.line,
-                    self.column,
-                    String::from("Unterminated string."),
-                    function_name!(),
-                    Some(RLoxErrorType::ScannerError),
-                );
+                self.report(String::from("Unterminated string."), function_name!());
                 return;
             }
 
             self.advance_token();
+            self.add_token(TokenType::String(value));
+        }
 
-            let value: String = match self
-                .source
-                .get(self.start as usize + 1..self.current as usize - 1)
-            {
-                Some(value) => value.to_string(),
-                None => panic!("[string] Could not get value from source!"),
+        /// Decodes the escape sequence after a `\`, `\u{1F600}` takes up to 6 hex digits.
+        fn escape_sequence(&mut self) -> Option<char> {
+            if self.is_last() {
+                return None;
+            }
+            let c = match self.advance_token() {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                '0' => '\0',
+                '"' => '"',
+                '\\' => '\\',
+                '$' => '$',
+                'u' => return self.unicode_escape(),
+                c => {
+                    self.report(
+                        format!("Invalid escape sequence '\\{}'.", c),
+                        function_name!(),
+                    );
+                    return None;
+                }
             };
-            self.add_token(TokenType::String(value));
+            Some(c)
+        }
+
+        fn unicode_escape(&mut self) -> Option<char> {
+            let mut digits = String::new();
+            if self.match_token('{') {
+                while self.peek().is_ascii_hexdigit() && digits.len() < 6 {
+                    digits.push(self.advance_token());
+                }
+                if !self.match_token('}') {
+                    digits.clear();
+                }
+            }
+
+            match u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+            {
+                Some(c) => Some(c),
+                None => {
+                    self.report(
+                        String::from("Invalid unicode escape sequence."),
+                        function_name!(),
+                    );
+                    None
+                }
+            }
         }
 
         fn match_token(&mut self, expected: char) -> bool {
@@ -399,7 +796,7 @@ pub mod scan {
                 return false;
             }
 
-            if self.source.chars().nth(self.current as usize).unwrap() != expected {
+            if self.peek() != expected {
                 return false;
             }
 
@@ -407,44 +804,100 @@ pub mod scan {
             true
         }
 
+        /// Scans decimal literals with an optional fraction and exponent, as well as `0x`, `0b`
+        /// and `0o` integers. Digits may be grouped with single underscores, e.g. `1_000_000`.
         fn number(&mut self) {
-            while self.peek().is_digit(10) {
+            let value = if self.source.get(self.start as usize) == Some(&'0')
+                && matches!(self.peek(), 'x' | 'X' | 'b' | 'B' | 'o' | 'O')
+            {
+                let radix = match self.advance_token() {
+                    'x' | 'X' => 16,
+                    'b' | 'B' => 2,
+                    _ => 8,
+                };
+                let mut digits = String::new();
+                self.digits(&mut digits, radix);
+                match digits.is_empty() {
+                    true => None,
+                    false => Some(digits.chars().fold(0.0, |value, digit| {
+                        value * radix as f64 + digit.to_digit(radix).unwrap() as f64
+                    })),
+                }
+            } else {
+                let mut text = self.lexeme();
+                self.digits(&mut text, 10);
+
+                if self.peek() == '.' && self.peek_n(1).is_ascii_digit() {
+                    self.advance_token();
+                    text.push('.');
+                    self.digits(&mut text, 10);
+                }
+
+                let signed = matches!(self.peek_n(1), '+' | '-');
+                if matches!(self.peek(), 'e' | 'E')
+                    && self.peek_n(if signed { 2 } else { 1 }).is_ascii_digit()
+                {
+                    text.push(self.advance_token());
+                    if signed {
+                        text.push(self.advance_token());
+                    }
+                    self.digits(&mut text, 10);
+                }
+                text.parse::<f64>().ok()
+            };
+
+            // a literal running into letters, e.g. `0b12`, `1e` or `1_`, is a single malformed token.
+            let mut malformed = value.is_none();
+            while self.peek().is_alphanumeric() || self.peek() == '_' {
                 self.advance_token();
+                malformed = true;
             }
 
-            if self.peek() == '.' && self.peek_n(2).is_digit(10) {
-                self.advance_token();
+            match value {
+                Some(value) if !malformed => self.add_token(TokenType::Number(value)),
+                _ => {
+                    let text = self.lexeme();
+                    self.report(
+                        format!("Invalid number literal '{}'.", text),
+                        function_name!(),
+                    );
+                    self.column += text.chars().count() as u32;
+                }
+            }
+        }
 
-                while self.peek().is_digit(10) {
+        /// Appends digits of the given radix to `text`, dropping the underscores that separate them.
+        fn digits(&mut self, text: &mut String, radix: u32) {
+            loop {
+                if self.peek().is_digit(radix) {
+                    text.push(self.advance_token());
+                } else if self.peek() == '_'
+                    && text.chars().last().is_some_and(|c| c.is_digit(radix))
+                    && self.peek_n(1).is_digit(radix)
+                {
                     self.advance_token();
+                } else {
+                    return;
                 }
             }
-
-            let value: f64 = match self.source.get(self.start as usize..self.current as usize) {
-                Some(value) => value.parse::<f64>().unwrap_or(-1.00),
-                None => panic!("[number] Could not get value from source!"),
-            };
-            self.add_token(TokenType::Number(value));
         }
 
         fn add_token(&mut self, t_type: TokenType) {
             // TODO: add functionality for columns of tokens and also the length of the token
-            let text: String = match self.source.get(self.start as usize..self.current as usize) {
-                Some(text) => text.to_string(),
-                None => panic!("[add_token] Could not get text from source!"),
-            };
+            let text = self.lexeme();
+            let length = text.chars().count() as u32;
             self.tokens.push(Token {
                 t_type: t_type,
-                lexeme: text.clone(),
+                lexeme: text,
                 line: self.line,
                 column: self.column,
-                length: text.clone().len() as u32,
+                length,
             });
 
             // !! IMPORTANT !! --> we should show the error to an user at the start
             // of the variable, therefore increase self.column and only show it at
             // the next token added.
-            self.column += text.clone().len() as u32;
+            self.column += length;
         }
     }
 
@@ -470,6 +923,76 @@ pub mod scan {
             assert_eq!(tokens[0].t_type, TokenType::EOF);
         }
 
+        #[test]
+        fn read_shebang_ok() {
+            let mut scanner = Scanner::new(String::from("#!/usr/bin/env rlox\nident"));
+            let tokens = scanner.scan_tokens();
+            assert!(scanner.errors().is_empty());
+            assert_eq!(tokens.len(), 2);
+            assert_eq!((tokens[0].line, tokens[0].column), (2, 1));
+        }
+
+        #[test]
+        fn read_non_ascii_ok() {
+            let mut scanner = Scanner::new(String::from("// ☃\n\"ä😀\" + é;"));
+            let tokens = scanner.scan_tokens();
+            assert!(scanner.errors().is_empty());
+            assert_eq!(tokens[0].t_type, TokenType::String(String::from("ä😀")));
+            assert_eq!(tokens[0].length, 4);
+            assert_eq!(tokens[2].t_type, TokenType::Identifier(String::from("é")));
+            assert_eq!((tokens[2].line, tokens[2].column), (2, 8));
+        }
+
+        #[test]
+        fn read_numbers_and_block_comments_after_non_ascii_ok() {
+            let mut scanner = Scanner::new(String::from(
+                "\"é\" /* ☃ /* ü */ */ 0x1F 1_000 2.5e1 é /* ä",
+            ));
+            let tokens = scanner.scan_tokens();
+            assert_eq!(tokens[1].t_type, TokenType::Number(31.0));
+            assert_eq!(tokens[2].t_type, TokenType::Number(1000.0));
+            assert_eq!(tokens[3].t_type, TokenType::Number(25.0));
+            assert_eq!((tokens[3].line, tokens[3].column), (1, 32));
+            assert_eq!(tokens[4].t_type, TokenType::Identifier(String::from("é")));
+            assert_eq!(scanner.errors().len(), 1);
+        }
+
+        #[test]
+        fn read_unterminated_at_end_nok() {
+            for source in ["\"", "\"\\", "/*", "0x", "1e", "\"\\u{"] {
+                let mut scanner = Scanner::new(String::from(source));
+                let tokens = scanner.scan_tokens();
+                assert!(scanner.had_error(), "{}", source);
+                assert_eq!(tokens.last().unwrap().t_type, TokenType::EOF);
+            }
+        }
+
+        #[test]
+        fn read_block_comment_ok() {
+            let mut scanner = Scanner::new(String::from(
+                "/* first\n /* nested\n */ still a comment */ ident /**/",
+            ));
+            let tokens = scanner.scan_tokens();
+            assert!(scanner.errors().is_empty());
+            assert_eq!(tokens.len(), 2);
+            assert_eq!(
+                tokens[0].t_type,
+                TokenType::Identifier(String::from("ident"))
+            );
+            assert_eq!((tokens[0].line, tokens[0].column), (3, 24));
+        }
+
+        #[test]
+        fn read_unterminated_block_comment_nok() {
+            let mut scanner = Scanner::new(String::from("print 1;\n /* a /* b */\n"));
+            let tokens = scanner.scan_tokens();
+            assert_eq!(tokens.len(), 4);
+            let errors = scanner.errors();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].message, "Unterminated block comment.");
+            assert_eq!((errors[0].line, errors[0].column), (2, 2));
+        }
+
         // generate a test for each token type
         macro_rules! test_token {
             ($name:ident, $token:expr, $expected:expr) => {
@@ -496,6 +1019,9 @@ pub mod scan {
         test_token!(read_right_brace_ok, "}", TokenType::RightBrace);
         test_token!(read_comma_ok, ",", TokenType::Comma);
         test_token!(read_dot_ok, ".", TokenType::Dot);
+        test_token!(read_dot_dot_dot_ok, "...", TokenType::DotDotDot);
+        test_token!(read_left_bracket_ok, "[", TokenType::LeftBracket);
+        test_token!(read_right_bracket_ok, "]", TokenType::RightBracket);
         test_token!(read_minus_ok, "-", TokenType::Minus);
         test_token!(read_plus_ok, "+", TokenType::Plus);
         test_token!(read_semicolon_ok, ";", TokenType::Semicolon);
@@ -505,27 +1031,54 @@ pub mod scan {
         test_token!(read_bang_equal_ok, "!=", TokenType::BangEqual);
         test_token!(read_equal_ok, "=", TokenType::Equal);
         test_token!(read_equal_equal_ok, "==", TokenType::EqualEqual);
+        test_token!(read_equal_greater_ok, "=>", TokenType::EqualGreater);
         test_token!(read_greater_ok, ">", TokenType::Greater);
         test_token!(read_greater_equal_ok, ">=", TokenType::GreaterEqual);
         test_token!(read_less_ok, "<", TokenType::Less);
         test_token!(read_less_equal_ok, "<=", TokenType::LessEqual);
+        test_token!(read_percent_ok, "%", TokenType::Percent);
+        test_token!(read_star_star_ok, "**", TokenType::StarStar);
+        test_token!(read_ampersand_ok, "&", TokenType::Ampersand);
+        test_token!(read_pipe_ok, "|", TokenType::Pipe);
+        test_token!(read_caret_ok, "^", TokenType::Caret);
+        test_token!(read_tilde_ok, "~", TokenType::Tilde);
+        test_token!(read_less_less_ok, "<<", TokenType::LessLess);
+        test_token!(read_greater_greater_ok, ">>", TokenType::GreaterGreater);
+        test_token!(read_plus_plus_ok, "++", TokenType::PlusPlus);
+        test_token!(read_minus_minus_ok, "--", TokenType::MinusMinus);
+        test_token!(read_plus_equal_ok, "+=", TokenType::PlusEqual);
+        test_token!(read_minus_equal_ok, "-=", TokenType::MinusEqual);
+        test_token!(read_star_equal_ok, "*=", TokenType::StarEqual);
+        test_token!(read_slash_equal_ok, "/=", TokenType::SlashEqual);
+        test_token!(read_percent_equal_ok, "%=", TokenType::PercentEqual);
+        test_token!(read_question_ok, "?", TokenType::Question);
+        test_token!(read_question_question_ok, "??", TokenType::QuestionQuestion);
+        test_token!(read_question_dot_ok, "?.", TokenType::QuestionDot);
         // test keywords and identifiers
         test_token!(read_and_ok, "and", TokenType::And);
         test_token!(read_class_ok, "class", TokenType::Class);
+        test_token!(read_const_ok, "const", TokenType::Const);
+        test_token!(read_case_ok, "case", TokenType::Case);
+        test_token!(read_default_ok, "default", TokenType::Default);
+        test_token!(read_match_ok, "match", TokenType::Match);
         test_token!(read_else_ok, "else", TokenType::Else);
         test_token!(read_false_ok, "false", TokenType::False);
         test_token!(read_fun_ok, "fun", TokenType::Fun);
         test_token!(read_for_ok, "for", TokenType::For);
         test_token!(read_if_ok, "if", TokenType::If);
+        test_token!(read_in_ok, "in", TokenType::In);
         test_token!(read_nil_ok, "nil", TokenType::Nil);
         test_token!(read_or_ok, "or", TokenType::Or);
         test_token!(read_print_ok, "print", TokenType::Print);
         test_token!(read_return_ok, "return", TokenType::Return);
         test_token!(read_super_ok, "super", TokenType::Super);
         test_token!(read_this_ok, "this", TokenType::This);
+        test_token!(read_trait_ok, "trait", TokenType::Trait);
         test_token!(read_true_ok, "true", TokenType::True);
         test_token!(read_var_ok, "var", TokenType::Var);
         test_token!(read_while_ok, "while", TokenType::While);
+        test_token!(read_with_ok, "with", TokenType::With);
+        test_token!(read_yield_ok, "yield", TokenType::Yield);
         test_token!(
             read_identifier_ok,
             "identifier",
@@ -541,6 +1094,11 @@ pub mod scan {
             "\"first\nsecond\nthird\nfourth\"",
             TokenType::String(String::from("first\nsecond\nthird\nfourth"))
         );
+        test_token!(
+            read_string_escapes_ok,
+            r#""a\tb\n\"c\" \\ \${d} \u{48}\u{1F600}""#,
+            TokenType::String(String::from("a\tb\n\"c\" \\ ${d} H\u{1F600}"))
+        );
         test_token!(read_number_ok, "123", TokenType::Number(123.00));
         test_token!(
             read_number_with_decimal_ok,
@@ -554,6 +1112,51 @@ pub mod scan {
         );
         test_token!(read_negative_number_ok, "-123", TokenType::Number(123.00));
 
+        test_token!(read_hex_number_ok, "0xFF_ff", TokenType::Number(65535.00));
+        test_token!(read_binary_number_ok, "0b1010", TokenType::Number(10.00));
+        test_token!(read_octal_number_ok, "0O17", TokenType::Number(15.00));
+        test_token!(
+            read_number_with_separators_ok,
+            "1_000_000.000_5",
+            TokenType::Number(1_000_000.000_5)
+        );
+        test_token!(
+            read_number_with_exponent_ok,
+            "1e-9",
+            TokenType::Number(1e-9)
+        );
+        test_token!(
+            read_number_with_signed_exponent_ok,
+            "2.5E+3",
+            TokenType::Number(2500.00)
+        );
+
+        #[test]
+        fn read_invalid_number_nok() {
+            let mut scanner = Scanner::new(String::from("0x 0b102 1__0 1_ 1e 1e+ 12abc 3;"));
+            let tokens = scanner.scan_tokens();
+            let messages: Vec<&str> = scanner
+                .errors()
+                .iter()
+                .map(|scan_error| scan_error.message.as_str())
+                .collect();
+            assert_eq!(
+                messages,
+                vec![
+                    "Invalid number literal '0x'.",
+                    "Invalid number literal '0b102'.",
+                    "Invalid number literal '1__0'.",
+                    "Invalid number literal '1_'.",
+                    "Invalid number literal '1e'.",
+                    "Invalid number literal '1e'.",
+                    "Invalid number literal '12abc'.",
+                ]
+            );
+            assert_eq!(tokens[0].t_type, TokenType::Plus);
+            assert_eq!(tokens[1].t_type, TokenType::Number(3.00));
+            assert_eq!(tokens[1].column, 31);
+        }
+
         // try to read a token that is not supported
         #[test]
         fn read_unsupported_token_nok() {
@@ -563,6 +1166,92 @@ pub mod scan {
             assert_eq!(tokens[0].t_type, TokenType::EOF);
         }
 
+        #[test]
+        fn read_invalid_escape_nok() {
+            let mut scanner = Scanner::new(String::from(r#""\q \u{110000}""#));
+            let tokens = scanner.scan_tokens();
+            let messages: Vec<&str> = scanner
+                .errors()
+                .iter()
+                .map(|scan_error| scan_error.message.as_str())
+                .collect();
+            assert_eq!(
+                messages,
+                vec![
+                    "Invalid escape sequence '\\q'.",
+                    "Invalid unicode escape sequence."
+                ]
+            );
+            assert_eq!(tokens[0].t_type, TokenType::String(String::from(" ")));
+        }
+
+        #[test]
+        fn read_interpolation_ok() {
+            let mut scanner = Scanner::new(String::from(r#""a ${b + "${c}"} {d} ${ {} }e""#));
+            let types: Vec<TokenType> = scanner
+                .scan_tokens()
+                .into_iter()
+                .map(|token| token.t_type)
+                .collect();
+            assert_eq!(
+                types,
+                vec![
+                    TokenType::Interpolation(String::from("a ")),
+                    TokenType::Identifier(String::from("b")),
+                    TokenType::Plus,
+                    TokenType::Interpolation(String::new()),
+                    TokenType::Identifier(String::from("c")),
+                    TokenType::String(String::new()),
+                    TokenType::Interpolation(String::from(" {d} ")),
+                    TokenType::LeftBrace,
+                    TokenType::RightBrace,
+                    TokenType::String(String::from("e")),
+                    TokenType::EOF,
+                ]
+            );
+        }
+
+        #[test]
+        fn dump_tokens_table_with_inline_error_ok() {
+            let dump = dump_tokens(String::from("var a @ 1;"), TokenDumpFormat::Table);
+            let lines: Vec<&str> = dump.lines().collect();
+            assert_eq!(lines[0], " LINE  COL  LEN  TYPE          LEXEME");
+            assert_eq!(lines[1], "    1    1    3  Var           var");
+            assert_eq!(lines[2], "    1    5    1  Identifier    a");
+            assert_eq!(
+                lines[3],
+                "    1    7    -  ERROR         Unexpected character: @"
+            );
+            assert_eq!(lines[4], "    1    9    1  Number        1");
+            assert_eq!(lines.len(), 7);
+        }
+
+        #[test]
+        fn dump_tokens_jsonl_ok() {
+            let dump = dump_tokens(String::from("print \"a\";"), TokenDumpFormat::Jsonl);
+            let lines: Vec<&str> = dump.lines().collect();
+            assert_eq!(lines.len(), 4);
+            assert_eq!(
+                lines[1],
+                r#"{"kind":"String","value":"a","lexeme":"\"a\"","line":1,"column":7,"length":3}"#
+            );
+            assert!(lines[3].starts_with(r#"{"kind":"EOF""#));
+        }
+
+        #[test]
+        fn dump_tokens_keeps_callers_capture_ok() {
+            use crate::error_handling::error_handling::{finish_capture, start_capture};
+
+            start_capture();
+            error(1, 1, String::from("before"), None, None);
+            dump_tokens(String::from("@"), TokenDumpFormat::Table);
+            error(2, 1, String::from("after"), None, None);
+            let captured = finish_capture();
+            assert_eq!(captured.errors.len(), 2);
+            assert!(captured.errors[0].contains("before"));
+            assert!(captured.errors[1].contains("after"));
+        }
+
         #[test]
         fn read_multiple_tokens_ok() {
             let mut scanner = Scanner::new(String::from("(){}"));
@@ -3,27 +3,103 @@ pub mod stmt {
     use crate::expr::expr::{Expr, Visitor};
     use crate::rlox_callable::rlox_callable::Callable;
     use crate::scanner::scan::Token;
+    use graphviz_rust::cmd::{CommandArg, Format};
+    use graphviz_rust::dot_structures::{
+        Attribute, Edge, EdgeTy, Graph, GraphAttributes, Id, Node, NodeId, Stmt as DotStmt,
+        Subgraph, Vertex,
+    };
+    use graphviz_rust::printer::PrinterContext;
     use log::debug;
+    use std::cell::RefCell;
     use std::fmt;
     use std::fs::File;
     use std::io::prelude::*;
-    use std::process::Command;
+    use std::path::{Path, PathBuf};
+    use std::rc::Rc;
 
     #[derive(Clone, Debug, PartialEq)]
     pub enum Stmt {
         ExprStmt(Expr),
-        PrintStmt(Expr),
+        PrintStmt(Token /*keyword*/, Expr),
         ReturnStmt(Token, Expr),
-        VarStmt(Token, Expr),
+        YieldStmt(Token /*keyword*/, Expr),
+        VarStmt(Token, Option<Token> /*type*/, Expr),
+        ConstStmt(Token, Option<Token> /*type*/, Expr),
         BlockStmt(Vec<Stmt>),
         ClassStmt(
             Token,
-            Option<Expr>, /*superclass*/
-            Vec<Stmt>,    /*methods*/
+            Option<Expr>,        /*superclass*/
+            Vec<Expr>,           /*traits*/
+            Vec<Stmt>,           /*methods*/
+            Vec<(Token, Token)>, /*field types*/
         ),
-        Function(Token, Vec<Token>, Vec<Stmt>),
+        TraitStmt(Token, Vec<Stmt> /*methods*/),
+        Function(Token, Vec<Token>, Vec<Stmt>, FunctionTypes, OptionalParams),
         IfStmt(Expr, Box<Stmt>, Option<Box<Stmt>>),
         WhileStmt(Expr, Box<Stmt>),
+        MatchStmt(
+            Token, /*keyword*/
+            Expr,
+            Vec<MatchCase>,
+            Option<Box<Stmt>>, /*default*/
+        ),
+    }
+
+    /// `case patterns if guard => body`, the body runs for the first case that matches.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct MatchCase {
+        pub keyword: Token,
+        pub patterns: Vec<Pattern>,
+        pub guard: Option<Expr>,
+        pub body: Stmt,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Pattern {
+        /// A number, string, bool or nil, compared with `==`.
+        Literal(Token, LiteralValue),
+        /// Matches the instances of the class and of its subclasses, optionally binding them.
+        Class(Token, Option<Token> /*binding*/),
+    }
+
+    impl fmt::Display for Pattern {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Pattern::Literal(_, LiteralValue::String(str)) => write!(f, "\"{}\"", str),
+                Pattern::Literal(_, value) => write!(f, "{}", value),
+                Pattern::Class(name, Some(binding)) => {
+                    write!(f, "{} {}", name.get_token_type(), binding.get_token_type())
+                }
+                Pattern::Class(name, None) => write!(f, "{}", name.get_token_type()),
+            }
+        }
+    }
+
+    /// Optional type annotations of a function, one entry per parameter.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct FunctionTypes {
+        pub params: Vec<Option<Token>>,
+        pub result: Option<Token>,
+    }
+
+    impl FunctionTypes {
+        pub fn is_empty(&self) -> bool {
+            self.result.is_none() && self.params.iter().all(|param| param.is_none())
+        }
+    }
+
+    /// Default values of a function, one entry per parameter, and whether the last parameter
+    /// collects the remaining arguments.
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct OptionalParams {
+        pub defaults: Vec<Option<Expr>>,
+        pub rest: bool,
+    }
+
+    impl OptionalParams {
+        pub fn is_empty(&self) -> bool {
+            !self.rest && self.defaults.iter().all(|default| default.is_none())
+        }
     }
 
     #[derive(Clone, Debug, PartialEq)]
@@ -32,6 +108,7 @@ pub mod stmt {
         Bool(bool),
         String(String),
         Callable(Callable),
+        List(Rc<RefCell<Vec<Rc<LiteralValue>>>>),
         Nil,
     }
 
@@ -42,6 +119,14 @@ pub mod stmt {
                 LiteralValue::Bool(bool) => write!(f, "{}", bool),
                 LiteralValue::String(str) => write!(f, "{}", str),
                 LiteralValue::Callable(callable_box) => write!(f, "{}", *callable_box),
+                LiteralValue::List(list) => {
+                    let elements: Vec<String> = list
+                        .borrow()
+                        .iter()
+                        .map(|element| element.to_string())
+                        .collect();
+                    write!(f, "[{}]", elements.join(", "))
+                }
                 LiteralValue::Nil => write!(f, "Nil"),
             }
         }
@@ -52,10 +137,14 @@ pub mod stmt {
             match self {
                 Stmt::ExprStmt(expr) => write!(f, "{}", expr),
                 Stmt::ReturnStmt(_keyword, value) => write!(f, "(return {:?})", value),
-                Stmt::PrintStmt(expr) => write!(f, "(print {})", expr),
-                Stmt::VarStmt(token, expr) => {
+                Stmt::YieldStmt(_keyword, value) => write!(f, "(yield {})", value),
+                Stmt::PrintStmt(_keyword, expr) => write!(f, "(print {})", expr),
+                Stmt::VarStmt(token, _, expr) => {
                     write!(f, "(var {} {})", token.get_token_type(), expr)
                 }
+                Stmt::ConstStmt(token, _, expr) => {
+                    write!(f, "(const {} {})", token.get_token_type(), expr)
+                }
                 Stmt::BlockStmt(stmts) => {
                     let mut stmts_str = String::new();
                     for stmt in stmts {
@@ -63,24 +152,45 @@ pub mod stmt {
                     }
                     write!(f, "{}", stmts_str)
                 }
-                Stmt::ClassStmt(name, _superclass, methods) => {
+                Stmt::ClassStmt(name, _superclass, traits, methods, _) => {
                     let mut methods_str = String::new();
                     // iterate over methods and add them to the string.
                     for method in methods {
                         methods_str.push_str(format!("{}, ", method).as_str());
                     }
+                    let traits: Vec<String> = traits.iter().map(|t| t.to_string()).collect();
+                    write!(
+                        f,
+                        "(class: <{}> traits: [{}] methods: [{}])",
+                        name.get_token_type(),
+                        traits.join(", "),
+                        methods_str
+                    )
+                }
+                Stmt::TraitStmt(name, methods) => {
+                    let mut methods_str = String::new();
+                    for method in methods {
+                        methods_str.push_str(format!("{}, ", method).as_str());
+                    }
                     write!(
                         f,
-                        "(class: <{}> methods: [{}])",
+                        "(trait: <{}> methods: [{}])",
                         name.get_token_type(),
                         methods_str
                     )
                 }
-                Stmt::Function(name, params, body) => {
+                Stmt::Function(name, params, body, _, optional) => {
                     let mut function_str = String::new();
                     function_str.push_str(format!("(fun {} (", name.get_token_type()).as_str());
-                    for param in params {
-                        function_str.push_str(format!("{} ", param.get_token_type()).as_str());
+                    for (idx, param) in params.iter().enumerate() {
+                        if optional.rest && idx + 1 == params.len() {
+                            function_str.push_str("...");
+                        }
+                        function_str.push_str(format!("{}", param.get_token_type()).as_str());
+                        if let Some(Some(default)) = optional.defaults.get(idx) {
+                            function_str.push_str(format!(" = {}", default).as_str());
+                        }
+                        function_str.push(' ');
                     }
                     function_str.push_str(") ");
                     for stmt in body {
@@ -99,6 +209,26 @@ pub mod stmt {
                     write!(f, "{}", if_stmt_str)
                 }
                 Stmt::WhileStmt(expr, stmt) => write!(f, "(while {} {})", expr, stmt),
+                Stmt::MatchStmt(_, subject, cases, default) => {
+                    let mut match_str = format!("(match {} ", subject);
+                    for case in cases {
+                        let patterns: Vec<String> = case
+                            .patterns
+                            .iter()
+                            .map(|pattern| pattern.to_string())
+                            .collect();
+                        match_str.push_str(format!("(case {} ", patterns.join(", ")).as_str());
+                        if let Some(guard) = &case.guard {
+                            match_str.push_str(format!("if {} ", guard).as_str());
+                        }
+                        match_str.push_str(format!("{}) ", case.body).as_str());
+                    }
+                    if let Some(default) = default {
+                        match_str.push_str(format!("(default {}) ", default).as_str());
+                    }
+                    match_str.push(')');
+                    write!(f, "{}", match_str)
+                }
             }
         }
     }
@@ -111,176 +241,283 @@ pub mod stmt {
         fn visit_expr_stmt(&mut self, expr: &Expr) -> T;
         fn visit_print_stmt(&mut self, expr: &Expr) -> T;
         fn visit_return_stmt(&mut self, keyword: &Token, expr: &Expr) -> T;
+        fn visit_yield_stmt(&mut self, keyword: &Token, expr: &Expr) -> T;
         fn visit_var_stmt(&mut self, token: &Token, expr: &Expr) -> T;
+        fn visit_const_stmt(&mut self, token: &Token, expr: &Expr) -> T;
         fn visit_block_stmt(&mut self, stmts: &Vec<Stmt>) -> T;
         fn visit_class_stmt(
             &mut self,
             name: &Token,
             superclass: &Option<Expr>,
+            traits: &Vec<Expr>,
             methods: &Vec<Stmt>,
         ) -> T;
-        fn visit_function_stmt(&mut self, name: &Token, params: &Vec<Token>, body: &Vec<Stmt>)
-            -> T;
+        fn visit_trait_stmt(&mut self, name: &Token, methods: &Vec<Stmt>) -> T;
+        fn visit_function_stmt(
+            &mut self,
+            name: &Token,
+            params: &Vec<Token>,
+            body: &Vec<Stmt>,
+            optional: &OptionalParams,
+        ) -> T;
         fn visit_if_stmt(&mut self, expr: &Expr, stmt: &Stmt, else_stmt: &Option<Box<Stmt>>) -> T;
         fn visit_while_stmt(&mut self, expr: &Expr, stmt: &Stmt) -> T;
+        fn visit_match_stmt(
+            &mut self,
+            keyword: &Token,
+            subject: &Expr,
+            cases: &Vec<MatchCase>,
+            default: &Option<Box<Stmt>>,
+        ) -> T;
     }
 
     impl StmtVisitable for Stmt {
         fn accept<T>(&self, visitor: &mut dyn StmtVisitor<T>) -> T {
             match self {
                 Stmt::ExprStmt(expr) => visitor.visit_expr_stmt(expr),
-                Stmt::PrintStmt(expr) => visitor.visit_print_stmt(expr),
+                Stmt::PrintStmt(_, expr) => visitor.visit_print_stmt(expr),
                 Stmt::ReturnStmt(keyword, expr) => visitor.visit_return_stmt(keyword, expr),
-                Stmt::VarStmt(token, expr) => visitor.visit_var_stmt(token, expr),
+                Stmt::YieldStmt(keyword, expr) => visitor.visit_yield_stmt(keyword, expr),
+                Stmt::VarStmt(token, _, expr) => visitor.visit_var_stmt(token, expr),
+                Stmt::ConstStmt(token, _, expr) => visitor.visit_const_stmt(token, expr),
                 Stmt::BlockStmt(stmts) => visitor.visit_block_stmt(stmts),
-                Stmt::ClassStmt(name, superclass, methods) => {
-                    visitor.visit_class_stmt(name, superclass, methods)
+                Stmt::ClassStmt(name, superclass, traits, methods, _) => {
+                    visitor.visit_class_stmt(name, superclass, traits, methods)
                 }
-                Stmt::Function(name, params, body) => {
-                    visitor.visit_function_stmt(name, params, body)
+                Stmt::TraitStmt(name, methods) => visitor.visit_trait_stmt(name, methods),
+                Stmt::Function(name, params, body, _, optional) => {
+                    visitor.visit_function_stmt(name, params, body, optional)
                 }
                 Stmt::IfStmt(expr, stmt, else_stmt) => visitor.visit_if_stmt(expr, stmt, else_stmt),
                 Stmt::WhileStmt(expr, stmt) => visitor.visit_while_stmt(expr, stmt),
+                Stmt::MatchStmt(keyword, subject, cases, default) => {
+                    visitor.visit_match_stmt(keyword, subject, cases, default)
+                }
             }
         }
     }
 
     impl Stmt {
+        /// Source line the statement starts on, used by the debugger.
+        pub fn line(&self) -> Option<u32> {
+            match self {
+                Stmt::ExprStmt(expr) => expr.line(),
+                Stmt::PrintStmt(keyword, _)
+                | Stmt::ReturnStmt(keyword, _)
+                | Stmt::YieldStmt(keyword, _)
+                | Stmt::MatchStmt(keyword, _, _, _) => Some(keyword.get_line()),
+                Stmt::VarStmt(name, _, _)
+                | Stmt::ConstStmt(name, _, _)
+                | Stmt::ClassStmt(name, _, _, _, _)
+                | Stmt::TraitStmt(name, _)
+                | Stmt::Function(name, _, _, _, _) => Some(name.get_line()),
+                Stmt::BlockStmt(stmts) => stmts.iter().find_map(|stmt| stmt.line()),
+                Stmt::IfStmt(expr, stmt, _) | Stmt::WhileStmt(expr, stmt) => {
+                    expr.line().or_else(|| stmt.line())
+                }
+            }
+        }
+
+        /// Whether running the statement can `yield`, the yields of nested functions don't count.
+        pub fn contains_yield(&self) -> bool {
+            match self {
+                Stmt::YieldStmt(_, _) => true,
+                Stmt::BlockStmt(stmts) => stmts.iter().any(Stmt::contains_yield),
+                Stmt::IfStmt(_, then_stmt, else_stmt) => {
+                    then_stmt.contains_yield()
+                        || else_stmt.as_ref().is_some_and(|stmt| stmt.contains_yield())
+                }
+                Stmt::WhileStmt(_, body) => body.contains_yield(),
+                Stmt::MatchStmt(_, _, cases, default) => {
+                    cases.iter().any(|case| case.body.contains_yield())
+                        || default.as_ref().is_some_and(|stmt| stmt.contains_yield())
+                }
+                _ => false,
+            }
+        }
+
         pub fn accept<T>(&self, visitor: &mut dyn StmtVisitor<T>) -> T {
             match self {
                 Stmt::ExprStmt(expr) => visitor.visit_expr_stmt(expr),
-                Stmt::PrintStmt(expr) => visitor.visit_print_stmt(expr),
+                Stmt::PrintStmt(_, expr) => visitor.visit_print_stmt(expr),
                 Stmt::ReturnStmt(keyword, lval) => visitor.visit_return_stmt(keyword, lval),
-                Stmt::VarStmt(token, expr) => visitor.visit_var_stmt(token, expr),
+                Stmt::YieldStmt(keyword, expr) => visitor.visit_yield_stmt(keyword, expr),
+                Stmt::VarStmt(token, _, expr) => visitor.visit_var_stmt(token, expr),
+                Stmt::ConstStmt(token, _, expr) => visitor.visit_const_stmt(token, expr),
                 Stmt::BlockStmt(stmts) => visitor.visit_block_stmt(stmts),
-                Stmt::ClassStmt(name, superclass, methods) => {
-                    visitor.visit_class_stmt(name, superclass, methods)
+                Stmt::ClassStmt(name, superclass, traits, methods, _) => {
+                    visitor.visit_class_stmt(name, superclass, traits, methods)
                 }
-                Stmt::Function(name, params, body) => {
-                    visitor.visit_function_stmt(name, params, body)
+                Stmt::TraitStmt(name, methods) => visitor.visit_trait_stmt(name, methods),
+                Stmt::Function(name, params, body, _, optional) => {
+                    visitor.visit_function_stmt(name, params, body, optional)
                 }
                 Stmt::IfStmt(expr, stmt, else_stmt) => visitor.visit_if_stmt(expr, stmt, else_stmt),
                 Stmt::WhileStmt(expr, stmt) => visitor.visit_while_stmt(expr, stmt),
+                Stmt::MatchStmt(keyword, subject, cases, default) => {
+                    visitor.visit_match_stmt(keyword, subject, cases, default)
+                }
             }
         }
     }
 
+    #[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+    pub enum GraphFormat {
+        Svg,
+        Png,
+    }
+
+    impl GraphFormat {
+        pub fn extension(&self) -> &'static str {
+            match self {
+                GraphFormat::Svg => "svg",
+                GraphFormat::Png => "png",
+            }
+        }
+    }
+
+    /// Quotes a label for the dot language, escaping quotes, backslashes and line breaks.
+    pub fn escape_label(label: &str) -> String {
+        let mut escaped = String::from("\"");
+        for chr in label.chars() {
+            match chr {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => (),
+                _ => escaped.push(chr),
+            }
+        }
+        escaped.push('"');
+        escaped
+    }
+
+    /**
+     * Builds a single Graphviz graph for the whole program, every top-level
+     * statement gets its own cluster subgraph.
+     */
     pub struct StmtGraphvizPrinter {
-        pub graph_name: String,
-        pub graph: String,
         pub node_count: u64,
+        stmts: Vec<DotStmt>,
     }
 
     impl StmtGraphvizPrinter {
-        pub fn new(name: String) -> StmtGraphvizPrinter {
+        pub fn new() -> StmtGraphvizPrinter {
             StmtGraphvizPrinter {
-                graph_name: name,
-                graph: String::from("digraph G { \n rankdir=\"LR\" \n"),
                 node_count: 0,
+                stmts: Vec::new(),
             }
         }
 
-        pub fn generate(ast_val: &Vec<Stmt>) {
-            let mut counter: u32 = 0;
-            for expr in ast_val {
-                // generate graph from AST both as a dot file and as a png image.
-                let graph_name = format!("graph_{}", counter);
-                let mut graph_printer = StmtGraphvizPrinter::new(graph_name);
-                counter += 1;
-                expr.accept(&mut graph_printer);
-                graph_printer.close_graph();
-                graph_printer.write_to_file();
-                graph_printer.generate_image();
+        pub fn graph(ast_val: &[Stmt]) -> Graph {
+            let mut graph_printer = StmtGraphvizPrinter::new();
+            let mut graph_stmts = vec![DotStmt::GAttribute(GraphAttributes::Graph(vec![
+                Attribute(
+                    Id::Plain(String::from("rankdir")),
+                    Id::Plain(String::from("LR")),
+                ),
+            ]))];
+
+            for (idx, stmt) in ast_val.iter().enumerate() {
+                let label = match stmt.line() {
+                    Some(line) => format!("statement {} (line {})", idx, line),
+                    None => format!("statement {}", idx),
+                };
+                graph_printer.stmts = vec![DotStmt::Attribute(Attribute(
+                    Id::Plain(String::from("label")),
+                    Id::Escaped(escape_label(&label)),
+                ))];
+                stmt.accept(&mut graph_printer);
+
+                graph_stmts.push(DotStmt::Subgraph(Subgraph {
+                    id: Id::Plain(format!("cluster_{}", idx)),
+                    stmts: std::mem::take(&mut graph_printer.stmts),
+                }));
             }
-        }
 
-        pub fn close_graph(&mut self) {
-            self.graph.push_str("}");
+            Graph::DiGraph {
+                id: Id::Plain(String::from("AST")),
+                strict: false,
+                stmts: graph_stmts,
+            }
         }
 
-        pub fn increase_node_count(&mut self) {
-            self.node_count += 1;
+        pub fn to_dot(ast_val: &[Stmt]) -> String {
+            graphviz_rust::print(
+                StmtGraphvizPrinter::graph(ast_val),
+                &mut PrinterContext::default(),
+            )
         }
 
-        pub fn add_node(&mut self, label: String) -> u64 {
-            self.increase_node_count();
-            // special rule for escaping strings which contain " as first and last character.
-            let formated_label = if label.starts_with("\"") && label.ends_with("\"") {
-                format!(
-                    "\"{}\"",
-                    "\\".to_string() + (&label.clone()[..label.len() - 1]) + "\\\""
-                )
-            } else {
-                format!("\"{}\"", label)
-            };
+        /// Writes `<name>.dot` and the rendered `<name>.svg`/`<name>.png` into `out_dir`.
+        pub fn generate(
+            ast_val: &[Stmt],
+            out_dir: &str,
+            name: &str,
+            format: GraphFormat,
+        ) -> std::io::Result<PathBuf> {
+            std::fs::create_dir_all(out_dir)?;
+            let out_dir = Path::new(out_dir);
 
-            self.graph.push_str(
-                format!("\tnode_{} [label={}];\n", self.node_count, formated_label).as_str(),
-            );
-            self.node_count
-        }
+            let dot = StmtGraphvizPrinter::to_dot(ast_val);
+            let mut dot_file = File::create(out_dir.join(format!("{}.dot", name)))?;
+            dot_file.write_all(dot.as_bytes())?;
 
-        pub fn add_edge(&mut self, from: u64, to: u64) {
-            self.graph
-                .push_str(format!("\tnode_{} -> node_{};\n", from, to).as_str());
-        }
-
-        pub fn to_string(&self) -> String {
-            self.graph.clone()
+            let image_path = out_dir.join(format!("{}.{}", name, format.extension()));
+            let image = StmtGraphvizPrinter::render(&dot, format)?;
+            File::create(&image_path)?.write_all(&image)?;
+            debug!("Rendered AST graph to {}", image_path.display());
+            Ok(image_path)
         }
 
-        pub fn write_to_file(&self) {
-            let path = self.path_to_generated() + &self.graph_name + ".dot";
-
-            let mut file = match File::create(path) {
-                Ok(file) => file,
-                Err(why) => panic!("couldn't create file: {}", why),
+        /// Renders the dot graph with Graphviz's `dot` executable, which draws the statement clusters.
+        pub fn render(dot: &str, format: GraphFormat) -> std::io::Result<Vec<u8>> {
+            let dot_format = match format {
+                GraphFormat::Svg => Format::Svg,
+                GraphFormat::Png => Format::Png,
             };
+            graphviz_rust::exec_dot(dot.to_string(), vec![CommandArg::Format(dot_format)]).map_err(
+                |why| match why.kind() {
+                    std::io::ErrorKind::NotFound => std::io::Error::new(
+                        why.kind(),
+                        "graphviz `dot` executable not found, only the .dot file was written",
+                    ),
+                    _ => why,
+                },
+            )
+        }
 
-            match file.write_all(self.graph.as_bytes()) {
-                Ok(_) => {}
-                Err(why) => panic!("couldn't write to file: {}", why),
-            }
+        pub fn increase_node_count(&mut self) {
+            self.node_count += 1;
         }
 
-        fn path_to_generated(&self) -> String {
-            let current_dir = match std::env::current_dir() {
-                Ok(dir) => dir,
-                Err(why) => panic!("couldn't get current dir: {}", why),
-            };
+        fn node_id(id: u64) -> NodeId {
+            NodeId(Id::Plain(format!("node_{}", id)), None)
+        }
 
-            // make sure bothh generated and ast directories exist, if not create them.
-            let generated_dir = format!("{}/src/resources/generated/", current_dir.display());
-            let ast_dir = format!("{}/src/resources/generated/ast/", current_dir.display());
+        pub fn add_node(&mut self, label: String) -> u64 {
+            self.increase_node_count();
+            self.stmts.push(DotStmt::Node(Node::new(
+                Self::node_id(self.node_count),
+                vec![Attribute(
+                    Id::Plain(String::from("label")),
+                    Id::Escaped(escape_label(&label)),
+                )],
+            )));
+            self.node_count
+        }
 
-            if !std::path::Path::new(&generated_dir).exists() {
-                match std::fs::create_dir(&generated_dir) {
-                    Ok(_) => debug!("Successfully created generated directory"),
-                    Err(why) => panic!("couldn't create generated directory: {}", why),
-                }
-            }
+        pub fn add_edge(&mut self, from: u64, to: u64) {
+            self.stmts.push(DotStmt::Edge(Edge {
+                ty: EdgeTy::Pair(Vertex::N(Self::node_id(from)), Vertex::N(Self::node_id(to))),
+                attributes: Vec::new(),
+            }));
+        }
+    }
 
-            if !std::path::Path::new(&ast_dir).exists() {
-                match std::fs::create_dir(&ast_dir) {
-                    Ok(_) => debug!("Successfully created ast directory"),
-                    Err(why) => panic!("couldn't create ast directory: {}", why),
-                }
-            }
-            format!("{}/src/resources/generated/ast/", current_dir.display())
-        }
-
-        pub fn generate_image(&self) {
-            let path = self.path_to_generated();
-            let dot_path = path.clone() + &self.graph_name + ".dot";
-            let output_path = path + &self.graph_name + ".png";
-            let output = Command::new("dot")
-                .arg("-Tpng")
-                .arg(dot_path)
-                .arg("-o")
-                .arg(output_path)
-                .output()
-                .expect("failed to execute process");
-            debug!("Output: {}", String::from_utf8_lossy(&output.stdout));
+    impl Default for StmtGraphvizPrinter {
+        fn default() -> Self {
+            Self::new()
         }
     }
 
@@ -306,6 +543,13 @@ pub mod stmt {
             token_node_id
         }
 
+        fn visit_yield_stmt(&mut self, keyword: &Token, expr: &Expr) -> u64 {
+            let expr_node_id = expr.accept(self);
+            let token_node_id = self.add_node(keyword.token_type_value());
+            self.add_edge(token_node_id, expr_node_id);
+            token_node_id
+        }
+
         fn visit_var_stmt(&mut self, token: &Token, expr: &Expr) -> u64 {
             let expr_node_id = expr.accept(self);
             let token_node_id = self.add_node(token.token_type_value());
@@ -313,6 +557,13 @@ pub mod stmt {
             token_node_id
         }
 
+        fn visit_const_stmt(&mut self, token: &Token, expr: &Expr) -> u64 {
+            let expr_node_id = expr.accept(self);
+            let token_node_id = self.add_node(format!("const {}", token.token_type_value()));
+            self.add_edge(token_node_id, expr_node_id);
+            token_node_id
+        }
+
         fn visit_block_stmt(&mut self, stmts: &Vec<Stmt>) -> u64 {
             let stmts_node_id = self.add_node(String::from("Block"));
             for stmt in stmts {
@@ -326,11 +577,16 @@ pub mod stmt {
             &mut self,
             name: &Token,
             _superclass: &Option<Expr>,
+            traits: &Vec<Expr>,
             methods: &Vec<Stmt>,
         ) -> u64 {
             let class_node_id = self.add_node(String::from("class"));
             let name_node_id = self.add_node(name.token_type_value());
             self.add_edge(class_node_id, name_node_id);
+            for used_trait in traits {
+                let trait_node_id = self.add_node(format!("with {}", used_trait));
+                self.add_edge(class_node_id, trait_node_id);
+            }
             for method in methods {
                 let method_node_id = method.accept(self);
                 self.add_edge(class_node_id, method_node_id);
@@ -338,18 +594,39 @@ pub mod stmt {
             class_node_id
         }
 
+        fn visit_trait_stmt(&mut self, name: &Token, methods: &Vec<Stmt>) -> u64 {
+            let trait_node_id = self.add_node(String::from("trait"));
+            let name_node_id = self.add_node(name.token_type_value());
+            self.add_edge(trait_node_id, name_node_id);
+            for method in methods {
+                let method_node_id = method.accept(self);
+                self.add_edge(trait_node_id, method_node_id);
+            }
+            trait_node_id
+        }
+
         fn visit_function_stmt(
             &mut self,
             name: &Token,
             params: &Vec<Token>,
             body: &Vec<Stmt>,
+            optional: &OptionalParams,
         ) -> u64 {
             let function_node_id = self.add_node(String::from("function"));
             let name_node_id = self.add_node(name.token_type_value());
             self.add_edge(function_node_id, name_node_id);
-            for param in params {
-                let param_node_id = self.add_node(param.token_type_value());
+            for (idx, param) in params.iter().enumerate() {
+                let label = if optional.rest && idx + 1 == params.len() {
+                    format!("...{}", param.token_type_value())
+                } else {
+                    param.token_type_value()
+                };
+                let param_node_id = self.add_node(label);
                 self.add_edge(function_node_id, param_node_id);
+                if let Some(Some(default)) = optional.defaults.get(idx) {
+                    let default_node_id = default.accept(self);
+                    self.add_edge(param_node_id, default_node_id);
+                }
             }
             for stmt in body {
                 let stmt_node_id = stmt.accept(self);
@@ -384,6 +661,40 @@ pub mod stmt {
             self.add_edge(while_node_id, stmt_node_id);
             while_node_id
         }
+
+        fn visit_match_stmt(
+            &mut self,
+            _keyword: &Token,
+            subject: &Expr,
+            cases: &Vec<MatchCase>,
+            default: &Option<Box<Stmt>>,
+        ) -> u64 {
+            let subject_node_id = subject.accept(self);
+            let match_node_id = self.add_node(String::from("match"));
+            self.add_edge(match_node_id, subject_node_id);
+            for case in cases {
+                let patterns: Vec<String> = case
+                    .patterns
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect();
+                let case_node_id = self.add_node(format!("case {}", patterns.join(", ")));
+                self.add_edge(match_node_id, case_node_id);
+                if let Some(guard) = &case.guard {
+                    let guard_node_id = guard.accept(self);
+                    self.add_edge(case_node_id, guard_node_id);
+                }
+                let body_node_id = case.body.accept(self);
+                self.add_edge(case_node_id, body_node_id);
+            }
+            if let Some(default) = default {
+                let default_node_id = self.add_node(String::from("default"));
+                let body_node_id = default.accept(self);
+                self.add_edge(match_node_id, default_node_id);
+                self.add_edge(default_node_id, body_node_id);
+            }
+            match_node_id
+        }
     }
 
     impl Visitor<u64> for StmtGraphvizPrinter {
@@ -434,11 +745,24 @@ pub mod stmt {
             operator_node_index
         }
 
-        fn visit_call_expr(&mut self, callee: &Expr, _paren: &Token, arguments: &Vec<Expr>) -> u64 {
+        fn visit_call_expr(
+            &mut self,
+            callee: &Expr,
+            _paren: &Token,
+            arguments: &Vec<Expr>,
+            names: &Vec<Option<Token>>,
+        ) -> u64 {
             let callee_node_index = callee.accept(self);
-            for argument in arguments {
+            for (argument, name) in arguments.iter().zip(names) {
                 let argument_node_index = argument.accept(self);
-                self.add_edge(callee_node_index, argument_node_index);
+                match name {
+                    Some(name) => {
+                        let name_id = self.add_node(name.token_type_value());
+                        self.add_edge(callee_node_index, name_id);
+                        self.add_edge(name_id, argument_node_index);
+                    }
+                    None => self.add_edge(callee_node_index, argument_node_index),
+                }
             }
             callee_node_index
         }
@@ -470,5 +794,106 @@ pub mod stmt {
             self.add_edge(super_idx, super_method_idx);
             super_idx
         }
+
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+            _postfix: bool,
+        ) -> u64 {
+            let target_node_index = target.accept(self);
+            let value_node_index = value.accept(self);
+            let operator_node_index = self.add_node(operator.token_type_value());
+            self.add_edge(operator_node_index, target_node_index);
+            self.add_edge(operator_node_index, value_node_index);
+            operator_node_index
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            _question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> u64 {
+            let condition_node_index = condition.accept(self);
+            let then_node_index = then_expr.accept(self);
+            let else_node_index = else_expr.accept(self);
+            let conditional_node_index = self.add_node(String::from("(Conditional)"));
+            self.add_edge(conditional_node_index, condition_node_index);
+            self.add_edge(conditional_node_index, then_node_index);
+            self.add_edge(conditional_node_index, else_node_index);
+            conditional_node_index
+        }
+
+        fn visit_optional_get_expr(&mut self, object: &Expr, name: &Token) -> u64 {
+            let object_node_index = object.accept(self);
+            let name_id = self.add_node(format!("?.{}", name.token_type_value()));
+            self.add_edge(object_node_index, name_id);
+
+            object_node_index
+        }
+
+        fn visit_index_expr(&mut self, object: &Expr, _bracket: &Token, index: &Expr) -> u64 {
+            let object_node_index = object.accept(self);
+            let index_node_index = index.accept(self);
+            let index_id = self.add_node(String::from("[]"));
+            self.add_edge(object_node_index, index_id);
+            self.add_edge(index_id, index_node_index);
+
+            object_node_index
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::parser::parser::Parser;
+        use crate::scanner::scan::Scanner;
+
+        #[test]
+        fn escape_label_ok() {
+            assert_eq!(escape_label("say \"hi\""), "\"say \\\"hi\\\"\"");
+            assert_eq!(escape_label("a\\b\nc"), "\"a\\\\b\\nc\"");
+        }
+
+        #[test]
+        fn one_graph_with_statement_subgraphs_ok() {
+            let source = "var a = \"back\\slash\";\nprint \"two\nlines\";";
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            let dot = StmtGraphvizPrinter::to_dot(&ast);
+
+            assert!(dot.contains("subgraph cluster_0"));
+            assert!(dot.contains("subgraph cluster_1"));
+            assert!(dot.contains("\"statement 1 (line 2)\""));
+            assert!(graphviz_rust::parse(&dot).is_ok());
+        }
+
+        #[test]
+        fn render_statement_clusters_ok() {
+            let source = "var a = \"say \\\"hi\\\"\";\nprint a + 1;";
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            let dot = StmtGraphvizPrinter::to_dot(&ast);
+            let svg = match StmtGraphvizPrinter::render(&dot, GraphFormat::Svg) {
+                Ok(svg) => String::from_utf8(svg).unwrap(),
+                // without graphviz installed, only the error can be checked.
+                Err(why) => {
+                    assert_eq!(why.kind(), std::io::ErrorKind::NotFound);
+                    assert!(why.to_string().contains("`dot` executable not found"));
+                    return;
+                }
+            };
+
+            assert!(svg.contains("class=\"cluster\""));
+            assert!(svg.contains("<title>cluster_0</title>"));
+            assert!(svg.contains("<title>cluster_1</title>"));
+            assert!(svg.contains("statement 1 (line 2)"));
+            assert!(svg.contains("say &quot;hi&quot;"));
+        }
     }
 }
@@ -0,0 +1,139 @@
+/*!
+ * Natives connecting a program to the process running it: its arguments, standard input,
+ * environment variables and exit code.
+ */
+pub mod system {
+    use crate::interpreter::interpreter::{Error, Interpreter};
+    use crate::rlox_callable::rlox_callable::{Arity, RLoxCallable};
+    use crate::stmt::stmt::LiteralValue;
+    use std::io::{self, BufRead, Read};
+    use std::{cell::RefCell, env, fmt, rc::Rc};
+
+    /// What the program sees of its process, embedders replace the input or allow the environment.
+    pub struct System {
+        /// Arguments given to the script, without the interpreter's own.
+        pub args: Vec<String>,
+        pub input: Box<dyn BufRead>,
+        /// Whether `getenv` may read environment variables.
+        pub allow_env: bool,
+    }
+
+    impl Default for System {
+        fn default() -> Self {
+            Self {
+                args: Vec::new(),
+                input: Box::new(io::BufReader::new(io::stdin())),
+                allow_env: false,
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum SystemNative {
+        Args,
+        ReadLine,
+        ReadAll,
+        Getenv,
+        Exit,
+    }
+
+    impl fmt::Display for SystemNative {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let name = match self {
+                SystemNative::Args => "args",
+                SystemNative::ReadLine => "readLine",
+                SystemNative::ReadAll => "readAll",
+                SystemNative::Getenv => "getenv",
+                SystemNative::Exit => "exit",
+            };
+            write!(f, "{}", name)
+        }
+    }
+
+    impl SystemNative {
+        pub const ALL: [SystemNative; 5] = [
+            SystemNative::Args,
+            SystemNative::ReadLine,
+            SystemNative::ReadAll,
+            SystemNative::Getenv,
+            SystemNative::Exit,
+        ];
+
+        fn input_error(&self, interpreter: &Interpreter, why: io::Error) -> Error {
+            interpreter.native_error(format!(
+                "{}() could not read the input: {}.",
+                self,
+                why.kind()
+            ))
+        }
+    }
+
+    impl RLoxCallable for SystemNative {
+        fn arity(&self) -> Arity {
+            match self {
+                SystemNative::Getenv | SystemNative::Exit => Arity::exact(1),
+                _ => Arity::exact(0),
+            }
+        }
+
+        fn call(
+            &self,
+            interpreter: &mut Interpreter,
+            args: &mut Vec<Rc<LiteralValue>>,
+        ) -> Result<Rc<LiteralValue>, Error> {
+            match self {
+                SystemNative::Args => Ok(Rc::new(LiteralValue::List(Rc::new(RefCell::new(
+                    interpreter
+                        .system
+                        .args
+                        .iter()
+                        .map(|arg| Rc::new(LiteralValue::String(arg.clone())))
+                        .collect(),
+                ))))),
+                SystemNative::ReadLine => {
+                    let mut line = String::new();
+                    match interpreter.system.input.read_line(&mut line) {
+                        Ok(0) => Ok(Rc::new(LiteralValue::Nil)),
+                        Ok(_) => {
+                            let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+                            let trimmed = trimmed.strip_suffix('\r').unwrap_or(trimmed);
+                            Ok(Rc::new(LiteralValue::String(trimmed.to_string())))
+                        }
+                        Err(why) => Err(self.input_error(interpreter, why)),
+                    }
+                }
+                SystemNative::ReadAll => {
+                    let mut all = String::new();
+                    match interpreter.system.input.read_to_string(&mut all) {
+                        Ok(_) => Ok(Rc::new(LiteralValue::String(all))),
+                        Err(why) => Err(self.input_error(interpreter, why)),
+                    }
+                }
+                SystemNative::Getenv => {
+                    let LiteralValue::String(name) = &*args[0] else {
+                        return Err(interpreter
+                            .native_error(String::from("getenv() takes a variable name.")));
+                    };
+                    if !interpreter.system.allow_env {
+                        return Err(interpreter
+                            .native_error(String::from("Environment access is not allowed.")));
+                    }
+                    Ok(Rc::new(match env::var(name) {
+                        Ok(value) => LiteralValue::String(value),
+                        Err(_) => LiteralValue::Nil,
+                    }))
+                }
+                SystemNative::Exit => match &*args[0] {
+                    LiteralValue::Number(code)
+                        if code.fract() == 0.0 && *code >= 0.0 && *code <= 255.0 =>
+                    {
+                        Err(Error::Exit(*code as i32))
+                    }
+                    _ => Err(interpreter.native_error(String::from(
+                        "exit() takes an exit code between 0 and 255.",
+                    ))),
+                },
+            }
+        }
+    }
+}
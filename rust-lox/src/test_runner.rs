@@ -0,0 +1,559 @@
+pub mod test_runner {
+    use crate::error_handling::error_handling::{
+        captured_error_count, finish_capture, start_capture, EXIT_COMPILE_ERROR, EXIT_RUNTIME_ERROR,
+    };
+    use crate::interpreter::interpreter::{Error, Interpreter, INTERPRETER_STACK_SIZE};
+    use crate::parser::parser::Parser;
+    use crate::resolver::resolver::Resolver;
+    use crate::scanner::scan::Scanner;
+    use nom::{
+        bytes::complete::{tag, take_until},
+        character::complete::char,
+        combinator::rest,
+        sequence::delimited,
+        IResult,
+    };
+    use std::collections::VecDeque;
+    use std::fmt;
+    use std::io::{self, Read};
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    pub const EXPECT_PATTERN: &str = "// expect:";
+    pub const PARSER_ERROR_PATTERN: &str = "// Error at ";
+    pub const RUNTIME_ERROR_PATTERN: &str = "// expect runtime error:";
+
+    // extra time given to a run after its deadline before it is abandoned.
+    const TIMEOUT_GRACE: Duration = Duration::from_millis(500);
+
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Expectation {
+        Output(String),
+        CompileError { token: String, message: String },
+        RuntimeError(String),
+    }
+
+    impl Expectation {
+        fn matches_error(&self, line: &str) -> bool {
+            match self {
+                Expectation::Output(_) => false,
+                Expectation::CompileError { token, message } => {
+                    line.contains(token) && line.contains(message)
+                }
+                Expectation::RuntimeError(message) => line.contains(message),
+            }
+        }
+    }
+
+    impl fmt::Display for Expectation {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Expectation::Output(literal) => write!(f, "{}", literal),
+                Expectation::CompileError { token, message } => {
+                    write!(f, "Error at '{}': {}", token, message)
+                }
+                Expectation::RuntimeError(message) => write!(f, "{}", message),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    pub enum Outcome {
+        Success,
+        CompileError,
+        RuntimeError,
+        Exit(i32),
+        Timeout,
+        Crash,
+    }
+
+    impl Outcome {
+        pub fn from_exit_code(code: i32) -> Self {
+            match code {
+                0 => Outcome::Success,
+                EXIT_COMPILE_ERROR => Outcome::CompileError,
+                EXIT_RUNTIME_ERROR => Outcome::RuntimeError,
+                _ => Outcome::Exit(code),
+            }
+        }
+    }
+
+    impl fmt::Display for Outcome {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Outcome::Success => write!(f, "success (exit code 0)"),
+                Outcome::CompileError => {
+                    write!(f, "compile error (exit code {})", EXIT_COMPILE_ERROR)
+                }
+                Outcome::RuntimeError => {
+                    write!(f, "runtime error (exit code {})", EXIT_RUNTIME_ERROR)
+                }
+                Outcome::Exit(code) => write!(f, "exit code {}", code),
+                Outcome::Timeout => write!(f, "timeout"),
+                Outcome::Crash => write!(f, "crash"),
+            }
+        }
+    }
+
+    /// What a single run of a Lox program produced.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct RunResult {
+        pub outcome: Outcome,
+        pub output: Vec<String>,
+        pub errors: Vec<String>,
+    }
+
+    impl RunResult {
+        fn without_output(outcome: Outcome) -> Self {
+            Self {
+                outcome,
+                output: Vec::new(),
+                errors: Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TestOptions {
+        /// Number of test files run in parallel.
+        pub jobs: usize,
+        /// Time limit for a single test file.
+        pub timeout: Duration,
+        /// External interpreter command, the test file path is appended to it.
+        pub interpreter: Option<String>,
+    }
+
+    impl Default for TestOptions {
+        fn default() -> Self {
+            Self {
+                jobs: thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+                timeout: Duration::from_secs(10),
+                interpreter: None,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TestReport {
+        pub path: PathBuf,
+        pub outcome: Outcome,
+        pub failures: Vec<String>,
+    }
+
+    impl TestReport {
+        pub fn passed(&self) -> bool {
+            self.failures.is_empty()
+        }
+    }
+
+    fn parse_expect_pattern(input: &str) -> IResult<&str, Expectation> {
+        let (input, _) = take_until(EXPECT_PATTERN)(input)?;
+        let (input, _) = tag(EXPECT_PATTERN)(input)?;
+        let (input, expected_literal) = rest(input)?;
+        Ok((
+            input,
+            Expectation::Output(expected_literal.trim().to_string()),
+        ))
+    }
+
+    fn parse_parser_error_pattern(input: &str) -> IResult<&str, Expectation> {
+        let (input, _) = take_until(PARSER_ERROR_PATTERN)(input)?;
+        let (input, _) = tag(PARSER_ERROR_PATTERN)(input)?;
+        let (input, location) = delimited(char('\''), take_until("'"), char('\''))(input)?;
+        let (input, _) = tag(": ")(input)?;
+        let (input, error_message) = rest(input)?;
+        Ok((
+            input,
+            Expectation::CompileError {
+                token: location.trim().to_string(),
+                message: error_message.trim().to_string(),
+            },
+        ))
+    }
+
+    fn parse_runtime_error_pattern(input: &str) -> IResult<&str, Expectation> {
+        let (input, _) = take_until(RUNTIME_ERROR_PATTERN)(input)?;
+        let (input, _) = tag(RUNTIME_ERROR_PATTERN)(input)?;
+        let (input, result) = rest(input)?;
+        Ok((input, Expectation::RuntimeError(result.trim().to_string())))
+    }
+
+    /// Collects the `// expect:`, `// Error at` and `// expect runtime error:` annotations.
+    pub fn parse_expectations(source: &str) -> Vec<Expectation> {
+        let mut expectations = Vec::new();
+        for line in source.lines() {
+            if let Ok((_, expect)) = parse_expect_pattern(line) {
+                expectations.push(expect);
+            } else if let Ok((_, parse_err)) = parse_parser_error_pattern(line) {
+                expectations.push(parse_err);
+            } else if let Ok((_, runtime_err)) = parse_runtime_error_pattern(line) {
+                expectations.push(runtime_err);
+            }
+        }
+        expectations
+    }
+
+    fn interpret_source(source: String, deadline: Instant) -> Outcome {
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens();
+
+        let mut parser = Parser::new(tokens);
+        let ast = match parser.parse() {
+            Ok(ast) if !scanner.had_error() => ast,
+            _ => return Outcome::CompileError,
+        };
+
+        let mut interpreter = Interpreter::new();
+        interpreter.deadline = Some(deadline);
+        // test files never wait for input.
+        interpreter.system.input = Box::new(io::empty());
+        let mut resolver = Resolver::new(&mut interpreter);
+        // the parser and resolver report some errors without giving up.
+        if resolver.resolve(&ast).is_err() || captured_error_count() > 0 {
+            return Outcome::CompileError;
+        }
+
+        match resolver.interpreter.interpret(&ast) {
+            Ok(_) => Outcome::Success,
+            Err(Error::Exit(code)) => Outcome::from_exit_code(code),
+            Err(_) if Instant::now() >= deadline => Outcome::Timeout,
+            Err(_) => Outcome::RuntimeError,
+        }
+    }
+
+    /// Interprets `source` on its own thread, capturing its output instead of printing it.
+    pub fn run_in_process(source: String, timeout: Duration) -> RunResult {
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new()
+            .stack_size(INTERPRETER_STACK_SIZE)
+            .spawn(move || {
+                start_capture();
+                let outcome = interpret_source(source, Instant::now() + timeout);
+                let captured = finish_capture();
+                let _ = sender.send(RunResult {
+                    outcome,
+                    output: captured.output,
+                    errors: captured.errors,
+                });
+            });
+        if spawned.is_err() {
+            return RunResult::without_output(Outcome::Crash);
+        }
+
+        match receiver.recv_timeout(timeout + TIMEOUT_GRACE) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => RunResult::without_output(Outcome::Timeout),
+            // the interpreter thread panicked.
+            Err(RecvTimeoutError::Disconnected) => RunResult::without_output(Outcome::Crash),
+        }
+    }
+
+    fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> thread::JoinHandle<String> {
+        thread::spawn(move || {
+            let mut content = String::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_string(&mut content);
+            }
+            content
+        })
+    }
+
+    /// Runs `path` through an external interpreter, stdout is the output and stderr the errors.
+    pub fn run_external(command: &str, path: &Path, timeout: Duration) -> RunResult {
+        let mut parts = command.split_whitespace();
+        let program = match parts.next() {
+            Some(program) => program,
+            None => return RunResult::without_output(Outcome::Crash),
+        };
+
+        let mut child = match Command::new(program)
+            .args(parts)
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(_) => return RunResult::without_output(Outcome::Crash),
+        };
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+
+        let deadline = Instant::now() + timeout;
+        let outcome = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => match status.code() {
+                    Some(code) => break Outcome::from_exit_code(code),
+                    // killed by a signal.
+                    None => break Outcome::Crash,
+                },
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break Outcome::Timeout;
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(5)),
+                Err(_) => break Outcome::Crash,
+            }
+        };
+
+        let lines = |content: String| content.lines().map(String::from).collect();
+        RunResult {
+            outcome,
+            output: lines(stdout.join().unwrap_or_default()),
+            errors: lines(stderr.join().unwrap_or_default()),
+        }
+    }
+
+    fn expected_outcome(expectations: &[Expectation]) -> Outcome {
+        let mut outcome = Outcome::Success;
+        for expectation in expectations {
+            match expectation {
+                Expectation::CompileError { .. } => return Outcome::CompileError,
+                Expectation::RuntimeError(_) => outcome = Outcome::RuntimeError,
+                Expectation::Output(_) => (),
+            }
+        }
+        outcome
+    }
+
+    fn diff_lines(expected: &[String], actual: &[String]) -> Vec<String> {
+        let mut diff = Vec::new();
+        for idx in 0..expected.len().max(actual.len()) {
+            match (expected.get(idx), actual.get(idx)) {
+                (Some(expected), Some(actual)) if expected == actual => {
+                    diff.push(format!("    {}", expected))
+                }
+                (expected, actual) => {
+                    if let Some(expected) = expected {
+                        diff.push(format!("  - {}", expected));
+                    }
+                    if let Some(actual) = actual {
+                        diff.push(format!("  + {}", actual));
+                    }
+                }
+            }
+        }
+        diff
+    }
+
+    /// Compares a run against the annotations, returns a description of every mismatch.
+    pub fn check(expectations: &[Expectation], result: &RunResult) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        let expected_outcome = expected_outcome(expectations);
+        if result.outcome != expected_outcome {
+            failures.push(format!(
+                "Expected {} but got {}.",
+                expected_outcome, result.outcome
+            ));
+        }
+
+        let expected_output: Vec<String> = expectations
+            .iter()
+            .filter_map(|expectation| match expectation {
+                Expectation::Output(literal) => Some(literal.clone()),
+                _ => None,
+            })
+            .collect();
+        if expected_output != result.output {
+            failures.push(String::from("Output differs (- expected, + actual):"));
+            failures.extend(diff_lines(&expected_output, &result.output));
+        }
+
+        // every expected error has to show up in order, interpreters may add context lines.
+        let expected_errors: Vec<&Expectation> = expectations
+            .iter()
+            .filter(|expectation| !matches!(expectation, Expectation::Output(_)))
+            .collect();
+        let mut actual_errors = result.errors.iter();
+        for expected in &expected_errors {
+            if !actual_errors.any(|line| expected.matches_error(line)) {
+                failures.push(format!("Missing expected error: {}", expected));
+            }
+        }
+        if expected_errors.is_empty() && !result.errors.is_empty() {
+            failures.push(String::from("Unexpected errors:"));
+            for error in &result.errors {
+                failures.push(format!("  + {}", error));
+            }
+        }
+
+        failures
+    }
+
+    pub fn run_test_file(path: &Path, options: &TestOptions) -> TestReport {
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(why) => {
+                return TestReport {
+                    path: path.to_path_buf(),
+                    outcome: Outcome::Crash,
+                    failures: vec![format!("Could not read test file: {}", why)],
+                }
+            }
+        };
+
+        let expectations = parse_expectations(&source);
+        let result = match &options.interpreter {
+            Some(command) => run_external(command, path, options.timeout),
+            None => run_in_process(source, options.timeout),
+        };
+
+        TestReport {
+            path: path.to_path_buf(),
+            outcome: result.outcome,
+            failures: check(&expectations, &result),
+        }
+    }
+
+    /// All `.lox` files under `path`(or `path` itself), sorted.
+    pub fn collect_test_files(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+        if path.is_file() {
+            return Ok(vec![path.to_path_buf()]);
+        }
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let entry_path = entry?.path();
+            if entry_path.is_dir() {
+                files.extend(collect_test_files(&entry_path)?);
+            } else if entry_path.extension().is_some_and(|ext| ext == "lox") {
+                files.push(entry_path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Runs every test file in parallel and prints a summary, returns whether all passed.
+    pub fn run_tests(path: &str, options: &TestOptions) -> bool {
+        let files = match collect_test_files(Path::new(path)) {
+            Ok(files) => files,
+            Err(why) => {
+                eprintln!("Could not collect tests from {}: {}", path, why);
+                return false;
+            }
+        };
+
+        let queue = Arc::new(Mutex::new(files.into_iter().collect::<VecDeque<PathBuf>>()));
+        let (sender, receiver) = mpsc::channel();
+        let mut workers = Vec::new();
+        for _ in 0..options.jobs.max(1) {
+            let queue = Arc::clone(&queue);
+            let sender = sender.clone();
+            let options = options.clone();
+            workers.push(thread::spawn(move || loop {
+                let next = queue.lock().map(|mut queue| queue.pop_front());
+                match next {
+                    Ok(Some(file)) => {
+                        let _ = sender.send(run_test_file(&file, &options));
+                    }
+                    _ => break,
+                }
+            }));
+        }
+        drop(sender);
+
+        let mut reports: Vec<TestReport> = receiver.iter().collect();
+        for worker in workers {
+            let _ = worker.join();
+        }
+        reports.sort_by(|left, right| left.path.cmp(&right.path));
+
+        let mut passed = 0;
+        let mut timed_out = 0;
+        for report in &reports {
+            if report.passed() {
+                passed += 1;
+                println!("PASS {}", report.path.display());
+                continue;
+            }
+
+            if report.outcome == Outcome::Timeout {
+                timed_out += 1;
+            }
+            println!("FAIL {}", report.path.display());
+            for failure in &report.failures {
+                println!("    {}", failure);
+            }
+        }
+
+        let failed = reports.len() - passed;
+        println!(
+            "\n{} tests: {} passed, {} failed ({} timed out).",
+            reports.len(),
+            passed,
+            failed,
+            timed_out
+        );
+        failed == 0
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        #[test]
+        fn parse_expectations_ok() {
+            let expectations = parse_expectations(
+                "print 1; // expect: 1\nvar a = ; // Error at ';': Expect expression.\nnil(); // expect runtime error: Can only call functions and classes",
+            );
+            assert_eq!(
+                expectations,
+                vec![
+                    Expectation::Output(String::from("1")),
+                    Expectation::CompileError {
+                        token: String::from(";"),
+                        message: String::from("Expect expression."),
+                    },
+                    Expectation::RuntimeError(String::from("Can only call functions and classes")),
+                ]
+            );
+        }
+
+        #[test]
+        fn run_in_process_captures_output_ok() {
+            let result = run_in_process(
+                String::from("print 1 + 2; print \"a\";"),
+                Duration::from_secs(5),
+            );
+            assert_eq!(result.outcome, Outcome::Success);
+            assert_eq!(result.output, vec!["3", "a"]);
+            assert!(result.errors.is_empty());
+        }
+
+        #[test]
+        fn run_in_process_runtime_error_ok() {
+            let source = String::from("print 1;\n-\"a\"; // expect runtime error: Operand must be a number.\n// expect: 1");
+            let result = run_in_process(source.clone(), Duration::from_secs(5));
+            assert_eq!(result.outcome, Outcome::RuntimeError);
+            assert!(check(&parse_expectations(&source), &result).is_empty());
+        }
+
+        #[test]
+        fn run_in_process_timeout_ok() {
+            let result = run_in_process(String::from("while (true) {}"), Duration::from_millis(50));
+            assert_eq!(result.outcome, Outcome::Timeout);
+        }
+
+        #[test]
+        fn check_reports_output_diff_nok() {
+            let result = RunResult {
+                outcome: Outcome::Success,
+                output: vec![String::from("2")],
+                errors: Vec::new(),
+            };
+            let failures = check(&[Expectation::Output(String::from("1"))], &result);
+            assert_eq!(
+                failures,
+                vec!["Output differs (- expected, + actual):", "  - 1", "  + 2"]
+            );
+        }
+    }
+}
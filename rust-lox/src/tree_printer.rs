@@ -0,0 +1,531 @@
+pub mod tree_printer {
+    use crate::expr::expr::{Expr, Visitor};
+    use crate::interpreter::interpreter::Interpreter;
+    use crate::scanner::scan::Token;
+    use crate::stmt::stmt::{LiteralValue, MatchCase, OptionalParams, Stmt, StmtVisitor};
+    use colored::{ColoredString, Colorize};
+    use rustc_hash::FxHashMap as HashMap;
+
+    pub struct TreeNode {
+        pub label: String,
+        pub children: Vec<TreeNode>,
+    }
+
+    impl TreeNode {
+        fn new(label: String, children: Vec<TreeNode>) -> Self {
+            Self { label, children }
+        }
+
+        fn leaf(label: String) -> Self {
+            Self::new(label, Vec::new())
+        }
+
+        /// Node without meaning of its own which groups children, e.g. the condition of an if.
+        fn group(name: &str, children: Vec<TreeNode>) -> Self {
+            Self::new(name.dimmed().italic().to_string(), children)
+        }
+
+        fn render(&self, prefix: &str, is_last: bool, tree: &mut String) {
+            let connector = if is_last { "└── " } else { "├── " };
+            tree.push_str(&format!("{}{}{}\n", prefix, connector, self.label));
+
+            let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+            self.render_children(&child_prefix, tree);
+        }
+
+        fn render_children(&self, prefix: &str, tree: &mut String) {
+            for (idx, child) in self.children.iter().enumerate() {
+                child.render(prefix, idx + 1 == self.children.len(), tree);
+            }
+        }
+    }
+
+    /**
+     * Renders the AST as a Unicode box-drawing tree for the terminal.
+     * Statements, declarations, operators, literals and names get different colours,
+     * variable references can be annotated with the scope depth the resolver found.
+     */
+    #[derive(Default)]
+    pub struct TreePrinter {
+        scope_depths: Option<HashMap<(u32, u32), usize>>,
+    }
+
+    impl TreePrinter {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Annotates variable references with the depths recorded by the resolver.
+        pub fn with_scope_depths(interpreter: &Interpreter) -> Self {
+            let mut scope_depths = HashMap::default();
+            for (expr, depth) in &interpreter.locals {
+                if let Expr::Variable(token) = expr {
+                    scope_depths.insert((token.get_line(), token.get_column()), *depth);
+                }
+            }
+            Self {
+                scope_depths: Some(scope_depths),
+            }
+        }
+
+        pub fn print(&mut self, ast: &[Stmt]) -> String {
+            let root = TreeNode::new(
+                "Program".bold().to_string(),
+                ast.iter().map(|stmt| stmt.accept(self)).collect(),
+            );
+
+            let mut tree = format!("{}\n", root.label);
+            root.render_children("", &mut tree);
+            tree
+        }
+
+        fn stmt_label(kind: ColoredString, detail: &str, line: Option<u32>) -> String {
+            let mut label = kind.to_string();
+            if !detail.is_empty() {
+                label.push_str(&format!(" {}", detail));
+            }
+            if let Some(line) = line {
+                label.push_str(&format!(" {}", format!("[line {}]", line).dimmed()));
+            }
+            label
+        }
+
+        fn name(token: &Token) -> String {
+            token.token_type_value().cyan().to_string()
+        }
+
+        /// Name with its line and, when enabled, the scope depth it resolves to.
+        fn reference(&self, kind: ColoredString, token: &Token, detail: &str) -> String {
+            let mut label = format!("{} {}", kind, detail);
+            let mut annotation = format!("line {}", token.get_line());
+            if let Some(scope_depths) = &self.scope_depths {
+                match scope_depths.get(&(token.get_line(), token.get_column())) {
+                    Some(depth) => annotation.push_str(&format!(", depth {}", depth)),
+                    None => annotation.push_str(", global"),
+                }
+            }
+            label.push_str(&format!(" {}", format!("[{}]", annotation).dimmed()));
+            label
+        }
+
+        fn block(&mut self, stmts: &[Stmt]) -> Vec<TreeNode> {
+            stmts.iter().map(|stmt| stmt.accept(self)).collect()
+        }
+    }
+
+    impl StmtVisitor<TreeNode> for TreePrinter {
+        fn visit_expr_stmt(&mut self, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("Expression".blue().bold(), "", expr.line()),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_print_stmt(&mut self, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("Print".blue().bold(), "", expr.line()),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_return_stmt(&mut self, keyword: &Token, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("Return".blue().bold(), "", Some(keyword.get_line())),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_yield_stmt(&mut self, keyword: &Token, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("Yield".blue().bold(), "", Some(keyword.get_line())),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_var_stmt(&mut self, token: &Token, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Var".magenta().bold(),
+                    &Self::name(token),
+                    Some(token.get_line()),
+                ),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_const_stmt(&mut self, token: &Token, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Const".magenta().bold(),
+                    &Self::name(token),
+                    Some(token.get_line()),
+                ),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_block_stmt(&mut self, stmts: &Vec<Stmt>) -> TreeNode {
+            let line = stmts.iter().find_map(|stmt| stmt.line());
+            TreeNode::new(
+                Self::stmt_label("Block".blue().bold(), "", line),
+                self.block(stmts),
+            )
+        }
+
+        fn visit_class_stmt(
+            &mut self,
+            name: &Token,
+            superclass: &Option<Expr>,
+            traits: &Vec<Expr>,
+            methods: &Vec<Stmt>,
+        ) -> TreeNode {
+            let mut children = Vec::new();
+            if let Some(superclass) = superclass {
+                children.push(TreeNode::group("superclass", vec![superclass.accept(self)]));
+            }
+            if !traits.is_empty() {
+                let traits = traits.iter().map(|used| used.accept(self)).collect();
+                children.push(TreeNode::group("traits", traits));
+            }
+            children.extend(self.block(methods));
+
+            TreeNode::new(
+                Self::stmt_label(
+                    "Class".magenta().bold(),
+                    &Self::name(name),
+                    Some(name.get_line()),
+                ),
+                children,
+            )
+        }
+
+        fn visit_trait_stmt(&mut self, name: &Token, methods: &Vec<Stmt>) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Trait".magenta().bold(),
+                    &Self::name(name),
+                    Some(name.get_line()),
+                ),
+                self.block(methods),
+            )
+        }
+
+        fn visit_function_stmt(
+            &mut self,
+            name: &Token,
+            params: &Vec<Token>,
+            body: &Vec<Stmt>,
+            optional: &OptionalParams,
+        ) -> TreeNode {
+            let mut children = Vec::new();
+            for (param, default) in params.iter().zip(&optional.defaults) {
+                if let Some(default) = default {
+                    let label = format!("default {}", param.token_type_value());
+                    children.push(TreeNode::group(&label, vec![default.accept(self)]));
+                }
+            }
+            children.extend(self.block(body));
+            let params = params
+                .iter()
+                .enumerate()
+                .map(
+                    |(idx, param)| match optional.rest && idx + 1 == params.len() {
+                        true => format!("...{}", Self::name(param)),
+                        false => Self::name(param),
+                    },
+                )
+                .collect::<Vec<String>>()
+                .join(", ");
+            TreeNode::new(
+                Self::stmt_label(
+                    "Fun".magenta().bold(),
+                    &format!("{}({})", Self::name(name), params),
+                    Some(name.get_line()),
+                ),
+                children,
+            )
+        }
+
+        fn visit_if_stmt(
+            &mut self,
+            expr: &Expr,
+            stmt: &Stmt,
+            else_stmt: &Option<Box<Stmt>>,
+        ) -> TreeNode {
+            let mut children = vec![
+                TreeNode::group("condition", vec![expr.accept(self)]),
+                TreeNode::group("then", vec![stmt.accept(self)]),
+            ];
+            if let Some(else_stmt) = else_stmt {
+                children.push(TreeNode::group("else", vec![else_stmt.accept(self)]));
+            }
+
+            TreeNode::new(
+                Self::stmt_label("If".blue().bold(), "", expr.line()),
+                children,
+            )
+        }
+
+        fn visit_while_stmt(&mut self, expr: &Expr, stmt: &Stmt) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("While".blue().bold(), "", expr.line()),
+                vec![
+                    TreeNode::group("condition", vec![expr.accept(self)]),
+                    TreeNode::group("body", vec![stmt.accept(self)]),
+                ],
+            )
+        }
+
+        fn visit_match_stmt(
+            &mut self,
+            keyword: &Token,
+            subject: &Expr,
+            cases: &Vec<MatchCase>,
+            default: &Option<Box<Stmt>>,
+        ) -> TreeNode {
+            let mut children = vec![TreeNode::group("subject", vec![subject.accept(self)])];
+            for case in cases {
+                let patterns: Vec<String> = case
+                    .patterns
+                    .iter()
+                    .map(|pattern| pattern.to_string())
+                    .collect();
+                let mut case_children = Vec::new();
+                if let Some(guard) = &case.guard {
+                    case_children.push(TreeNode::group("guard", vec![guard.accept(self)]));
+                }
+                case_children.push(TreeNode::group("body", vec![case.body.accept(self)]));
+                children.push(TreeNode::new(
+                    Self::stmt_label(
+                        "Case".blue().bold(),
+                        &patterns.join(", "),
+                        Some(case.keyword.get_line()),
+                    ),
+                    case_children,
+                ));
+            }
+            if let Some(default) = default {
+                children.push(TreeNode::group("default", vec![default.accept(self)]));
+            }
+
+            TreeNode::new(
+                Self::stmt_label("Match".blue().bold(), "", Some(keyword.get_line())),
+                children,
+            )
+        }
+    }
+
+    impl Visitor<TreeNode> for TreePrinter {
+        fn visit_binary_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Binary".yellow(),
+                    &operator.token_type_value(),
+                    Some(operator.get_line()),
+                ),
+                vec![left.accept(self), right.accept(self)],
+            )
+        }
+
+        fn visit_grouping_expr(&mut self, expression: &Expr) -> TreeNode {
+            TreeNode::new(
+                "Grouping".yellow().to_string(),
+                vec![expression.accept(self)],
+            )
+        }
+
+        fn visit_literal_expr(&mut self, value: &LiteralValue) -> TreeNode {
+            let value = match value {
+                LiteralValue::String(string) => format!("{:?}", string),
+                _ => value.to_string(),
+            };
+            TreeNode::leaf(format!("{} {}", "Literal".green(), value.green()))
+        }
+
+        fn visit_unary_expr(&mut self, operator: &Token, right: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Unary".yellow(),
+                    &operator.token_type_value(),
+                    Some(operator.get_line()),
+                ),
+                vec![right.accept(self)],
+            )
+        }
+
+        fn visit_variable_expr(&mut self, token: &Token) -> TreeNode {
+            TreeNode::leaf(self.reference("Variable".cyan(), token, &Self::name(token)))
+        }
+
+        fn visit_assign_expr(&mut self, token: &Token, expr: &Expr) -> TreeNode {
+            TreeNode::new(
+                self.reference("Assign".cyan(), token, &Self::name(token)),
+                vec![expr.accept(self)],
+            )
+        }
+
+        fn visit_logical_expr(&mut self, left: &Expr, operator: &Token, right: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Logical".yellow(),
+                    &operator.token_type_value(),
+                    Some(operator.get_line()),
+                ),
+                vec![left.accept(self), right.accept(self)],
+            )
+        }
+
+        fn visit_call_expr(
+            &mut self,
+            callee: &Expr,
+            paren: &Token,
+            arguments: &Vec<Expr>,
+            names: &Vec<Option<Token>>,
+        ) -> TreeNode {
+            let mut children = vec![TreeNode::group("callee", vec![callee.accept(self)])];
+            if !arguments.is_empty() {
+                let arguments = arguments
+                    .iter()
+                    .zip(names)
+                    .map(|(arg, name)| match name {
+                        Some(name) => TreeNode::group(
+                            &format!("{}:", name.token_type_value()),
+                            vec![arg.accept(self)],
+                        ),
+                        None => arg.accept(self),
+                    })
+                    .collect();
+                children.push(TreeNode::group("arguments", arguments));
+            }
+            TreeNode::new(
+                Self::stmt_label("Call".yellow(), "", Some(paren.get_line())),
+                children,
+            )
+        }
+
+        fn visit_get_expr(&mut self, object: &Expr, name: &Token) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Get".yellow(),
+                    &format!(".{}", Self::name(name)),
+                    Some(name.get_line()),
+                ),
+                vec![object.accept(self)],
+            )
+        }
+
+        fn visit_set_expr(&mut self, object: &Expr, name: &Token, value: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "Set".yellow(),
+                    &format!(".{}", Self::name(name)),
+                    Some(name.get_line()),
+                ),
+                vec![object.accept(self), value.accept(self)],
+            )
+        }
+
+        fn visit_this_expr(&mut self, keyword: &Token) -> TreeNode {
+            TreeNode::leaf(self.reference("This".red(), keyword, ""))
+        }
+
+        fn visit_super_expr(&mut self, keyword: &Token, method: &Token) -> TreeNode {
+            TreeNode::leaf(self.reference(
+                "Super".red(),
+                keyword,
+                &format!(".{}", Self::name(method)),
+            ))
+        }
+
+        fn visit_update_expr(
+            &mut self,
+            target: &Expr,
+            operator: &Token,
+            value: &Expr,
+            postfix: bool,
+        ) -> TreeNode {
+            let operator_name = match postfix {
+                true => format!("{} (postfix)", operator.token_type_value()),
+                false => operator.token_type_value(),
+            };
+            TreeNode::new(
+                Self::stmt_label("Update".yellow(), &operator_name, Some(operator.get_line())),
+                vec![target.accept(self), value.accept(self)],
+            )
+        }
+
+        fn visit_conditional_expr(
+            &mut self,
+            condition: &Expr,
+            question: &Token,
+            then_expr: &Expr,
+            else_expr: &Expr,
+        ) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("Conditional".yellow(), "", Some(question.get_line())),
+                vec![
+                    TreeNode::group("condition", vec![condition.accept(self)]),
+                    TreeNode::group("then", vec![then_expr.accept(self)]),
+                    TreeNode::group("else", vec![else_expr.accept(self)]),
+                ],
+            )
+        }
+
+        fn visit_optional_get_expr(&mut self, object: &Expr, name: &Token) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label(
+                    "OptionalGet".yellow(),
+                    &format!("?.{}", Self::name(name)),
+                    Some(name.get_line()),
+                ),
+                vec![object.accept(self)],
+            )
+        }
+
+        fn visit_index_expr(&mut self, object: &Expr, bracket: &Token, index: &Expr) -> TreeNode {
+            TreeNode::new(
+                Self::stmt_label("Index".yellow(), "", Some(bracket.get_line())),
+                vec![
+                    object.accept(self),
+                    TreeNode::group("index", vec![index.accept(self)]),
+                ],
+            )
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        use crate::parser::parser::Parser;
+        use crate::resolver::resolver::Resolver;
+        use crate::scanner::scan::Scanner;
+
+        #[test]
+        fn render_tree_with_scope_depths_ok() {
+            colored::control::set_override(false);
+            let source = "var a = 1;\n{\n  var b = a + 2;\n  print b;\n}";
+            let ast = Parser::new(Scanner::new(source.to_string()).scan_tokens())
+                .parse()
+                .unwrap();
+            let mut interpreter = Interpreter::new();
+            Resolver::new(&mut interpreter).resolve(&ast).unwrap();
+
+            let tree = TreePrinter::with_scope_depths(&interpreter).print(&ast);
+            assert_eq!(
+                tree,
+                "\
+Program
+├── Var a [line 1]
+│   └── Literal 1
+└── Block [line 3]
+    ├── Var b [line 3]
+    │   └── Binary + [line 3]
+    │       ├── Variable a [line 3, depth 1]
+    │       └── Literal 2
+    └── Print [line 4]
+        └── Variable b [line 4, depth 0]
+"
+            );
+        }
+    }
+}
@@ -10,6 +10,26 @@ mod assignment_tests {
         &format!("{}{}", ASSIGNMENT_PREFIX, "/associativity.lox")
     );
 
+    generate_integration_test!(
+        test_compound_invalid_target,
+        &format!("{}{}", ASSIGNMENT_PREFIX, "/compound_invalid_target.lox")
+    );
+
+    generate_integration_test!(
+        test_compound_nonnum,
+        &format!("{}{}", ASSIGNMENT_PREFIX, "/compound_nonnum.lox")
+    );
+
+    generate_integration_test!(
+        test_compound_property,
+        &format!("{}{}", ASSIGNMENT_PREFIX, "/compound_property.lox")
+    );
+
+    generate_integration_test!(
+        test_compound,
+        &format!("{}{}", ASSIGNMENT_PREFIX, "/compound.lox")
+    );
+
     generate_integration_test!(
         test_global,
         &format!("{}{}", ASSIGNMENT_PREFIX, "/global.lox")
@@ -25,6 +45,16 @@ mod assignment_tests {
         &format!("{}{}", ASSIGNMENT_PREFIX, "/infix_operator.lox")
     );
 
+    generate_integration_test!(
+        test_increment_invalid_target,
+        &format!("{}{}", ASSIGNMENT_PREFIX, "/increment_invalid_target.lox")
+    );
+
+    generate_integration_test!(
+        test_increment,
+        &format!("{}{}", ASSIGNMENT_PREFIX, "/increment.lox")
+    );
+
     generate_integration_test!(
         test_local,
         &format!("{}{}", ASSIGNMENT_PREFIX, "/local.lox")
@@ -94,11 +124,86 @@ mod comment_tests {
         &format!("{}{}", COMMENT_PREFIX, "/only_line_comment.lox")
     );
 
-    // TODO: allow unicode comments
-    // generate_integration_test!(
-    //     test_unicode,
-    //     &format!("{}{}", COMMENT_PREFIX, "/unicode.lox")
-    // );
+    generate_integration_test!(
+        test_unicode,
+        &format!("{}{}", COMMENT_PREFIX, "/unicode.lox")
+    );
+}
+
+mod conditional_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const CONDITIONAL_PREFIX: &str = "tests/resources/integration_tests/conditional";
+
+    generate_integration_test!(
+        test_coalesce,
+        &format!("{}{}", CONDITIONAL_PREFIX, "/coalesce.lox")
+    );
+
+    generate_integration_test!(
+        test_optional_get_on_number,
+        &format!("{}{}", CONDITIONAL_PREFIX, "/optional_get_on_number.lox")
+    );
+
+    generate_integration_test!(
+        test_optional_get,
+        &format!("{}{}", CONDITIONAL_PREFIX, "/optional_get.lox")
+    );
+
+    generate_integration_test!(
+        test_ternary_missing_colon,
+        &format!("{}{}", CONDITIONAL_PREFIX, "/ternary_missing_colon.lox")
+    );
+
+    generate_integration_test!(
+        test_ternary_nested,
+        &format!("{}{}", CONDITIONAL_PREFIX, "/ternary_nested.lox")
+    );
+
+    generate_integration_test!(
+        test_ternary,
+        &format!("{}{}", CONDITIONAL_PREFIX, "/ternary.lox")
+    );
+}
+
+mod const_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const CONST_PREFIX: &str = "tests/resources/integration_tests/const";
+
+    generate_integration_test!(
+        test_assign_before_declaration,
+        &format!("{}{}", CONST_PREFIX, "/assign_before_declaration.lox")
+    );
+
+    generate_integration_test!(
+        test_assign_global,
+        &format!("{}{}", CONST_PREFIX, "/assign_global.lox")
+    );
+
+    generate_integration_test!(
+        test_assign_local,
+        &format!("{}{}", CONST_PREFIX, "/assign_local.lox")
+    );
+
+    generate_integration_test!(test_closure, &format!("{}{}", CONST_PREFIX, "/closure.lox"));
+
+    generate_integration_test!(test_declare, &format!("{}{}", CONST_PREFIX, "/declare.lox"));
+
+    generate_integration_test!(
+        test_increment,
+        &format!("{}{}", CONST_PREFIX, "/increment.lox")
+    );
+
+    generate_integration_test!(
+        test_missing_initializer,
+        &format!("{}{}", CONST_PREFIX, "/missing_initializer.lox")
+    );
+
+    generate_integration_test!(
+        test_redeclare,
+        &format!("{}{}", CONST_PREFIX, "/redeclare.lox")
+    );
 }
 
 mod constructor_tests {
@@ -257,6 +362,50 @@ mod for_tests {
         &format!("{}{}", FOR_PREFIX, "/fun_in_body.lox")
     );
 
+    generate_integration_test!(test_in_class, &format!("{}{}", FOR_PREFIX, "/in_class.lox"));
+
+    generate_integration_test!(
+        test_in_closure,
+        &format!("{}{}", FOR_PREFIX, "/in_closure.lox")
+    );
+
+    generate_integration_test!(
+        test_in_generator,
+        &format!("{}{}", FOR_PREFIX, "/in_generator.lox")
+    );
+
+    generate_integration_test!(
+        test_in_iterator,
+        &format!("{}{}", FOR_PREFIX, "/in_iterator.lox")
+    );
+
+    generate_integration_test!(test_in_list, &format!("{}{}", FOR_PREFIX, "/in_list.lox"));
+
+    generate_integration_test!(
+        test_in_missing_paren,
+        &format!("{}{}", FOR_PREFIX, "/in_missing_paren.lox")
+    );
+
+    generate_integration_test!(
+        test_in_nested,
+        &format!("{}{}", FOR_PREFIX, "/in_nested.lox")
+    );
+
+    generate_integration_test!(
+        test_in_no_iterator_method,
+        &format!("{}{}", FOR_PREFIX, "/in_no_iterator_method.lox")
+    );
+
+    generate_integration_test!(
+        test_in_not_iterable,
+        &format!("{}{}", FOR_PREFIX, "/in_not_iterable.lox")
+    );
+
+    generate_integration_test!(
+        test_in_string,
+        &format!("{}{}", FOR_PREFIX, "/in_string.lox")
+    );
+
     generate_integration_test!(
         test_return_closure,
         &format!("{}{}", FOR_PREFIX, "/return_closure.lox")
@@ -463,6 +612,250 @@ mod function_tests {
     );
 }
 
+mod generator_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const GENERATOR_PREFIX: &str = "tests/resources/integration_tests/generator";
+
+    generate_integration_test!(
+        test_already_running,
+        &format!("{}{}", GENERATOR_PREFIX, "/already_running.lox")
+    );
+
+    generate_integration_test!(
+        test_at_top_level,
+        &format!("{}{}", GENERATOR_PREFIX, "/at_top_level.lox")
+    );
+
+    generate_integration_test!(test_basic, &format!("{}{}", GENERATOR_PREFIX, "/basic.lox"));
+
+    generate_integration_test!(
+        test_closure,
+        &format!("{}{}", GENERATOR_PREFIX, "/closure.lox")
+    );
+
+    generate_integration_test!(
+        test_error_propagates,
+        &format!("{}{}", GENERATOR_PREFIX, "/error_propagates.lox")
+    );
+
+    generate_integration_test!(
+        test_has_next,
+        &format!("{}{}", GENERATOR_PREFIX, "/has_next.lox")
+    );
+
+    generate_integration_test!(
+        test_in_initializer,
+        &format!("{}{}", GENERATOR_PREFIX, "/in_initializer.lox")
+    );
+
+    generate_integration_test!(
+        test_method,
+        &format!("{}{}", GENERATOR_PREFIX, "/method.lox")
+    );
+
+    generate_integration_test!(
+        test_nested,
+        &format!("{}{}", GENERATOR_PREFIX, "/nested.lox")
+    );
+
+    generate_integration_test!(
+        test_return,
+        &format!("{}{}", GENERATOR_PREFIX, "/return.lox")
+    );
+
+    generate_integration_test!(
+        test_unknown_method,
+        &format!("{}{}", GENERATOR_PREFIX, "/unknown_method.lox")
+    );
+}
+
+mod event_loop_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const EVENT_LOOP_PREFIX: &str = "tests/resources/integration_tests/event_loop";
+
+    generate_integration_test!(
+        test_clear_timer,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/clear_timer.lox")
+    );
+
+    generate_integration_test!(
+        test_fiber_error,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/fiber_error.lox")
+    );
+
+    generate_integration_test!(
+        test_negative_delay,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/negative_delay.lox")
+    );
+
+    generate_integration_test!(
+        test_not_a_function,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/not_a_function.lox")
+    );
+
+    generate_integration_test!(
+        test_set_interval,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/set_interval.lox")
+    );
+
+    generate_integration_test!(
+        test_set_timeout,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/set_timeout.lox")
+    );
+
+    generate_integration_test!(
+        test_sleep,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/sleep.lox")
+    );
+
+    generate_integration_test!(
+        test_sleep_in_loop,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/sleep_in_loop.lox")
+    );
+
+    generate_integration_test!(
+        test_sleep_outside_fiber,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/sleep_outside_fiber.lox")
+    );
+
+    generate_integration_test!(
+        test_sleep_in_nested_call,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/sleep_in_nested_call.lox")
+    );
+
+    generate_integration_test!(
+        test_sleep_as_expression,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/sleep_as_expression.lox")
+    );
+
+    generate_integration_test!(
+        test_spawn,
+        &format!("{}{}", EVENT_LOOP_PREFIX, "/spawn.lox")
+    );
+}
+
+mod file_system_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const FILE_SYSTEM_PREFIX: &str = "tests/resources/integration_tests/file_system";
+
+    generate_integration_test!(
+        test_arity,
+        &format!("{}{}", FILE_SYSTEM_PREFIX, "/arity.lox")
+    );
+
+    generate_integration_test!(
+        test_content_not_string,
+        &format!("{}{}", FILE_SYSTEM_PREFIX, "/content_not_string.lox")
+    );
+
+    generate_integration_test!(
+        test_exists_denied,
+        &format!("{}{}", FILE_SYSTEM_PREFIX, "/exists_denied.lox")
+    );
+
+    generate_integration_test!(
+        test_path_not_string,
+        &format!("{}{}", FILE_SYSTEM_PREFIX, "/path_not_string.lox")
+    );
+
+    generate_integration_test!(
+        test_read_denied,
+        &format!("{}{}", FILE_SYSTEM_PREFIX, "/read_denied.lox")
+    );
+
+    generate_integration_test!(
+        test_write_denied,
+        &format!("{}{}", FILE_SYSTEM_PREFIX, "/write_denied.lox")
+    );
+}
+
+mod system_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const SYSTEM_PREFIX: &str = "tests/resources/integration_tests/system";
+
+    generate_integration_test!(test_args, &format!("{}{}", SYSTEM_PREFIX, "/args.lox"));
+
+    generate_integration_test!(test_exit, &format!("{}{}", SYSTEM_PREFIX, "/exit.lox"));
+
+    generate_integration_test!(
+        test_exit_bad_code,
+        &format!("{}{}", SYSTEM_PREFIX, "/exit_bad_code.lox")
+    );
+
+    generate_integration_test!(
+        test_exit_in_fiber,
+        &format!("{}{}", SYSTEM_PREFIX, "/exit_in_fiber.lox")
+    );
+
+    generate_integration_test!(
+        test_getenv_denied,
+        &format!("{}{}", SYSTEM_PREFIX, "/getenv_denied.lox")
+    );
+
+    generate_integration_test!(
+        test_getenv_not_string,
+        &format!("{}{}", SYSTEM_PREFIX, "/getenv_not_string.lox")
+    );
+
+    generate_integration_test!(
+        test_read_line_eof,
+        &format!("{}{}", SYSTEM_PREFIX, "/read_line_eof.lox")
+    );
+
+    generate_integration_test!(
+        test_shebang,
+        &format!("{}{}", SYSTEM_PREFIX, "/shebang.lox")
+    );
+}
+
+mod match_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const MATCH_PREFIX: &str = "tests/resources/integration_tests/match";
+
+    generate_integration_test!(
+        test_binding_with_several_patterns,
+        &format!("{}{}", MATCH_PREFIX, "/binding_with_several_patterns.lox")
+    );
+
+    generate_integration_test!(test_class, &format!("{}{}", MATCH_PREFIX, "/class.lox"));
+
+    generate_integration_test!(
+        test_default_not_last,
+        &format!("{}{}", MATCH_PREFIX, "/default_not_last.lox")
+    );
+
+    generate_integration_test!(test_guard, &format!("{}{}", MATCH_PREFIX, "/guard.lox"));
+
+    generate_integration_test!(
+        test_invalid_pattern,
+        &format!("{}{}", MATCH_PREFIX, "/invalid_pattern.lox")
+    );
+
+    generate_integration_test!(test_literal, &format!("{}{}", MATCH_PREFIX, "/literal.lox"));
+
+    generate_integration_test!(
+        test_missing_arrow,
+        &format!("{}{}", MATCH_PREFIX, "/missing_arrow.lox")
+    );
+
+    generate_integration_test!(
+        test_not_a_class,
+        &format!("{}{}", MATCH_PREFIX, "/not_a_class.lox")
+    );
+
+    generate_integration_test!(
+        test_same_name_class,
+        &format!("{}{}", MATCH_PREFIX, "/same_name_class.lox")
+    );
+
+    generate_integration_test!(test_scope, &format!("{}{}", MATCH_PREFIX, "/scope.lox"));
+}
+
 mod method_tests {
     use crate::common::common::run_test;
     use crate::generate_integration_test;
@@ -500,6 +893,16 @@ mod method_tests {
         &format!("{}{}", METHOD_PREFIX, "/refer_to_name.lox")
     );
 
+    generate_integration_test!(
+        test_sets_field,
+        &format!("{}{}", METHOD_PREFIX, "/sets_field.lox")
+    );
+
+    generate_integration_test!(
+        test_bound_method_sets_field,
+        &format!("{}{}", METHOD_PREFIX, "/bound_method_sets_field.lox")
+    );
+
     generate_integration_test!(
         test_too_many_arguments,
         &format!("{}{}", METHOD_PREFIX, "/too_many_arguments.lox")
@@ -572,6 +975,21 @@ mod operator_tests {
 
     generate_integration_test!(test_add, &format!("{}{}", OPERATOR_PREFIX, "/add.lox"));
 
+    generate_integration_test!(
+        test_bitwise_nonint,
+        &format!("{}{}", OPERATOR_PREFIX, "/bitwise_nonint.lox")
+    );
+
+    generate_integration_test!(
+        test_bitwise_not_nonint,
+        &format!("{}{}", OPERATOR_PREFIX, "/bitwise_not_nonint.lox")
+    );
+
+    generate_integration_test!(
+        test_bitwise,
+        &format!("{}{}", OPERATOR_PREFIX, "/bitwise.lox")
+    );
+
     generate_integration_test!(
         test_comparison,
         &format!("{}{}", OPERATOR_PREFIX, "/comparison.lox")
@@ -647,6 +1065,16 @@ mod operator_tests {
         &format!("{}{}", OPERATOR_PREFIX, "/less_or_equal_num_nonnum.lox")
     );
 
+    generate_integration_test!(
+        test_modulo_nonnum_num,
+        &format!("{}{}", OPERATOR_PREFIX, "/modulo_nonnum_num.lox")
+    );
+
+    generate_integration_test!(
+        test_modulo,
+        &format!("{}{}", OPERATOR_PREFIX, "/modulo.lox")
+    );
+
     generate_integration_test!(
         test_multiply_nonnum_num,
         &format!("{}{}", OPERATOR_PREFIX, "/multiply_nonnum_num.lox")
@@ -684,6 +1112,13 @@ mod operator_tests {
 
     generate_integration_test!(test_not, &format!("{}{}", OPERATOR_PREFIX, "/not.lox"));
 
+    generate_integration_test!(test_power, &format!("{}{}", OPERATOR_PREFIX, "/power.lox"));
+
+    generate_integration_test!(
+        test_shift_out_of_range,
+        &format!("{}{}", OPERATOR_PREFIX, "/shift_out_of_range.lox")
+    );
+
     generate_integration_test!(
         test_subtract_nonnum_num,
         &format!("{}{}", OPERATOR_PREFIX, "/subtract_nonnum_num.lox")
@@ -700,6 +1135,86 @@ mod operator_tests {
     );
 }
 
+mod parameters_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const PARAMETERS_PREFIX: &str = "tests/resources/integration_tests/parameters";
+
+    generate_integration_test!(
+        test_arity,
+        &format!("{}{}", PARAMETERS_PREFIX, "/arity.lox")
+    );
+
+    generate_integration_test!(
+        test_arity_rest,
+        &format!("{}{}", PARAMETERS_PREFIX, "/arity_rest.lox")
+    );
+
+    generate_integration_test!(
+        test_default,
+        &format!("{}{}", PARAMETERS_PREFIX, "/default.lox")
+    );
+
+    generate_integration_test!(
+        test_default_before_required,
+        &format!("{}{}", PARAMETERS_PREFIX, "/default_before_required.lox")
+    );
+
+    generate_integration_test!(
+        test_index,
+        &format!("{}{}", PARAMETERS_PREFIX, "/index.lox")
+    );
+
+    generate_integration_test!(
+        test_index_invalid_object,
+        &format!("{}{}", PARAMETERS_PREFIX, "/index_invalid_object.lox")
+    );
+
+    generate_integration_test!(
+        test_index_not_integer,
+        &format!("{}{}", PARAMETERS_PREFIX, "/index_not_integer.lox")
+    );
+
+    generate_integration_test!(test_init, &format!("{}{}", PARAMETERS_PREFIX, "/init.lox"));
+
+    generate_integration_test!(
+        test_named,
+        &format!("{}{}", PARAMETERS_PREFIX, "/named.lox")
+    );
+
+    generate_integration_test!(
+        test_named_missing,
+        &format!("{}{}", PARAMETERS_PREFIX, "/named_missing.lox")
+    );
+
+    generate_integration_test!(
+        test_named_native,
+        &format!("{}{}", PARAMETERS_PREFIX, "/named_native.lox")
+    );
+
+    generate_integration_test!(
+        test_named_twice,
+        &format!("{}{}", PARAMETERS_PREFIX, "/named_twice.lox")
+    );
+
+    generate_integration_test!(
+        test_named_unknown,
+        &format!("{}{}", PARAMETERS_PREFIX, "/named_unknown.lox")
+    );
+
+    generate_integration_test!(
+        test_positional_after_named,
+        &format!("{}{}", PARAMETERS_PREFIX, "/positional_after_named.lox")
+    );
+
+    generate_integration_test!(test_rest, &format!("{}{}", PARAMETERS_PREFIX, "/rest.lox"));
+
+    generate_integration_test!(
+        test_rest_not_last,
+        &format!("{}{}", PARAMETERS_PREFIX, "/rest_not_last.lox")
+    );
+}
+
 mod print_tests {
     use crate::common::common::run_test;
     use crate::generate_integration_test;
@@ -788,17 +1303,31 @@ mod string_tests {
         &format!("{}{}", STRING_PREFIX, "/error_after_multiline.lox")
     );
 
-    // TODO: add support for UNICODE.
-    // generate_integration_test!(
-    //     test_literals,
-    //     &format!("{}{}", STRING_PREFIX, "/literals.lox")
-    // );
+    generate_integration_test!(
+        test_escapes,
+        &format!("{}{}", STRING_PREFIX, "/escapes.lox")
+    );
+
+    generate_integration_test!(
+        test_interpolation,
+        &format!("{}{}", STRING_PREFIX, "/interpolation.lox")
+    );
+
+    generate_integration_test!(
+        test_literals,
+        &format!("{}{}", STRING_PREFIX, "/literals.lox")
+    );
 
     generate_integration_test!(
         test_multiline,
         &format!("{}{}", STRING_PREFIX, "/multiline.lox")
     );
 
+    generate_integration_test!(
+        test_unclosed_interpolation,
+        &format!("{}{}", STRING_PREFIX, "/unclosed_interpolation.lox")
+    );
+
     // TODO: support for SCAN errors in mod.rs
     // generate_integration_test!(
     //     test_unterminated,
@@ -940,6 +1469,82 @@ mod this_tests {
     );
 }
 
+mod trait_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const TRAIT_PREFIX: &str = "tests/resources/integration_tests/trait";
+
+    generate_integration_test!(
+        test_call_trait,
+        &format!("{}{}", TRAIT_PREFIX, "/call_trait.lox")
+    );
+
+    generate_integration_test!(
+        test_conflict,
+        &format!("{}{}", TRAIT_PREFIX, "/conflict.lox")
+    );
+
+    generate_integration_test!(
+        test_initializer,
+        &format!("{}{}", TRAIT_PREFIX, "/initializer.lox")
+    );
+
+    generate_integration_test!(
+        test_missing_trait_name,
+        &format!("{}{}", TRAIT_PREFIX, "/missing_trait_name.lox")
+    );
+
+    generate_integration_test!(test_mixin, &format!("{}{}", TRAIT_PREFIX, "/mixin.lox"));
+
+    generate_integration_test!(
+        test_not_a_trait,
+        &format!("{}{}", TRAIT_PREFIX, "/not_a_trait.lox")
+    );
+
+    generate_integration_test!(
+        test_override,
+        &format!("{}{}", TRAIT_PREFIX, "/override.lox")
+    );
+
+    generate_integration_test!(test_super, &format!("{}{}", TRAIT_PREFIX, "/super.lox"));
+
+    generate_integration_test!(
+        test_super_without_superclass,
+        &format!("{}{}", TRAIT_PREFIX, "/super_without_superclass.lox")
+    );
+
+    generate_integration_test!(
+        test_this_in_nested_function,
+        &format!("{}{}", TRAIT_PREFIX, "/this_in_nested_function.lox")
+    );
+
+    generate_integration_test!(
+        test_use_itself,
+        &format!("{}{}", TRAIT_PREFIX, "/use_itself.lox")
+    );
+
+    generate_integration_test!(
+        test_use_twice,
+        &format!("{}{}", TRAIT_PREFIX, "/use_twice.lox")
+    );
+}
+
+mod types_tests {
+    use crate::common::common::run_test;
+    use crate::generate_integration_test;
+    pub const TYPES_PREFIX: &str = "tests/resources/integration_tests/types";
+
+    generate_integration_test!(
+        test_annotations,
+        &format!("{}{}", TYPES_PREFIX, "/annotations.lox")
+    );
+
+    generate_integration_test!(
+        test_missing_type,
+        &format!("{}{}", TYPES_PREFIX, "/missing_type.lox")
+    );
+}
+
 mod variable_tests {
     use crate::common::common::run_test;
     use crate::generate_integration_test;
@@ -1095,3 +1700,39 @@ mod while_tests {
         &format!("{}{}", WHILE_PREFIX, "/var_in_body.lox")
     );
 }
+
+mod cli_tests {
+    use crate::common::common::run_cli_test;
+    use crate::generate_cli_test;
+    pub const INTEGRATION_PREFIX: &str = "tests/resources/integration_tests";
+
+    generate_cli_test!(
+        test_parse_error,
+        &format!("{}{}", INTEGRATION_PREFIX, "/variable/use_false_as_var.lox")
+    );
+
+    generate_cli_test!(
+        test_resolve_error,
+        &format!("{}{}", INTEGRATION_PREFIX, "/return/at_top_level.lox")
+    );
+
+    generate_cli_test!(
+        test_runtime_error,
+        &format!("{}{}", INTEGRATION_PREFIX, "/operator/add_bool_nil.lox")
+    );
+
+    generate_cli_test!(
+        test_sandbox_error,
+        &format!("{}{}", INTEGRATION_PREFIX, "/file_system/read_denied.lox")
+    );
+
+    generate_cli_test!(
+        test_event_loop,
+        &format!("{}{}", INTEGRATION_PREFIX, "/event_loop/spawn.lox")
+    );
+
+    generate_cli_test!(
+        test_exit,
+        &format!("{}{}", INTEGRATION_PREFIX, "/system/exit.lox")
+    );
+}